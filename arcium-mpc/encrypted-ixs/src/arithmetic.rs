@@ -0,0 +1,348 @@
+//! Plain arithmetic shared by the circuits and the `simulation` mirrors.
+//!
+//! `#[encrypted]` only sees the tokens it's handed and won't expand macro
+//! calls inside its module, so the shared functions are spliced in from the
+//! outside: `shared_arithmetic!` takes a module and re-emits it with the
+//! functions below appended. They inherit the module's visibility, which keeps
+//! them private helpers in `circuits` and public in `simulation`.
+//!
+//! Everything here has to stay within what the circuit compiler accepts:
+//! scalar and fixed-size inputs, `if`/`else` instead of early returns, and no
+//! iterator or `std` helpers.
+
+macro_rules! shared_arithmetic {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident { $($body:tt)* }) => {
+        $(#[$attr])*
+        $vis mod $name {
+            $($body)*
+
+            /// Health as collateral over debt, in bps. Debt-free positions
+            /// read as exactly 100%.
+            $vis fn health_ratio_bps(collateral_usd: u64, debt_usd: u64) -> u64 {
+                if debt_usd > 0 {
+                    (collateral_usd * 10000) / debt_usd
+                } else {
+                    10000
+                }
+            }
+
+            /// Leverage as debt over collateral, in bps. Debt-free positions
+            /// read as exactly 1x; debt with no collateral left saturates.
+            $vis fn leverage_ratio_bps(collateral_usd: u64, debt_usd: u64) -> u64 {
+                if debt_usd == 0 {
+                    10000
+                } else if collateral_usd == 0 {
+                    u64::MAX
+                } else {
+                    (debt_usd * 10000) / collateral_usd
+                }
+            }
+
+            /// Health implied by the market price: how far it sits from the
+            /// liquidation price, 100% at liquidation. Longs gain as the price
+            /// rises and shorts as it falls. Floored at 1 bps so it can divide;
+            /// zero prices are read as 1.
+            $vis fn priced_health_bps(oracle_price: u64, liquidation_price: u64, is_short: bool) -> u64 {
+                let price = if oracle_price > 0 { oracle_price } else { 1 };
+                let liquidation = if liquidation_price > 0 { liquidation_price } else { 1 };
+                let health = if is_short {
+                    (liquidation * 10000) / price
+                } else {
+                    (price * 10000) / liquidation
+                };
+
+                if health > 0 { health } else { 1 }
+            }
+
+            /// Health left after a price move of `price_impact_bps`, floored at 0.
+            $vis fn impacted_health(health_factor_bps: u64, price_impact_bps: u64) -> u64 {
+                if health_factor_bps > price_impact_bps {
+                    health_factor_bps - price_impact_bps
+                } else {
+                    0
+                }
+            }
+
+            /// Maps an adjusted health factor onto risk levels 0 (safe) through 4
+            /// (liquidatable). Level `4 - n` starts at `10000 + n * band_width_bps`.
+            $vis fn risk_band(adjusted_health: u64, band_width_bps: u64) -> u8 {
+                let floor: u64 = 10000;
+
+                if adjusted_health >= floor + 4 * band_width_bps {
+                    0
+                } else if adjusted_health >= floor + 3 * band_width_bps {
+                    1
+                } else if adjusted_health >= floor + 2 * band_width_bps {
+                    2
+                } else if adjusted_health >= floor + band_width_bps {
+                    3
+                } else {
+                    4
+                }
+            }
+
+            /// Whether health, less the safety buffer, clears the threshold.
+            /// Once triggered, health has to clear threshold + hysteresis to
+            /// reset; otherwise it has to fall below threshold - hysteresis to
+            /// trigger.
+            $vis fn clears_health_threshold(
+                health_factor_bps: u64,
+                threshold_bps: u64,
+                hysteresis_bps: u64,
+                was_triggered: bool,
+                safety_buffer_bps: u64,
+            ) -> bool {
+                let buffered_health = impacted_health(health_factor_bps, safety_buffer_bps);
+
+                let trigger_below = if was_triggered {
+                    threshold_bps + hysteresis_bps
+                } else if threshold_bps > hysteresis_bps {
+                    threshold_bps - hysteresis_bps
+                } else {
+                    0
+                };
+
+                buffered_health >= trigger_below
+            }
+
+            /// Merges every `reveal_precision` adjacent buckets into one, so the same
+            /// config value coarsens all bucketed reveals alike. 1 keeps full precision.
+            $vis fn coarsen_bucket(bucket: u8, reveal_precision: u8) -> u8 {
+                bucket / reveal_precision
+            }
+
+            /// A buy up to `price_tolerance_bps` below the sell still crosses, so
+            /// rounding in submitted limit prices doesn't block equivalent orders.
+            $vis fn prices_cross(buy_price: u64, sell_price: u64, price_tolerance_bps: u64) -> bool {
+                buy_price * 10000 >= sell_price * (10000 - price_tolerance_bps)
+            }
+
+            /// Reported on a failed match: 0 = matched, 1 = orders don't cross,
+            /// 2 = self-trade, 3 = fill-or-kill. A self-trade is reported even
+            /// when the orders also fail to cross.
+            $vis fn match_failure_reason(is_crossable: bool, is_self_trade: bool, fok_blocked: bool) -> u8 {
+                if is_crossable && !is_self_trade && !fok_blocked {
+                    0
+                } else if is_self_trade {
+                    2
+                } else if !is_crossable {
+                    1
+                } else {
+                    3
+                }
+            }
+
+            /// Midpoint of two limit prices. An odd sum falls between two ticks and
+            /// `round_toward` picks one: 0 = buyer (round down, the default),
+            /// 1 = seller (round up), 2 = nearest, with ties going to the even tick
+            /// so neither side is favored on average.
+            $vis fn midpoint_price(buy_price: u64, sell_price: u64, round_toward: u8) -> u64 {
+                let sum = buy_price + sell_price;
+                let down = sum / 2;
+
+                let round_up = if sum % 2 == 0 {
+                    false
+                } else if round_toward == 1 {
+                    true
+                } else if round_toward == 2 {
+                    down % 2 == 1
+                } else {
+                    false
+                };
+
+                if round_up { down + 1 } else { down }
+            }
+
+            /// Whether the taker does at least as well at `execution_price` as at
+            /// the external `reference_price`.
+            $vis fn beats_reference_price(execution_price: u64, reference_price: u64, taker_is_buy: bool) -> bool {
+                if taker_is_buy {
+                    execution_price <= reference_price
+                } else {
+                    execution_price >= reference_price
+                }
+            }
+
+            /// Rebate owed to the maker on the filled notional. The notional is
+            /// taken in u128 and split around the bps division so large fills
+            /// don't overflow; the rebate saturates at u64.
+            $vis fn maker_rebate(execution_price: u64, fill_amount: u64, maker_rebate_bps: u64) -> u64 {
+                let notional = execution_price as u128 * fill_amount as u128;
+                let bps = maker_rebate_bps as u128;
+                let rebate = (notional / 10000) * bps + ((notional % 10000) * bps) / 10000;
+
+                if rebate > u64::MAX as u128 { u64::MAX } else { rebate as u64 }
+            }
+
+            /// The part of an accrued rebate that can be paid out: whole multiples
+            /// of `quantum`, so the payout doesn't reveal the fill's notional.
+            $vis fn releasable_rebate(accrued_rebate: u64, quantum: u64) -> u64 {
+                if quantum > 0 {
+                    (accrued_rebate / quantum) * quantum
+                } else {
+                    accrued_rebate
+                }
+            }
+
+            /// What `match_book` ranks a crossing taker on.
+            $vis struct BookRank {
+                $vis limit_price: u64,
+                /// Displayed size, so iceberg reserves stay out of the ranking.
+                $vis visible_amount: u64,
+                $vis sequence: u64,
+                $vis priority_fee: u64,
+            }
+
+            /// Whether `candidate` should replace `best` as the taker that fills
+            /// a resting order. Size priority prefers the larger displayed
+            /// size; otherwise the better price wins (the lower one when
+            /// `lower_price_wins`), then the higher priority fee. Ties go to the
+            /// earlier order.
+            $vis fn book_outranks(
+                candidate: &BookRank,
+                best: &BookRank,
+                size_priority: bool,
+                lower_price_wins: bool,
+            ) -> bool {
+                let earlier = candidate.sequence < best.sequence;
+                let better_price = if lower_price_wins {
+                    candidate.limit_price < best.limit_price
+                } else {
+                    candidate.limit_price > best.limit_price
+                };
+                let same_price = candidate.limit_price == best.limit_price;
+                let higher_fee = candidate.priority_fee > best.priority_fee;
+                let same_fee = candidate.priority_fee == best.priority_fee;
+
+                if size_priority {
+                    candidate.visible_amount > best.visible_amount
+                        || (candidate.visible_amount == best.visible_amount && earlier)
+                } else {
+                    better_price || (same_price && (higher_fee || (same_fee && earlier)))
+                }
+            }
+
+            /// 0: <90%, 1: 90-100%, 2: 100-110%, 3: 110-125%, 4: 125-150%, 5: >=150%
+            $vis fn solvency_bucket(total_collateral: u64, total_debt: u64) -> u8 {
+                let ratio_bps = if total_debt > 0 {
+                    (total_collateral * 10000) / total_debt
+                } else {
+                    u64::MAX
+                };
+
+                if ratio_bps >= 15000 {
+                    5
+                } else if ratio_bps >= 12500 {
+                    4
+                } else if ratio_bps >= 11000 {
+                    3
+                } else if ratio_bps >= 10000 {
+                    2
+                } else if ratio_bps >= 9000 {
+                    1
+                } else {
+                    0
+                }
+            }
+
+            /// How far a position with debt sits below `threshold_bps`; 0 above it.
+            $vis fn health_shortfall(health_factor_bps: u64, debt_usd: u64, threshold_bps: u64) -> u64 {
+                if health_factor_bps < threshold_bps && debt_usd > 0 {
+                    threshold_bps - health_factor_bps
+                } else {
+                    0
+                }
+            }
+
+            /// Buckets a summed shortfall against the ten-position worst case:
+            /// 0 = nothing at risk, 4 = at least 75% of the worst case.
+            $vis fn weighted_risk_bucket(score: u64, threshold_bps: u64) -> u8 {
+                let max_score = threshold_bps * 10;
+
+                if score == 0 {
+                    0
+                } else if score * 4 < max_score {
+                    1
+                } else if score * 2 < max_score {
+                    2
+                } else if score * 4 < max_score * 3 {
+                    3
+                } else {
+                    4
+                }
+            }
+
+            /// Order-of-magnitude bucket of a USD amount: 0 = none, 1 = under 1k,
+            /// 2 = under 10k, 3 = under 100k, 4 = under 1M, 5 = 1M and above.
+            $vis fn magnitude_bucket(amount_usd: u64) -> u8 {
+                if amount_usd == 0 {
+                    0
+                } else if amount_usd < 1_000 {
+                    1
+                } else if amount_usd < 10_000 {
+                    2
+                } else if amount_usd < 100_000 {
+                    3
+                } else if amount_usd < 1_000_000 {
+                    4
+                } else {
+                    5
+                }
+            }
+
+            /// Most debt a liquidator may repay, as a `magnitude_bucket`.
+            $vis fn repay_bucket(debt_usd: u64, close_factor_bps: u64) -> u8 {
+                magnitude_bucket((debt_usd * close_factor_bps) / 10000)
+            }
+
+            /// Collateral to add so that, after the price moves from
+            /// `current_price` to `projected_price`, health stays at
+            /// `margin_call_health_bps`; 0 when it already would.
+            $vis fn margin_call_buffer(
+                collateral_usd: u64,
+                debt_usd: u64,
+                current_price: u64,
+                projected_price: u64,
+                margin_call_health_bps: u64,
+            ) -> u64 {
+                let projected_collateral = (collateral_usd * projected_price) / current_price;
+                let required_collateral = (debt_usd * margin_call_health_bps) / 10000;
+
+                if required_collateral > projected_collateral {
+                    required_collateral - projected_collateral
+                } else {
+                    0
+                }
+            }
+
+            /// Age range of a position: 0 = under a day, 1 = under a week,
+            /// 2 = under 30 days, 3 = older.
+            $vis fn age_bucket(age_seconds: i64) -> u8 {
+                if age_seconds < 86_400 {
+                    0
+                } else if age_seconds < 604_800 {
+                    1
+                } else if age_seconds < 2_592_000 {
+                    2
+                } else {
+                    3
+                }
+            }
+
+            /// Maps health onto a fee tier from 0 (healthiest, cheapest) to 3.
+            /// `thresholds_bps` are descending; tier `n` is the first threshold the
+            /// health meets. Debt-free positions are tier 0.
+            $vis fn fee_tier(health_factor_bps: u64, debt_usd: u64, thresholds_bps: [u64; 3]) -> u8 {
+                if debt_usd == 0 || health_factor_bps >= thresholds_bps[0] {
+                    0
+                } else if health_factor_bps >= thresholds_bps[1] {
+                    1
+                } else if health_factor_bps >= thresholds_bps[2] {
+                    2
+                } else {
+                    3
+                }
+            }
+        }
+    };
+}
@@ -1,5 +1,12 @@
 use arcis_imports::*;
 
+#[macro_use]
+mod arithmetic;
+
+#[cfg(feature = "simulation")]
+pub mod simulation;
+
+shared_arithmetic! {
 #[encrypted]
 mod circuits {
     use arcis_imports::*;
@@ -12,15 +19,108 @@ mod circuits {
         liquidation_price: u64,
         protocol_id: u8,
         last_updated: i64,
+        liquidation_count: u32,
+        /// When `record_liquidation` last ran; 0 if never liquidated.
+        last_liquidation_at: i64,
+        /// Layout version. Any change to this struct bumps it, along with
+        /// `CURRENT_POSITION_SCHEMA_VERSION`, and adds a migration.
+        schema_version: u8,
+        created_at: i64,
+        collateral_mint: [u8; 32],
+        debt_mint: [u8; 32],
+        /// Last eight health readings, oldest overwritten first; capped at
+        /// `u16::MAX` bps. Only the first `health_sample_count` slots hold
+        /// readings.
+        health_samples: [u16; 8],
+        /// Slot the next health reading is written to.
+        health_sample_cursor: u8,
+        /// Readings taken so far, capped at 8.
+        health_sample_count: u8,
+        /// 0 = long, 1 = short. Set by the owner through `update_position_data`.
+        direction: u8,
+        /// Exponential moving average of health; meaningless until
+        /// `ema_seeded`.
+        ema_health_bps: u64,
+        /// Set by the first reading folded into the EMA. A real reading can
+        /// be 0, so the average itself can't mark this.
+        ema_seeded: bool,
+        /// Price the position was opened at, set by the owner through
+        /// `update_position_data`. 0 means no cost basis is known.
+        entry_price: u64,
+    }
+
+    /// Layout written before `liquidation_count` and `schema_version` existed.
+    pub struct EncryptedPositionV0 {
+        collateral_usd: u64,
+        debt_usd: u64,
+        health_factor_bps: u64,
+        leverage_bps: u64,
+        liquidation_price: u64,
+        protocol_id: u8,
+        last_updated: i64,
+    }
+
+    /// Schema version 1: the layout before liquidation times, mints, health
+    /// samples, direction and the EMA were added.
+    pub struct EncryptedPositionV1 {
+        collateral_usd: u64,
+        debt_usd: u64,
+        health_factor_bps: u64,
+        leverage_bps: u64,
+        liquidation_price: u64,
+        protocol_id: u8,
+        last_updated: i64,
+        liquidation_count: u32,
+        schema_version: u8,
+    }
+
+    /// Schema version 2: the layout before `entry_price` was added.
+    pub struct EncryptedPositionV2 {
+        collateral_usd: u64,
+        debt_usd: u64,
+        health_factor_bps: u64,
+        leverage_bps: u64,
+        liquidation_price: u64,
+        protocol_id: u8,
+        last_updated: i64,
+        liquidation_count: u32,
+        last_liquidation_at: i64,
+        schema_version: u8,
+        created_at: i64,
+        collateral_mint: [u8; 32],
+        debt_mint: [u8; 32],
+        health_samples: [u16; 8],
+        health_sample_cursor: u8,
+        health_sample_count: u8,
+        direction: u8,
+        ema_health_bps: u64,
+        ema_seeded: bool,
     }
 
     pub struct DarkPoolOrder {
         side: u8,
         token_mint: [u8; 32],
         amount: u64,
+        /// Iceberg slice shown to depth calculations; 0 shows the full amount.
+        display_amount: u64,
         limit_price: u64,
         min_fill_amount: u64,
         expires_at: i64,
+        stop_price: u64,
+        stop_triggered: bool,
+        owner_key: [u8; 32],
+        convert_on_expiry: bool,
+        expiry_slippage_bps: u64,
+        created_at: i64,
+        /// 0 = good-til-cancelled, 1 = immediate-or-cancel, 2 = fill-or-kill.
+        tif: u8,
+        /// Position whose health gates a conditional order; unused otherwise.
+        trigger_position_id: [u8; 32],
+        /// The order activates once that position's health drops below this.
+        trigger_health_bps: u64,
+        /// Price of the order's latest match; 0 until it first matches. Only
+        /// the match circuits set it.
+        last_execution_price: u64,
     }
 
     pub struct SwapIntent {
@@ -30,6 +130,9 @@ mod circuits {
         min_amount_out: u64,
         max_slippage_bps: u64,
         deadline: i64,
+        /// `amount_in` consumed by the latest execution; only
+        /// `execute_private_swap` sets it.
+        last_filled_in: u64,
     }
 
     pub struct HealthCheckResult {
@@ -42,6 +145,38 @@ mod circuits {
         is_matched: bool,
         execution_price: u64,
         fill_amount: u64,
+        maker_rebate: u64,
+    }
+
+    pub struct MakerVolume {
+        matched_volume: u64,
+        /// Filled volume in the current rebate window. Only fills add to it,
+        /// so placed or cancelled orders can't inflate a rebate tier.
+        realized_volume: u64,
+        /// Rebate earned but not yet paid out. Only whole quanta are revealed,
+        /// so the remainder carries over to the maker's next fill.
+        accrued_rebate: u64,
+    }
+
+    /// Per-owner order lifecycle counts, for spotting place-and-cancel spoofing.
+    pub struct OrderActivity {
+        placements: u64,
+        cancels: u64,
+        fills: u64,
+    }
+
+    pub struct CollateralLeg {
+        mint: [u8; 32],
+        amount_usd: u64,
+        haircut_bps: u64,
+        is_stable: bool,
+        /// 0 for a clean source; any other value marks a flagged source.
+        source_flag: u8,
+    }
+
+    pub struct MultiCollateralPosition {
+        legs: [CollateralLeg; 4],
+        debt_usd: u64,
     }
 
     #[instruction]
@@ -49,6 +184,7 @@ mod circuits {
         mxe: Mxe,
         position_id: [u8; 32],
         protocol: u8,
+        created_at: i64,
     ) -> Enc<Mxe, EncryptedPosition> {
         let position = EncryptedPosition {
             collateral_usd: 0,
@@ -58,6 +194,19 @@ mod circuits {
             liquidation_price: 0,
             protocol_id: protocol,
             last_updated: 0,
+            liquidation_count: 0,
+            last_liquidation_at: 0,
+            schema_version: 3,
+            created_at,
+            collateral_mint: [0u8; 32],
+            debt_mint: [0u8; 32],
+            health_samples: [0; 8],
+            health_sample_cursor: 0,
+            health_sample_count: 0,
+            direction: 0,
+            ema_health_bps: 0,
+            ema_seeded: false,
+            entry_price: 0,
         };
         mxe.from_arcis(position)
     }
@@ -67,68 +216,180 @@ mod circuits {
         position_ctxt: Enc<Shared, EncryptedPosition>,
         stored_ctxt: Enc<Mxe, EncryptedPosition>,
     ) -> Enc<Mxe, EncryptedPosition> {
-        let position = position_ctxt.to_arcis();
+        let mut position = position_ctxt.to_arcis();
+        let stored = stored_ctxt.to_arcis();
+        
+        // Liquidation history is owned by the MXE; owners can't overwrite it.
+        position.liquidation_count = stored.liquidation_count;
+        position.last_liquidation_at = stored.last_liquidation_at;
+        position.schema_version = stored.schema_version;
+        position.created_at = stored.created_at;
+        position.last_updated = stored.last_updated;
+        position.health_samples = stored.health_samples;
+        position.health_sample_cursor = stored.health_sample_cursor;
+        position.health_sample_count = stored.health_sample_count;
+        position.ema_health_bps = stored.ema_health_bps;
+        position.ema_seeded = stored.ema_seeded;
+        
         stored_ctxt.owner.from_arcis(position)
     }
 
+    fn health_sample(health_factor_bps: u64) -> u16 {
+        if health_factor_bps > 65535 {
+            65535
+        } else {
+            health_factor_bps as u16
+        }
+    }
+
+    /// Writes the current health into the sample ring. The cursor is secret,
+    /// so every slot is visited and only the matching one is written.
+    fn record_health_sample(position: &mut EncryptedPosition) {
+        let sample = health_sample(position.health_factor_bps);
+        
+        let mut i = 0;
+        while i < 8 {
+            if (i as u8) == position.health_sample_cursor {
+                position.health_samples[i] = sample;
+            }
+            i = i + 1;
+        }
+        
+        position.health_sample_cursor = (position.health_sample_cursor + 1) % 8;
+        if position.health_sample_count < 8 {
+            position.health_sample_count = position.health_sample_count + 1;
+        }
+    }
+
+    /// Folds the current health into the EMA, weighting the new reading by
+    /// `ema_smoothing_bps`. The first reading seeds the average directly.
+    fn record_health_ema(position: &mut EncryptedPosition, ema_smoothing_bps: u64) {
+        position.ema_health_bps = if position.ema_seeded {
+            (position.health_factor_bps * ema_smoothing_bps
+                + position.ema_health_bps * (10000 - ema_smoothing_bps))
+                / 10000
+        } else {
+            position.health_factor_bps
+        };
+        position.ema_seeded = true;
+    }
+
+
+    /// Reprices a position at `oracle_price`, the bound feed's price for
+    /// `oracle_mint` in the same units as `liquidation_price`. Longs are priced
+    /// on their collateral and shorts on their debt: health is the distance
+    /// to the liquidation price, 100% at liquidation, and the priced leg is
+    /// rederived from it. Returns whether the feed prices the position's
+    /// asset; nothing is repriced when it doesn't, or when there's no debt.
+    fn reprice_position(position: &mut EncryptedPosition, oracle_price: u64, oracle_mint: [u8; 32]) -> bool {
+        let is_short = position.direction == 1;
+        let priced_mint = if is_short { position.debt_mint } else { position.collateral_mint };
+        let feed_matches = priced_mint == oracle_mint;
+        
+        let health = priced_health_bps(oracle_price, position.liquidation_price, is_short);
+        
+        if feed_matches && position.debt_usd > 0 && position.liquidation_price > 0 {
+            position.health_factor_bps = health;
+            if is_short {
+                position.debt_usd = (position.collateral_usd * 10000) / health;
+            } else {
+                position.collateral_usd = (position.debt_usd * health) / 10000;
+            }
+            position.leverage_bps = leverage_ratio_bps(position.collateral_usd, position.debt_usd);
+        }
+        
+        feed_matches
+    }
 
+    /// Reprices the stored position against the bound feed and records the
+    /// new reading, stamped with the queue-time clock `now`. Also reveals
+    /// whether the feed prices the position's asset; the program discards the
+    /// update when it doesn't.
     #[instruction]
     pub fn update_health_factor(
         stored_ctxt: Enc<Mxe, EncryptedPosition>,
-        new_collateral: u64,
-        new_debt: u64,
-    ) -> Enc<Mxe, EncryptedPosition> {
+        oracle_price: u64,
+        oracle_mint: [u8; 32],
+        ema_smoothing_bps: u64,
+        now: i64,
+    ) -> (Enc<Mxe, EncryptedPosition>, bool) {
         let mut position = stored_ctxt.to_arcis();
         
-        position.collateral_usd = new_collateral;
-        position.debt_usd = new_debt;
-        
-        if new_debt > 0 {
-            position.health_factor_bps = (new_collateral * 10000) / new_debt;
-            position.leverage_bps = (new_debt * 10000) / new_collateral;
-        } else {
-            position.health_factor_bps = 10000;
-            position.leverage_bps = 10000;
-        }
+        let feed_matches = reprice_position(&mut position, oracle_price, oracle_mint);
+        record_health_sample(&mut position);
+        record_health_ema(&mut position, ema_smoothing_bps);
+        position.last_updated = now;
         
-        stored_ctxt.owner.from_arcis(position)
+        (stored_ctxt.owner.from_arcis(position), feed_matches.reveal())
     }
 
     #[instruction]
     pub fn prove_health_threshold(
         position_ctxt: Enc<Mxe, EncryptedPosition>,
         threshold_bps: u64,
+        hysteresis_bps: u64,
+        was_triggered: bool,
+        safety_buffer_bps: u64,
     ) -> bool {
         let position = position_ctxt.to_arcis();
-        (position.health_factor_bps >= threshold_bps).reveal()
+        
+        // The buffer comes off health first, so the proof only passes with margin to spare.
+        clears_health_threshold(
+            position.health_factor_bps,
+            threshold_bps,
+            hysteresis_bps,
+            was_triggered,
+            safety_buffer_bps,
+        )
+        .reveal()
     }
 
+    /// Reveals the risk level coarsened by `reveal_precision`, and whether
+    /// it's the liquidatable level 4, which the liquidation flow needs at
+    /// full precision.
     #[instruction]
     pub fn calculate_liquidation_risk(
         position_ctxt: Enc<Mxe, EncryptedPosition>,
         price_impact_bps: u64,
+        band_width_bps: u64,
+        reveal_precision: u8,
+    ) -> (u8, bool) {
+        let position = position_ctxt.to_arcis();
+        
+        let adjusted_health = impacted_health(position.health_factor_bps, price_impact_bps);
+        
+        let risk_level = risk_band(adjusted_health, band_width_bps);
+        
+        (
+            coarsen_bucket(risk_level, reveal_precision).reveal(),
+            (risk_level == 4).reveal(),
+        )
+    }
+
+    /// Like `calculate_liquidation_risk`, but escalates the level by one for
+    /// every `band_width_bps` of oracle uncertainty, capped at 4, then
+    /// coarsened by `reveal_precision`.
+    #[instruction]
+    pub fn calculate_liquidation_risk_weighted(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        price_impact_bps: u64,
+        band_width_bps: u64,
+        confidence_bps: u64,
+        reveal_precision: u8,
     ) -> u8 {
         let position = position_ctxt.to_arcis();
         
-        let adjusted_health = if position.health_factor_bps > price_impact_bps {
-            position.health_factor_bps - price_impact_bps
-        } else {
-            0
-        };
+        let adjusted_health = impacted_health(position.health_factor_bps, price_impact_bps);
         
-        let risk_level: u8 = if adjusted_health >= 15000 {
-            0
-        } else if adjusted_health >= 12500 {
-            1
-        } else if adjusted_health >= 11000 {
-            2
-        } else if adjusted_health >= 10500 {
-            3
+        let escalation = if band_width_bps > 0 {
+            confidence_bps / band_width_bps
         } else {
-            4
+            0
         };
+        let escalated = risk_band(adjusted_health, band_width_bps) as u64 + escalation;
+        let risk_level: u8 = if escalated > 4 { 4 } else { escalated as u8 };
         
-        risk_level.reveal()
+        coarsen_bucket(risk_level, reveal_precision).reveal()
     }
 
     #[instruction]
@@ -156,14 +417,30 @@ mod circuits {
         order_id: [u8; 32],
         side: u8,
         expires_at: i64,
+        owner_key: [u8; 32],
+        created_at: i64,
+        tif: u8,
+        trigger_position_id: [u8; 32],
+        trigger_health_bps: u64,
     ) -> Enc<Mxe, DarkPoolOrder> {
         let order = DarkPoolOrder {
             side,
             token_mint: [0u8; 32],
             amount: 0,
+            display_amount: 0,
             limit_price: 0,
             min_fill_amount: 0,
             expires_at,
+            stop_price: 0,
+            stop_triggered: false,
+            owner_key,
+            convert_on_expiry: false,
+            expiry_slippage_bps: 0,
+            created_at,
+            tif,
+            trigger_position_id,
+            trigger_health_bps,
+            last_execution_price: 0,
         };
         mxe.from_arcis(order)
     }
@@ -173,43 +450,243 @@ mod circuits {
         order_ctxt: Enc<Shared, DarkPoolOrder>,
         stored_ctxt: Enc<Mxe, DarkPoolOrder>,
     ) -> Enc<Mxe, DarkPoolOrder> {
-        let order = order_ctxt.to_arcis();
+        let mut order = order_ctxt.to_arcis();
+        let stored = stored_ctxt.to_arcis();
+        
+        // The owner witness, creation time, time-in-force and trigger are
+        // fixed at init and can't be rewritten by an update.
+        order.owner_key = stored.owner_key;
+        order.created_at = stored.created_at;
+        order.tif = stored.tif;
+        order.trigger_position_id = stored.trigger_position_id;
+        order.trigger_health_bps = stored.trigger_health_bps;
+        order.last_execution_price = stored.last_execution_price;
+        
+        // Amounts are only known here, so this is where the slice is bounded.
+        if order.display_amount > order.amount {
+            order.display_amount = order.amount;
+        }
+        
         stored_ctxt.owner.from_arcis(order)
     }
 
+    fn orders_cross(buy: &DarkPoolOrder, sell: &DarkPoolOrder, price_tolerance_bps: u64) -> bool {
+        let tokens_match = buy.token_mint == sell.token_mint;
+        let price_compatible = prices_cross(buy.limit_price, sell.limit_price, price_tolerance_bps);
+        let amount_sufficient = buy.amount >= sell.min_fill_amount && sell.amount >= buy.min_fill_amount;
+        let sides_valid = buy.side == 0 && sell.side == 1;
+        
+        tokens_match && price_compatible && amount_sufficient && sides_valid
+    }
+
+    /// Fill-or-kill orders only match when the counterparty covers their
+    /// whole amount.
+    fn fill_or_kill_blocked(buy: &DarkPoolOrder, sell: &DarkPoolOrder) -> bool {
+        (buy.tif == 2 && buy.amount > sell.amount) || (sell.tif == 2 && sell.amount > buy.amount)
+    }
+
+    /// Size an order shows to depth calculations. Icebergs show their current
+    /// slice; everything else shows the full amount.
+    fn visible_amount(order: &DarkPoolOrder) -> u64 {
+        if order.display_amount > 0 && order.display_amount < order.amount {
+            order.display_amount
+        } else {
+            order.amount
+        }
+    }
+
+    /// Whether a fill uses up an iceberg's displayed slice while hidden size
+    /// remains, so the slice is refreshed from the reserve.
+    fn replenishes_slice(order: &DarkPoolOrder, fill_amount: u64) -> bool {
+        let is_iceberg = order.display_amount > 0 && order.display_amount < order.amount;
+        is_iceberg && fill_amount >= order.display_amount && fill_amount < order.amount
+    }
+
+    /// What a match attempt reveals besides the orders and maker volume it
+    /// writes back.
+    pub struct MatchFill {
+        is_matched: bool,
+        failure_reason: u8,
+        released_rebate: u64,
+        buy_replenished: bool,
+        sell_replenished: bool,
+        settled_fill: u64,
+        settled_price: u64,
+        /// Nothing is left to fill, after a full fill or a dropped IOC
+        /// remainder.
+        buy_exhausted: bool,
+        sell_exhausted: bool,
+    }
+
+    /// The matching rules every order-filling circuit shares: fills both
+    /// orders, credits the maker's volume and rebate, and drops any
+    /// immediate-or-cancel remainder.
+    fn fill_orders(
+        buy: &mut DarkPoolOrder,
+        sell: &mut DarkPoolOrder,
+        maker_volume: &mut MakerVolume,
+        price_tolerance_bps: u64,
+        round_toward: u8,
+        maker_rebate_bps: u64,
+        rebate_quantum: u64,
+        reveal_settlement: bool,
+        reset_realized_window: bool,
+    ) -> MatchFill {
+        let is_self_trade = buy.owner_key == sell.owner_key;
+        
+        let is_crossable = orders_cross(buy, sell, price_tolerance_bps);
+        let fok_blocked = fill_or_kill_blocked(buy, sell);
+        
+        // The failure reason decides the match, so a self-trade can't match
+        // under any other outcome.
+        let failure_reason = match_failure_reason(is_crossable, is_self_trade, fok_blocked);
+        let is_matched = failure_reason == 0;
+        
+        if reset_realized_window {
+            maker_volume.realized_volume = 0;
+        }
+        
+        let mut buy_replenished = false;
+        let mut sell_replenished = false;
+        let mut settled_fill: u64 = 0;
+        let mut settled_price: u64 = 0;
+        if is_matched {
+            // Fills draw on the full hidden size, not just the displayed slice.
+            let fill_amount = if buy.amount < sell.amount { buy.amount } else { sell.amount };
+            let execution_price = midpoint_price(buy.limit_price, sell.limit_price, round_toward);
+            buy.last_execution_price = execution_price;
+            sell.last_execution_price = execution_price;
+            
+            // Fill terms are only revealed when the venue settles on-chain.
+            if reveal_settlement {
+                settled_fill = fill_amount;
+                settled_price = execution_price;
+            }
+            maker_volume.matched_volume = maker_volume.matched_volume + fill_amount;
+            maker_volume.realized_volume = maker_volume.realized_volume + fill_amount;
+            maker_volume.accrued_rebate = maker_volume.accrued_rebate
+                + maker_rebate(execution_price, fill_amount, maker_rebate_bps);
+            
+            // display_amount is the slice size, so once the shown slice is
+            // consumed the next one is whatever of it the reserve can cover.
+            buy_replenished = replenishes_slice(buy, fill_amount);
+            sell_replenished = replenishes_slice(sell, fill_amount);
+            buy.amount = buy.amount - fill_amount;
+            sell.amount = sell.amount - fill_amount;
+        }
+        
+        // Immediate-or-cancel orders drop whatever didn't fill on this attempt.
+        if buy.tif == 1 {
+            buy.amount = 0;
+        }
+        if sell.tif == 1 {
+            sell.amount = 0;
+        }
+        
+        let released_rebate = releasable_rebate(maker_volume.accrued_rebate, rebate_quantum);
+        maker_volume.accrued_rebate = maker_volume.accrued_rebate - released_rebate;
+        
+        MatchFill {
+            is_matched,
+            failure_reason,
+            released_rebate,
+            buy_replenished,
+            sell_replenished,
+            settled_fill,
+            settled_price,
+            buy_exhausted: buy.amount == 0,
+            sell_exhausted: sell.amount == 0,
+        }
+    }
+
     #[instruction]
     pub fn match_dark_pool_orders(
         buy_order: Enc<Mxe, DarkPoolOrder>,
         sell_order: Enc<Mxe, DarkPoolOrder>,
-    ) -> bool {
-        let buy = buy_order.to_arcis();
-        let sell = sell_order.to_arcis();
+        maker_volume_ctxt: Enc<Mxe, MakerVolume>,
+        price_tolerance_bps: u64,
+        round_toward: u8,
+        maker_rebate_bps: u64,
+        rebate_quantum: u64,
+        reveal_settlement: bool,
+        reset_realized_window: bool,
+    ) -> (
+        bool,
+        u8,
+        Enc<Mxe, MakerVolume>,
+        u64,
+        Enc<Mxe, DarkPoolOrder>,
+        Enc<Mxe, DarkPoolOrder>,
+        bool,
+        bool,
+        u64,
+        u64,
+        bool,
+        bool,
+    ) {
+        let mut buy = buy_order.to_arcis();
+        let mut sell = sell_order.to_arcis();
+        let mut maker_volume = maker_volume_ctxt.to_arcis();
         
-        let tokens_match = buy.token_mint == sell.token_mint;
-        let price_compatible = buy.limit_price >= sell.limit_price;
-        let amount_sufficient = buy.amount >= sell.min_fill_amount && sell.amount >= buy.min_fill_amount;
-        let sides_valid = buy.side == 0 && sell.side == 1;
+        let fill = fill_orders(
+            &mut buy,
+            &mut sell,
+            &mut maker_volume,
+            price_tolerance_bps,
+            round_toward,
+            maker_rebate_bps,
+            rebate_quantum,
+            reveal_settlement,
+            reset_realized_window,
+        );
         
-        (tokens_match && price_compatible && amount_sufficient && sides_valid).reveal()
+        (
+            fill.is_matched.reveal(),
+            fill.failure_reason.reveal(),
+            maker_volume_ctxt.owner.from_arcis(maker_volume),
+            fill.released_rebate.reveal(),
+            buy_order.owner.from_arcis(buy),
+            sell_order.owner.from_arcis(sell),
+            fill.buy_replenished.reveal(),
+            fill.sell_replenished.reveal(),
+            fill.settled_fill.reveal(),
+            fill.settled_price.reveal(),
+            fill.buy_exhausted.reveal(),
+            fill.sell_exhausted.reveal(),
+        )
     }
 
     #[instruction]
     pub fn calculate_execution_price(
         buy_order: Enc<Mxe, DarkPoolOrder>,
         sell_order: Enc<Mxe, DarkPoolOrder>,
+        price_tolerance_bps: u64,
+        reference_price: u64,
+        taker_is_buy: bool,
+        maker_rebate_bps: u64,
+        round_toward: u8,
     ) -> Enc<Shared, OrderMatchResult> {
         let buy = buy_order.to_arcis();
         let sell = sell_order.to_arcis();
         
-        let execution_price = (buy.limit_price + sell.limit_price) / 2;
+        let execution_price = midpoint_price(buy.limit_price, sell.limit_price, round_toward);
         let fill_amount = if buy.amount < sell.amount { buy.amount } else { sell.amount };
         
-        let is_matched = buy.limit_price >= sell.limit_price;
+        // Don't fill the taker at a midpoint worse than they could get externally.
+        let beats_reference = beats_reference_price(execution_price, reference_price, taker_is_buy);
+        
+        let is_matched = prices_cross(buy.limit_price, sell.limit_price, price_tolerance_bps)
+            && beats_reference;
         
         let result = OrderMatchResult {
             is_matched,
             execution_price,
             fill_amount,
+            maker_rebate: if is_matched {
+                maker_rebate(execution_price, fill_amount, maker_rebate_bps)
+            } else {
+                0
+            },
         };
         
         buy_order.owner.from_arcis(result)
@@ -228,6 +705,7 @@ mod circuits {
             min_amount_out: 0,
             max_slippage_bps: 50,
             deadline: 0,
+            last_filled_in: 0,
         };
         mxe.from_arcis(intent)
     }
@@ -237,22 +715,100 @@ mod circuits {
         intent_ctxt: Enc<Shared, SwapIntent>,
         stored_ctxt: Enc<Mxe, SwapIntent>,
     ) -> Enc<Mxe, SwapIntent> {
-        let intent = intent_ctxt.to_arcis();
+        let mut intent = intent_ctxt.to_arcis();
+        intent.last_filled_in = 0;
         stored_ctxt.owner.from_arcis(intent)
     }
 
+    /// Widens `base_slippage_bps` by the oracle's confidence interval as a
+    /// share of its price, capped at `max_slippage_bps`.
+    fn confidence_slippage_bps(
+        oracle_price: u64,
+        confidence: u64,
+        base_slippage_bps: u64,
+        max_slippage_bps: u64,
+    ) -> u64 {
+        let widened = base_slippage_bps + (confidence * 10000) / oracle_price;
+        if widened > max_slippage_bps { max_slippage_bps } else { widened }
+    }
+
+    #[instruction]
+    pub fn compute_slippage_from_confidence(
+        oracle_price: u64,
+        confidence: u64,
+        base_slippage_bps: u64,
+        max_slippage_bps: u64,
+    ) -> u64 {
+        confidence_slippage_bps(oracle_price, confidence, base_slippage_bps, max_slippage_bps).reveal()
+    }
+
+    /// Executes against `actual_output`. An output short of `min_amount_out`
+    /// fills the intent proportionally and leaves the rest as a reduced
+    /// intent; a full fill consumes it. The allowed slippage widens with the
+    /// oracle's confidence interval; the oracle has to price one side of the
+    /// intent's pair, checked against `feed_mint`. Reveals whether the swap
+    /// executed, the remaining share of `amount_in` in quarters (0 = fully
+    /// consumed, 4 = at least 75% left), the output it executed at (0 if it
+    /// didn't) and whether the feed matched.
     #[instruction]
     pub fn execute_private_swap(
         intent_ctxt: Enc<Mxe, SwapIntent>,
         actual_output: u64,
+        base_slippage_bps: u64,
+        oracle_price: u64,
+        confidence: u64,
         max_slippage_bps: u64,
-    ) -> bool {
-        let intent = intent_ctxt.to_arcis();
+        feed_mint: [u8; 32],
+    ) -> (Enc<Mxe, SwapIntent>, bool, u8, u64, bool) {
+        let mut intent = intent_ctxt.to_arcis();
+        let original_amount_in = intent.amount_in;
+        
+        let feed_matches = intent.from_token == feed_mint || intent.to_token == feed_mint;
+        let allowed_slippage_bps =
+            confidence_slippage_bps(oracle_price, confidence, base_slippage_bps, max_slippage_bps);
+        let within_max_slippage = intent.max_slippage_bps <= allowed_slippage_bps;
+        let executed =
+            feed_matches && within_max_slippage && actual_output > 0 && original_amount_in > 0;
+        let full_fill = actual_output >= intent.min_amount_out;
+        
+        let consumed_in = if full_fill {
+            original_amount_in
+        } else {
+            (original_amount_in * actual_output) / intent.min_amount_out
+        };
+        
+        intent.last_filled_in = 0;
+        if executed {
+            intent.amount_in = original_amount_in - consumed_in;
+            intent.min_amount_out = if full_fill {
+                0
+            } else {
+                intent.min_amount_out - actual_output
+            };
+            intent.last_filled_in = consumed_in;
+        }
+        let executed_output = if executed { actual_output } else { 0 };
         
-        let slippage_ok = actual_output >= intent.min_amount_out;
-        let within_max_slippage = intent.max_slippage_bps <= max_slippage_bps;
+        let remaining = intent.amount_in;
+        let remaining_bucket: u8 = if remaining == 0 {
+            0
+        } else if remaining * 4 < original_amount_in {
+            1
+        } else if remaining * 2 < original_amount_in {
+            2
+        } else if remaining * 4 < original_amount_in * 3 {
+            3
+        } else {
+            4
+        };
         
-        (slippage_ok && within_max_slippage).reveal()
+        (
+            intent_ctxt.owner.from_arcis(intent),
+            executed.reveal(),
+            remaining_bucket.reveal(),
+            executed_output.reveal(),
+            feed_matches.reveal(),
+        )
     }
 
     #[instruction]
@@ -296,6 +852,61 @@ mod circuits {
         additional_needed.reveal()
     }
 
+    /// Forward-looking counterpart of `compute_optimal_rebalance`: collateral
+    /// the owner would need to add to stay at `margin_call_health_bps` if the
+    /// collateral price moved from `current_price` to `projected_price`.
+    /// Encrypted to the owner as a `magnitude_bucket` coarsened by
+    /// `reveal_precision`.
+    #[instruction]
+    pub fn compute_margin_call_buffer(
+        owner: Shared,
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        current_price: u64,
+        projected_price: u64,
+        margin_call_health_bps: u64,
+        reveal_precision: u8,
+    ) -> Enc<Shared, u8> {
+        let position = position_ctxt.to_arcis();
+        
+        let buffer = margin_call_buffer(
+            position.collateral_usd,
+            position.debt_usd,
+            current_price,
+            projected_price,
+            margin_call_health_bps,
+        );
+        
+        owner.from_arcis(coarsen_bucket(magnitude_bucket(buffer), reveal_precision))
+    }
+
+    /// When health is below `trigger_health_bps`, repays just enough debt to
+    /// bring health back to `target_health_bps`, the debt-side counterpart of
+    /// `compute_optimal_rebalance`. Reveals the debt repaid; 0 when the trigger
+    /// isn't met.
+    #[instruction]
+    pub fn apply_auto_deleverage(
+        stored_ctxt: Enc<Mxe, EncryptedPosition>,
+        trigger_health_bps: u64,
+        target_health_bps: u64,
+    ) -> (Enc<Mxe, EncryptedPosition>, u64) {
+        let mut position = stored_ctxt.to_arcis();
+        
+        let triggered = position.debt_usd > 0 && position.health_factor_bps < trigger_health_bps;
+        let target_debt = (position.collateral_usd * 10000) / target_health_bps;
+        let debt_reduced = if triggered && position.debt_usd > target_debt {
+            position.debt_usd - target_debt
+        } else {
+            0
+        };
+        
+        position.debt_usd = position.debt_usd - debt_reduced;
+        position.health_factor_bps = health_ratio_bps(position.collateral_usd, position.debt_usd);
+        position.leverage_bps = leverage_ratio_bps(position.collateral_usd, position.debt_usd);
+        record_health_sample(&mut position);
+        
+        (stored_ctxt.owner.from_arcis(position), debt_reduced.reveal())
+    }
+
     #[instruction]
     pub fn prove_no_front_running(
         intent_ctxt: Enc<Mxe, SwapIntent>,
@@ -316,14 +927,35 @@ mod circuits {
         total_debt: u64,
         weighted_health: u64,
         positions_at_risk: u8,
+        /// Collateral minus debt; negative when the portfolio is underwater,
+        /// which `weighted_health` alone doesn't make explicit.
+        net_equity: i64,
+    }
+
+    /// Highest positions-at-risk count any aggregation has seen, kept by the
+    /// MXE so the owner can't lower it.
+    pub struct RiskCapTracker {
+        max_observed_positions_at_risk: u8,
+    }
+
+    #[instruction]
+    pub fn init_risk_cap_tracker(
+        mxe: Mxe,
+    ) -> Enc<Mxe, RiskCapTracker> {
+        let tracker = RiskCapTracker {
+            max_observed_positions_at_risk: 0,
+        };
+        mxe.from_arcis(tracker)
     }
 
     #[instruction]
     pub fn aggregate_portfolio_risk(
         positions: Enc<Shared, [EncryptedPosition; 10]>,
+        tracker_ctxt: Enc<Mxe, RiskCapTracker>,
         risk_threshold_bps: u64,
-    ) -> Enc<Shared, AggregatedRiskMetrics> {
+    ) -> (Enc<Shared, AggregatedRiskMetrics>, Enc<Mxe, RiskCapTracker>) {
         let pos_array = positions.to_arcis();
+        let mut tracker = tracker_ctxt.to_arcis();
         
         let mut total_collateral: u64 = 0;
         let mut total_debt: u64 = 0;
@@ -348,13 +980,1707 @@ mod circuits {
             10000
         };
         
+        // Every aggregation folds into the running max, so no later call can
+        // bring it back down.
+        if positions_at_risk > tracker.max_observed_positions_at_risk {
+            tracker.max_observed_positions_at_risk = positions_at_risk;
+        }
+        
+        let net_equity = (total_collateral as i64) - (total_debt as i64);
+        
         let metrics = AggregatedRiskMetrics {
             total_collateral,
             total_debt,
             weighted_health,
             positions_at_risk,
+            net_equity,
         };
         
-        positions.owner.from_arcis(metrics)
+        (positions.owner.from_arcis(metrics), tracker_ctxt.owner.from_arcis(tracker))
     }
+
+    #[instruction]
+    pub fn extend_order_expiry(
+        order_ctxt: Enc<Mxe, DarkPoolOrder>,
+        new_expiry: i64,
+    ) -> (Enc<Mxe, DarkPoolOrder>, bool, i64) {
+        let mut order = order_ctxt.to_arcis();
+
+        let is_later = new_expiry > order.expires_at;
+        if is_later {
+            order.expires_at = new_expiry;
+        }
+
+        let effective_expiry = order.expires_at;
+
+        (
+            order_ctxt.owner.from_arcis(order),
+            is_later.reveal(),
+            effective_expiry.reveal(),
+        )
+    }
+
+    #[instruction]
+    pub fn init_maker_volume(
+        mxe: Mxe,
+        maker: [u8; 32],
+    ) -> Enc<Mxe, MakerVolume> {
+        let volume = MakerVolume {
+            matched_volume: 0,
+            realized_volume: 0,
+            accrued_rebate: 0,
+        };
+        mxe.from_arcis(volume)
+    }
+
+    #[instruction]
+    pub fn prove_volume_tier(
+        volume_ctxt: Enc<Mxe, MakerVolume>,
+        tier_1_volume: u64,
+        tier_2_volume: u64,
+        tier_3_volume: u64,
+    ) -> u8 {
+        let volume = volume_ctxt.to_arcis();
+        
+        let tier: u8 = if volume.matched_volume >= tier_3_volume {
+            3
+        } else if volume.matched_volume >= tier_2_volume {
+            2
+        } else if volume.matched_volume >= tier_1_volume {
+            1
+        } else {
+            0
+        };
+        
+        tier.reveal()
+    }
+
+    /// True when the price the two orders last matched at lies within the
+    /// band. The program only queues orders recorded as each other's latest
+    /// counterparty; both sides have to carry the same price.
+    #[instruction]
+    pub fn prove_price_in_band(
+        buy_order: Enc<Mxe, DarkPoolOrder>,
+        sell_order: Enc<Mxe, DarkPoolOrder>,
+        band_low: u64,
+        band_high: u64,
+    ) -> bool {
+        let buy = buy_order.to_arcis();
+        let sell = sell_order.to_arcis();
+        
+        let execution_price = buy.last_execution_price;
+        let recorded = execution_price > 0 && sell.last_execution_price == execution_price;
+        
+        (recorded && execution_price >= band_low && execution_price <= band_high).reveal()
+    }
+
+    #[instruction]
+    pub fn convert_stop_to_market(
+        order_ctxt: Enc<Mxe, DarkPoolOrder>,
+        market_price: u64,
+        oracle_mint: [u8; 32],
+        max_slippage_bps: u64,
+    ) -> (Enc<Mxe, DarkPoolOrder>, bool, bool) {
+        let mut order = order_ctxt.to_arcis();
+        
+        let feed_matches = order.token_mint == oracle_mint;
+        let has_stop = order.stop_price > 0 && !order.stop_triggered;
+        let crossed = if order.side == 0 {
+            market_price >= order.stop_price
+        } else {
+            market_price <= order.stop_price
+        };
+        let triggered = has_stop && crossed && feed_matches;
+        
+        // stop_price is left in place so the original trigger stays auditable.
+        if triggered {
+            let slippage = (market_price * max_slippage_bps) / 10000;
+            order.limit_price = if order.side == 0 {
+                market_price + slippage
+            } else {
+                market_price - slippage
+            };
+            order.stop_triggered = true;
+        }
+        
+        (order_ctxt.owner.from_arcis(order), triggered.reveal(), feed_matches.reveal())
+    }
+
+    /// True when `position_id` is the order's trigger position and its health
+    /// has dropped below the order's trigger level.
+    #[instruction]
+    pub fn check_conditional_trigger(
+        order_ctxt: Enc<Mxe, DarkPoolOrder>,
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        position_id: [u8; 32],
+    ) -> bool {
+        let order = order_ctxt.to_arcis();
+        let position = position_ctxt.to_arcis();
+        
+        let is_trigger_position = order.trigger_position_id == position_id;
+        let crossed = position.health_factor_bps < order.trigger_health_bps;
+        
+        (is_trigger_position && crossed).reveal()
+    }
+
+    #[instruction]
+    pub fn compute_solvency_ratio(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        reveal_precision: u8,
+    ) -> u8 {
+        let pos_array = positions.to_arcis();
+        
+        let mut total_collateral: u64 = 0;
+        let mut total_debt: u64 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            total_collateral = total_collateral + pos_array[i].collateral_usd;
+            total_debt = total_debt + pos_array[i].debt_usd;
+            i = i + 1;
+        }
+        
+        let ratio_bucket = solvency_bucket(total_collateral, total_debt);
+        
+        coarsen_bucket(ratio_bucket, reveal_precision).reveal()
+    }
+
+    #[instruction]
+    pub fn init_multi_collateral_position(
+        mxe: Mxe,
+        position_id: [u8; 32],
+        mints: [[u8; 32]; 4],
+    ) -> Enc<Mxe, MultiCollateralPosition> {
+        let position = MultiCollateralPosition {
+            legs: [
+                CollateralLeg { mint: mints[0], amount_usd: 0, haircut_bps: 0, is_stable: false, source_flag: 0 },
+                CollateralLeg { mint: mints[1], amount_usd: 0, haircut_bps: 0, is_stable: false, source_flag: 0 },
+                CollateralLeg { mint: mints[2], amount_usd: 0, haircut_bps: 0, is_stable: false, source_flag: 0 },
+                CollateralLeg { mint: mints[3], amount_usd: 0, haircut_bps: 0, is_stable: false, source_flag: 0 },
+            ],
+            debt_usd: 0,
+        };
+        mxe.from_arcis(position)
+    }
+
+    #[instruction]
+    pub fn update_multi_collateral_position(
+        position_ctxt: Enc<Shared, MultiCollateralPosition>,
+        stored_ctxt: Enc<Mxe, MultiCollateralPosition>,
+        source_flags: [u8; 4],
+    ) -> Enc<Mxe, MultiCollateralPosition> {
+        let mut position = position_ctxt.to_arcis();
+        let stored = stored_ctxt.to_arcis();
+        
+        // Leg mints were validated as unique at init; updates may only change amounts.
+        // Source flags are attested on-chain by the admin at deposit, never
+        // taken from the owner's input, and stick once set so a later clean
+        // deposit can't launder a flagged leg.
+        let mut i = 0;
+        while i < 4 {
+            position.legs[i].mint = stored.legs[i].mint;
+            position.legs[i].source_flag = if stored.legs[i].source_flag != 0 {
+                stored.legs[i].source_flag
+            } else {
+                source_flags[i]
+            };
+            i = i + 1;
+        }
+        
+        stored_ctxt.owner.from_arcis(position)
+    }
+
+    /// Sums haircut-weighted collateral across legs. Legs are assumed to have
+    /// distinct mints (enforced on-chain at init), so nothing is double-counted.
+    #[instruction]
+    pub fn prove_weighted_health(
+        position_ctxt: Enc<Mxe, MultiCollateralPosition>,
+        threshold_bps: u64,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        
+        let mut weighted_collateral: u64 = 0;
+        let mut i = 0;
+        while i < 4 {
+            let haircut = if position.legs[i].haircut_bps > 10000 {
+                10000
+            } else {
+                position.legs[i].haircut_bps
+            };
+            weighted_collateral = weighted_collateral
+                + (position.legs[i].amount_usd * (10000 - haircut)) / 10000;
+            i = i + 1;
+        }
+        
+        let health_bps = if position.debt_usd > 0 {
+            (weighted_collateral * 10000) / position.debt_usd
+        } else {
+            10000
+        };
+        
+        (health_bps >= threshold_bps).reveal()
+    }
+
+    #[instruction]
+    pub fn select_keeper(
+        seed: u64,
+        keeper_count: u8,
+    ) -> (u8, u64) {
+        let keeper_index = (seed % (keeper_count as u64)) as u8;
+        
+        (keeper_index.reveal(), seed.reveal())
+    }
+
+    #[instruction]
+    pub fn prove_aggregate_leverage_bounded(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        max_leverage_bps: u64,
+    ) -> bool {
+        let pos_array = positions.to_arcis();
+        
+        let mut total_collateral: u64 = 0;
+        let mut total_debt: u64 = 0;
+        
+        // Zero-debt positions still add collateral, which dilutes leverage.
+        let mut i = 0;
+        while i < 10 {
+            total_collateral = total_collateral + pos_array[i].collateral_usd;
+            total_debt = total_debt + pos_array[i].debt_usd;
+            i = i + 1;
+        }
+        
+        let bounded = if total_collateral > 0 {
+            (total_debt * 10000) / total_collateral <= max_leverage_bps
+        } else {
+            total_debt == 0
+        };
+        
+        bounded.reveal()
+    }
+
+    /// Checks up to three independent markets in one computation and reveals
+    /// which pairs would cross; nothing is filled. Slot `i` of `buy_orders` is
+    /// only ever compared with slot `i` of `sell_orders`, and `orders_cross`
+    /// requires equal mints, so cross-pair matches can't be reported.
+    #[instruction]
+    pub fn preview_multi_market(
+        buy_orders: Enc<Mxe, [DarkPoolOrder; 3]>,
+        sell_orders: Enc<Mxe, [DarkPoolOrder; 3]>,
+        pair_count: u8,
+        price_tolerance_bps: u64,
+    ) -> u8 {
+        let buys = buy_orders.to_arcis();
+        let sells = sell_orders.to_arcis();
+        
+        let mut crossing_mask: u8 = 0;
+        let mut bit: u8 = 1;
+        
+        let mut i = 0;
+        while i < 3 {
+            let in_use = (i as u8) < pair_count;
+            let is_self_trade = buys[i].owner_key == sells[i].owner_key;
+            if in_use && !is_self_trade && orders_cross(&buys[i], &sells[i], price_tolerance_bps) {
+                crossing_mask = crossing_mask | bit;
+            }
+            bit = bit * 2;
+            i = i + 1;
+        }
+        
+        crossing_mask.reveal()
+    }
+
+    #[instruction]
+    pub fn prove_collateral_composition(
+        position_ctxt: Enc<Mxe, MultiCollateralPosition>,
+        min_stable_bps: u64,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        
+        let mut total_collateral: u64 = 0;
+        let mut stable_collateral: u64 = 0;
+        
+        let mut i = 0;
+        while i < 4 {
+            total_collateral = total_collateral + position.legs[i].amount_usd;
+            if position.legs[i].is_stable {
+                stable_collateral = stable_collateral + position.legs[i].amount_usd;
+            }
+            i = i + 1;
+        }
+        
+        let satisfied = if total_collateral > 0 {
+            stable_collateral * 10000 >= min_stable_bps * total_collateral
+        } else {
+            min_stable_bps == 0
+        };
+        
+        satisfied.reveal()
+    }
+
+    /// Also reveals the post-topup risk level, read the same way as
+    /// `calculate_liquidation_risk`, so the program can clear the flag that
+    /// allowed the topup.
+    #[instruction]
+    pub fn apply_collateral_topup(
+        stored_ctxt: Enc<Mxe, EncryptedPosition>,
+        topup_amount: u64,
+        price_impact_bps: u64,
+        band_width_bps: u64,
+    ) -> (Enc<Mxe, EncryptedPosition>, u64, u8) {
+        let mut position = stored_ctxt.to_arcis();
+        
+        position.collateral_usd = position.collateral_usd + topup_amount;
+        
+        position.health_factor_bps = health_ratio_bps(position.collateral_usd, position.debt_usd);
+        position.leverage_bps = leverage_ratio_bps(position.collateral_usd, position.debt_usd);
+        record_health_sample(&mut position);
+        
+        let adjusted_health = impacted_health(position.health_factor_bps, price_impact_bps);
+        let risk_level = risk_band(adjusted_health, band_width_bps);
+        
+        (stored_ctxt.owner.from_arcis(position), topup_amount.reveal(), risk_level.reveal())
+    }
+
+    /// Counts a liquidation only if the stored health still reads as
+    /// liquidatable under the configured impact, so a stale or forged risk
+    /// flag can't inflate the history. Reveals whether it was counted.
+    #[instruction]
+    pub fn record_liquidation(
+        stored_ctxt: Enc<Mxe, EncryptedPosition>,
+        liquidated_at: i64,
+        price_impact_bps: u64,
+        band_width_bps: u64,
+    ) -> (Enc<Mxe, EncryptedPosition>, bool) {
+        let mut position = stored_ctxt.to_arcis();
+        
+        let adjusted_health = impacted_health(position.health_factor_bps, price_impact_bps);
+        let is_liquidatable = risk_band(adjusted_health, band_width_bps) == 4;
+        
+        if is_liquidatable {
+            position.liquidation_count = position.liquidation_count + 1;
+            position.last_liquidation_at = liquidated_at;
+        }
+        
+        (stored_ctxt.owner.from_arcis(position), is_liquidatable.reveal())
+    }
+
+    #[instruction]
+    pub fn prove_clean_history(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        (position.liquidation_count == 0).reveal()
+    }
+
+    #[instruction]
+    pub fn prove_health_thresholds(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        thresholds_bps: [u64; 3],
+    ) -> [bool; 3] {
+        let position = position_ctxt.to_arcis();
+        let mut results = [false; 3];
+        
+        let mut i = 0;
+        while i < 3 {
+            results[i] = position.health_factor_bps >= thresholds_bps[i];
+            i = i + 1;
+        }
+        
+        results.reveal()
+    }
+
+    /// Same matching rules as `match_dark_pool_orders`, but nothing is
+    /// persisted and only whether the orders would match is revealed; price
+    /// and size stay hidden from both sides.
+    #[instruction]
+    pub fn preview_match(
+        buy_order: Enc<Mxe, DarkPoolOrder>,
+        sell_order: Enc<Mxe, DarkPoolOrder>,
+        price_tolerance_bps: u64,
+    ) -> bool {
+        let buy = buy_order.to_arcis();
+        let sell = sell_order.to_arcis();
+        
+        let is_self_trade = buy.owner_key == sell.owner_key;
+        let is_matched = orders_cross(&buy, &sell, price_tolerance_bps) && !is_self_trade;
+        
+        is_matched.reveal()
+    }
+
+    /// True when the intent's latest execution returned at least
+    /// `min_improvement_bps` more than `quoted_price` would have for what it
+    /// consumed. Prices are output per unit of input.
+    #[instruction]
+    pub fn prove_price_improvement(
+        intent_ctxt: Enc<Mxe, SwapIntent>,
+        executed_output: u64,
+        quoted_price: u64,
+        min_improvement_bps: u64,
+    ) -> bool {
+        let intent = intent_ctxt.to_arcis();
+        
+        // Scaling the output down keeps the comparison inside u128.
+        let discounted_output =
+            (executed_output as u128 * 10000) / (10000 + min_improvement_bps) as u128;
+        let quoted_output = intent.last_filled_in as u128 * quoted_price as u128;
+        let improved = intent.last_filled_in > 0 && discounted_output >= quoted_output;
+        
+        improved.reveal()
+    }
+
+    #[instruction]
+    pub fn migrate_position_schema(
+        mxe: Mxe,
+        legacy_ctxt: Enc<Mxe, EncryptedPositionV0>,
+    ) -> Enc<Mxe, EncryptedPosition> {
+        let legacy = legacy_ctxt.to_arcis();
+        
+        // Positions that predate the counter were never liquidated through
+        // record_liquidation, so they start from a clean history.
+        // The legacy health is the one real reading it carries.
+        let mut health_samples = [0u16; 8];
+        health_samples[0] = health_sample(legacy.health_factor_bps);
+        
+        let position = EncryptedPosition {
+            collateral_usd: legacy.collateral_usd,
+            debt_usd: legacy.debt_usd,
+            health_factor_bps: legacy.health_factor_bps,
+            leverage_bps: legacy.leverage_bps,
+            liquidation_price: legacy.liquidation_price,
+            protocol_id: legacy.protocol_id,
+            last_updated: legacy.last_updated,
+            liquidation_count: 0,
+            last_liquidation_at: 0,
+            schema_version: 3,
+            created_at: legacy.last_updated,
+            collateral_mint: [0u8; 32],
+            debt_mint: [0u8; 32],
+            health_samples,
+            health_sample_cursor: 1,
+            health_sample_count: 1,
+            direction: 0,
+            ema_health_bps: legacy.health_factor_bps,
+            ema_seeded: true,
+            entry_price: 0,
+        };
+        mxe.from_arcis(position)
+    }
+
+    /// Schema 1 predates `last_liquidation_at`. A position that was already
+    /// liquidated starts its cooldown from `migrated_at`, so migrating can't
+    /// make it look like the cooldown has passed.
+    #[instruction]
+    pub fn migrate_position_schema_v1(
+        mxe: Mxe,
+        legacy_ctxt: Enc<Mxe, EncryptedPositionV1>,
+        migrated_at: i64,
+    ) -> Enc<Mxe, EncryptedPosition> {
+        let legacy = legacy_ctxt.to_arcis();
+        
+        let mut health_samples = [0u16; 8];
+        health_samples[0] = health_sample(legacy.health_factor_bps);
+        
+        let last_liquidation_at = if legacy.liquidation_count > 0 { migrated_at } else { 0 };
+        
+        let position = EncryptedPosition {
+            collateral_usd: legacy.collateral_usd,
+            debt_usd: legacy.debt_usd,
+            health_factor_bps: legacy.health_factor_bps,
+            leverage_bps: legacy.leverage_bps,
+            liquidation_price: legacy.liquidation_price,
+            protocol_id: legacy.protocol_id,
+            last_updated: legacy.last_updated,
+            liquidation_count: legacy.liquidation_count,
+            last_liquidation_at,
+            schema_version: 3,
+            created_at: legacy.last_updated,
+            collateral_mint: [0u8; 32],
+            debt_mint: [0u8; 32],
+            health_samples,
+            health_sample_cursor: 1,
+            health_sample_count: 1,
+            direction: 0,
+            ema_health_bps: legacy.health_factor_bps,
+            ema_seeded: true,
+            entry_price: 0,
+        };
+        mxe.from_arcis(position)
+    }
+
+    /// Schema 2 predates `entry_price`. No cost basis was ever recorded, so
+    /// it starts unset until the owner supplies one.
+    #[instruction]
+    pub fn migrate_position_schema_v2(
+        mxe: Mxe,
+        legacy_ctxt: Enc<Mxe, EncryptedPositionV2>,
+    ) -> Enc<Mxe, EncryptedPosition> {
+        let legacy = legacy_ctxt.to_arcis();
+        
+        let position = EncryptedPosition {
+            collateral_usd: legacy.collateral_usd,
+            debt_usd: legacy.debt_usd,
+            health_factor_bps: legacy.health_factor_bps,
+            leverage_bps: legacy.leverage_bps,
+            liquidation_price: legacy.liquidation_price,
+            protocol_id: legacy.protocol_id,
+            last_updated: legacy.last_updated,
+            liquidation_count: legacy.liquidation_count,
+            last_liquidation_at: legacy.last_liquidation_at,
+            schema_version: 3,
+            created_at: legacy.created_at,
+            collateral_mint: legacy.collateral_mint,
+            debt_mint: legacy.debt_mint,
+            health_samples: legacy.health_samples,
+            health_sample_cursor: legacy.health_sample_cursor,
+            health_sample_count: legacy.health_sample_count,
+            direction: legacy.direction,
+            ema_health_bps: legacy.ema_health_bps,
+            ema_seeded: legacy.ema_seeded,
+            entry_price: 0,
+        };
+        mxe.from_arcis(position)
+    }
+
+    #[instruction]
+    pub fn stress_test_portfolio(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        price_shock_bps: u64,
+        band_width_bps: u64,
+    ) -> u8 {
+        let pos_array = positions.to_arcis();
+        let mut liquidations: u8 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            // The shock scales each position's health by the same fraction.
+            let shocked_health = (pos_array[i].health_factor_bps * (10000 - price_shock_bps)) / 10000;
+            if pos_array[i].debt_usd > 0 && risk_band(shocked_health, band_width_bps) == 4 {
+                liquidations = liquidations + 1;
+            }
+            i = i + 1;
+        }
+        
+        liquidations.reveal()
+    }
+
+    #[instruction]
+    pub fn commit_delayed_reveal(
+        mxe: Mxe,
+        result_ctxt: Enc<Shared, u64>,
+    ) -> Enc<Mxe, u64> {
+        let result = result_ctxt.to_arcis();
+        mxe.from_arcis(result)
+    }
+
+    #[instruction]
+    pub fn finalize_delayed_reveal(
+        committed_ctxt: Enc<Mxe, u64>,
+    ) -> u64 {
+        let result = committed_ctxt.to_arcis();
+        result.reveal()
+    }
+
+    /// Picks which taker fills a resting order. Price-time priority takes the
+    /// best price for the resting order, then the higher priority fee at the
+    /// same price; size priority takes the largest displayed taker amount.
+    /// Remaining ties go to the lower (earlier) sequence. Fees are the
+    /// lamports each taker escrowed when it was placed. Returns the selected
+    /// slot, or 255 when nothing crosses.
+    #[instruction]
+    pub fn match_book(
+        resting_order: Enc<Mxe, DarkPoolOrder>,
+        taker_orders: Enc<Mxe, [DarkPoolOrder; 5]>,
+        taker_sequences: [u64; 5],
+        taker_fees: [u64; 5],
+        taker_count: u8,
+        size_priority: bool,
+        price_tolerance_bps: u64,
+    ) -> u8 {
+        let resting = resting_order.to_arcis();
+        let takers = taker_orders.to_arcis();
+        let resting_is_buy = resting.side == 0;
+        
+        let mut selected: u8 = 255;
+        let mut best = BookRank {
+            limit_price: 0,
+            visible_amount: 0,
+            sequence: 0,
+            priority_fee: 0,
+        };
+        
+        let mut i = 0;
+        while i < 5 {
+            let taker = &takers[i];
+            let in_use = (i as u8) < taker_count;
+            let is_self_trade = taker.owner_key == resting.owner_key;
+            let crosses = if resting_is_buy {
+                orders_cross(&resting, taker, price_tolerance_bps)
+            } else {
+                orders_cross(taker, &resting, price_tolerance_bps)
+            };
+            
+            // Size priority ranks on displayed size so iceberg reserves stay hidden.
+            let candidate = BookRank {
+                limit_price: taker.limit_price,
+                visible_amount: visible_amount(taker),
+                sequence: taker_sequences[i],
+                priority_fee: taker_fees[i],
+            };
+            // A resting buy wants the cheapest seller, a resting sell the
+            // highest bidder.
+            let outranks = book_outranks(&candidate, &best, size_priority, resting_is_buy);
+            
+            if in_use && crosses && !is_self_trade && (selected == 255 || outranks) {
+                selected = i as u8;
+                best = candidate;
+            }
+            i = i + 1;
+        }
+        
+        selected.reveal()
+    }
+
+    /// Each position below `threshold_bps` contributes its shortfall, so deeply
+    /// underwater positions weigh more than ones just under the line. The sum is
+    /// revealed only as a bucket (0 = nothing at risk, 4 = at least 75% of the
+    /// worst case).
+    #[instruction]
+    pub fn batch_weighted_risk_score(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        threshold_bps: u64,
+        reveal_precision: u8,
+    ) -> u8 {
+        let pos_array = positions.to_arcis();
+        let mut score: u64 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            score = score + health_shortfall(pos_array[i].health_factor_bps, pos_array[i].debt_usd, threshold_bps);
+            i = i + 1;
+        }
+        
+        let score_bucket = weighted_risk_bucket(score, threshold_bps);
+        
+        coarsen_bucket(score_bucket, reveal_precision).reveal()
+    }
+
+    /// Reveals the most debt a liquidator may repay in one call, as an
+    /// order-of-magnitude bucket: 0 = none, 1 = under 1k, 2 = under 10k,
+    /// 3 = under 100k, 4 = under 1M, 5 = 1M and above.
+    #[instruction]
+    pub fn compute_max_liquidatable(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        close_factor_bps: u64,
+        reveal_precision: u8,
+    ) -> u8 {
+        let position = position_ctxt.to_arcis();
+        
+        let amount_bucket = repay_bucket(position.debt_usd, close_factor_bps);
+        
+        coarsen_bucket(amount_bucket, reveal_precision).reveal()
+    }
+
+    /// Sums collateral held with each protocol and reveals only the ordering:
+    /// 0 = `protocol_a` is larger, 1 = `protocol_b` is larger, 2 = equal.
+    #[instruction]
+    pub fn compare_protocol_exposure(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        protocol_a: u8,
+        protocol_b: u8,
+    ) -> u8 {
+        let pos_array = positions.to_arcis();
+        let mut exposure_a: u64 = 0;
+        let mut exposure_b: u64 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            if pos_array[i].protocol_id == protocol_a {
+                exposure_a = exposure_a + pos_array[i].collateral_usd;
+            }
+            if pos_array[i].protocol_id == protocol_b {
+                exposure_b = exposure_b + pos_array[i].collateral_usd;
+            }
+            i = i + 1;
+        }
+        
+        let larger: u8 = if exposure_a > exposure_b {
+            0
+        } else if exposure_b > exposure_a {
+            1
+        } else {
+            2
+        };
+        
+        larger.reveal()
+    }
+
+    /// Bundles `update_health_factor` and a threshold proof into one
+    /// computation.
+    #[instruction]
+    pub fn update_and_prove_health(
+        stored_ctxt: Enc<Mxe, EncryptedPosition>,
+        oracle_price: u64,
+        oracle_mint: [u8; 32],
+        threshold_bps: u64,
+        ema_smoothing_bps: u64,
+        now: i64,
+    ) -> (Enc<Mxe, EncryptedPosition>, bool, bool) {
+        let mut position = stored_ctxt.to_arcis();
+        
+        let feed_matches = reprice_position(&mut position, oracle_price, oracle_mint);
+        record_health_sample(&mut position);
+        record_health_ema(&mut position, ema_smoothing_bps);
+        position.last_updated = now;
+        
+        let is_healthy = position.health_factor_bps >= threshold_bps;
+        
+        (stored_ctxt.owner.from_arcis(position), feed_matches.reveal(), is_healthy.reveal())
+    }
+
+    /// Histogram of position ages over the `age_bucket` ranges. Positions
+    /// without a creation time are skipped. A coarser `reveal_precision`
+    /// merges adjacent ranges into the lower slot, leaving the top slots 0.
+    #[instruction]
+    pub fn compute_age_distribution(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        current_time: i64,
+        reveal_precision: u8,
+    ) -> [u8; 4] {
+        let pos_array = positions.to_arcis();
+        let mut histogram = [0u8; 4];
+        
+        let mut i = 0;
+        while i < 10 {
+            let created_at = pos_array[i].created_at;
+            let slot = coarsen_bucket(age_bucket(current_time - created_at), reveal_precision);
+            
+            // The slot is secret, so every one is visited.
+            let mut k = 0;
+            while k < 4 {
+                if created_at > 0 && (k as u8) == slot {
+                    histogram[k] = histogram[k] + 1;
+                }
+                k = k + 1;
+            }
+            i = i + 1;
+        }
+        
+        histogram.reveal()
+    }
+
+    /// Turns an order that opted into `convert_on_expiry` into a marketable one,
+    /// bounded by the slippage cap the owner set on the order. `market_price`
+    /// is the registered feed's price for `oracle_mint`; the order is left
+    /// alone unless that's the token it trades. Reveals whether it was
+    /// converted and whether the feed matched.
+    #[instruction]
+    pub fn handle_expiring_order(
+        order_ctxt: Enc<Mxe, DarkPoolOrder>,
+        market_price: u64,
+        oracle_mint: [u8; 32],
+    ) -> (Enc<Mxe, DarkPoolOrder>, bool, bool) {
+        let mut order = order_ctxt.to_arcis();
+        
+        let feed_matches = order.token_mint == oracle_mint;
+        let converted = order.convert_on_expiry && feed_matches;
+        
+        if converted {
+            // The cap comes from owner-supplied order data, so clamp it here.
+            let slippage_bps = if order.expiry_slippage_bps > 10000 {
+                10000
+            } else {
+                order.expiry_slippage_bps
+            };
+            let slippage = (market_price * slippage_bps) / 10000;
+            order.limit_price = if order.side == 0 {
+                market_price + slippage
+            } else {
+                market_price - slippage
+            };
+            order.convert_on_expiry = false;
+        }
+        
+        (order_ctxt.owner.from_arcis(order), converted.reveal(), feed_matches.reveal())
+    }
+
+    #[instruction]
+    pub fn prove_unleveraged(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        
+        let unleveraged = position.debt_usd == 0 || position.leverage_bps <= 10000;
+        
+        unleveraged.reveal()
+    }
+
+    fn fresh_position(protocol: u8, created_at: i64) -> EncryptedPosition {
+        EncryptedPosition {
+            collateral_usd: 0,
+            debt_usd: 0,
+            health_factor_bps: 10000,
+            leverage_bps: 10000,
+            liquidation_price: 0,
+            protocol_id: protocol,
+            last_updated: 0,
+            liquidation_count: 0,
+            last_liquidation_at: 0,
+            schema_version: 3,
+            created_at,
+            collateral_mint: [0u8; 32],
+            debt_mint: [0u8; 32],
+            health_samples: [0; 8],
+            health_sample_cursor: 0,
+            health_sample_count: 0,
+            direction: 0,
+            ema_health_bps: 0,
+            ema_seeded: false,
+            entry_price: 0,
+        }
+    }
+
+    #[instruction]
+    pub fn batch_init_encrypted_positions(
+        mxe: Mxe,
+        protocols: [u8; 5],
+        created_at: i64,
+    ) -> Enc<Mxe, [EncryptedPosition; 5]> {
+        let positions = [
+            fresh_position(protocols[0], created_at),
+            fresh_position(protocols[1], created_at),
+            fresh_position(protocols[2], created_at),
+            fresh_position(protocols[3], created_at),
+            fresh_position(protocols[4], created_at),
+        ];
+        mxe.from_arcis(positions)
+    }
+
+    #[instruction]
+    pub fn prove_min_collateral(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        min_collateral_usd: u64,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        (position.collateral_usd >= min_collateral_usd).reveal()
+    }
+
+    /// Buckets a portfolio's return against the dispersion of its health
+    /// factors, revealed only to the owner.
+    ///
+    /// Return is aggregate PnL over aggregate collateral, in bps, where each
+    /// position's PnL is its notional (`collateral_usd * leverage_bps`) moved
+    /// by `mark_prices[i]` against its `entry_price`, signed by direction.
+    /// Positions without an entry or mark price contribute no PnL. A net loss
+    /// counts as a return of 0. Volatility is the mean absolute deviation of
+    /// health factors from their mean, in bps. The score is
+    /// `return * 10000 / (10000 + volatility)`, bucketed as:
+    /// 0 = under 2000, 1 = under 4000, 2 = under 6000, 3 = under 8000,
+    /// 4 = 8000 and above. Portfolios without collateral score 0.
+    #[instruction]
+    pub fn compute_risk_adjusted_score(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        mark_prices: [u64; 10],
+    ) -> Enc<Shared, u8> {
+        let pos_array = positions.to_arcis();
+        let mut total_collateral: u64 = 0;
+        let mut gains: u128 = 0;
+        let mut losses: u128 = 0;
+        let mut health_sum: u64 = 0;
+        let mut active: u64 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            if pos_array[i].collateral_usd > 0 {
+                total_collateral = total_collateral + pos_array[i].collateral_usd;
+                health_sum = health_sum + pos_array[i].health_factor_bps;
+                active = active + 1;
+                
+                let entry = pos_array[i].entry_price;
+                let mark = mark_prices[i];
+                if entry > 0 && mark > 0 {
+                    let notional =
+                        (pos_array[i].collateral_usd as u128 * pos_array[i].leverage_bps as u128) / 10000;
+                    let price_move = if mark > entry { mark - entry } else { entry - mark };
+                    let pnl = (notional * price_move as u128) / entry as u128;
+                    let gained = (mark > entry) != (pos_array[i].direction == 1);
+                    if gained {
+                        gains = gains + pnl;
+                    } else {
+                        losses = losses + pnl;
+                    }
+                }
+            }
+            i = i + 1;
+        }
+        
+        let mean_health = if active > 0 { health_sum / active } else { 0 };
+        
+        let mut deviation_sum: u64 = 0;
+        let mut j = 0;
+        while j < 10 {
+            if pos_array[j].collateral_usd > 0 {
+                let health = pos_array[j].health_factor_bps;
+                deviation_sum = deviation_sum + if health > mean_health {
+                    health - mean_health
+                } else {
+                    mean_health - health
+                };
+            }
+            j = j + 1;
+        }
+        
+        let volatility_bps = if active > 0 { deviation_sum / active } else { 0 };
+        let return_bps = if total_collateral > 0 && gains > losses {
+            ((gains - losses) * 10000) / total_collateral as u128
+        } else {
+            0
+        };
+        let score = (return_bps * 10000) / (10000 + volatility_bps as u128);
+        
+        let bucket: u8 = if score < 2000 {
+            0
+        } else if score < 4000 {
+            1
+        } else if score < 6000 {
+            2
+        } else if score < 8000 {
+            3
+        } else {
+            4
+        };
+        
+        positions.owner.from_arcis(bucket)
+    }
+
+    /// True when collateral and debt are different assets and the pair isn't
+    /// listed in the first `pair_count` entries of `correlated_pairs`, in
+    /// either order.
+    #[instruction]
+    pub fn prove_uncorrelated(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        correlated_pairs: [[[u8; 32]; 2]; 4],
+        pair_count: u8,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        let collateral = position.collateral_mint;
+        let debt = position.debt_mint;
+        
+        let mut correlated = collateral == debt;
+        
+        let mut i = 0;
+        while i < 4 {
+            let pair = correlated_pairs[i];
+            let listed = (pair[0] == collateral && pair[1] == debt)
+                || (pair[0] == debt && pair[1] == collateral);
+            if (i as u8) < pair_count && listed {
+                correlated = true;
+            }
+            i = i + 1;
+        }
+        
+        (!correlated).reveal()
+    }
+
+    /// True when the orders have different owners and weren't created within
+    /// `wash_window_seconds` of each other.
+    #[instruction]
+    pub fn prove_arms_length(
+        buy_order: Enc<Mxe, DarkPoolOrder>,
+        sell_order: Enc<Mxe, DarkPoolOrder>,
+        wash_window_seconds: i64,
+    ) -> bool {
+        let buy = buy_order.to_arcis();
+        let sell = sell_order.to_arcis();
+        
+        let distinct_owners = buy.owner_key != sell.owner_key;
+        let separation = if buy.created_at > sell.created_at {
+            buy.created_at - sell.created_at
+        } else {
+            sell.created_at - buy.created_at
+        };
+        
+        (distinct_owners && separation >= wash_window_seconds).reveal()
+    }
+
+    /// Maps health onto a fee tier from 0 (healthiest, cheapest) to 3.
+    /// `thresholds_bps` are descending; tier `n` is the first threshold the
+    /// health meets. Debt-free positions are tier 0.
+    #[instruction]
+    pub fn compute_fee_tier(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        thresholds_bps: [u64; 3],
+    ) -> u8 {
+        let position = position_ctxt.to_arcis();
+        
+        fee_tier(position.health_factor_bps, position.debt_usd, thresholds_bps).reveal()
+    }
+
+    /// True when no aggregation has ever seen more than `cap` positions at risk.
+    #[instruction]
+    pub fn prove_risk_cap_respected(
+        tracker_ctxt: Enc<Mxe, RiskCapTracker>,
+        cap: u8,
+    ) -> bool {
+        let tracker = tracker_ctxt.to_arcis();
+        (tracker.max_observed_positions_at_risk <= cap).reveal()
+    }
+
+    /// Matches two orders exactly as `match_dark_pool_orders` does and, only
+    /// if they match, adds `collateral_delta` to the taker's position and
+    /// recomputes its health. A failed match leaves the position untouched.
+    /// Reveals the same values as `match_dark_pool_orders`, followed by the
+    /// position.
+    #[instruction]
+    pub fn match_and_topup(
+        buy_order: Enc<Mxe, DarkPoolOrder>,
+        sell_order: Enc<Mxe, DarkPoolOrder>,
+        maker_volume_ctxt: Enc<Mxe, MakerVolume>,
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        price_tolerance_bps: u64,
+        round_toward: u8,
+        maker_rebate_bps: u64,
+        rebate_quantum: u64,
+        reveal_settlement: bool,
+        reset_realized_window: bool,
+        collateral_delta: u64,
+    ) -> (
+        bool,
+        u8,
+        Enc<Mxe, MakerVolume>,
+        u64,
+        Enc<Mxe, DarkPoolOrder>,
+        Enc<Mxe, DarkPoolOrder>,
+        bool,
+        bool,
+        u64,
+        u64,
+        bool,
+        bool,
+        Enc<Mxe, EncryptedPosition>,
+    ) {
+        let mut buy = buy_order.to_arcis();
+        let mut sell = sell_order.to_arcis();
+        let mut maker_volume = maker_volume_ctxt.to_arcis();
+        let mut position = position_ctxt.to_arcis();
+        
+        let fill = fill_orders(
+            &mut buy,
+            &mut sell,
+            &mut maker_volume,
+            price_tolerance_bps,
+            round_toward,
+            maker_rebate_bps,
+            rebate_quantum,
+            reveal_settlement,
+            reset_realized_window,
+        );
+        
+        if fill.is_matched {
+            position.collateral_usd = position.collateral_usd + collateral_delta;
+            
+            position.health_factor_bps = health_ratio_bps(position.collateral_usd, position.debt_usd);
+            position.leverage_bps = leverage_ratio_bps(position.collateral_usd, position.debt_usd);
+            record_health_sample(&mut position);
+        }
+        
+        (
+            fill.is_matched.reveal(),
+            fill.failure_reason.reveal(),
+            maker_volume_ctxt.owner.from_arcis(maker_volume),
+            fill.released_rebate.reveal(),
+            buy_order.owner.from_arcis(buy),
+            sell_order.owner.from_arcis(sell),
+            fill.buy_replenished.reveal(),
+            fill.sell_replenished.reveal(),
+            fill.settled_fill.reveal(),
+            fill.settled_price.reveal(),
+            fill.buy_exhausted.reveal(),
+            fill.sell_exhausted.reveal(),
+            position_ctxt.owner.from_arcis(position),
+        )
+    }
+
+    /// Checks rewards-program criteria in one pass: collateral of at least
+    /// `min_collateral_usd`, held since at least `min_hold_seconds` before
+    /// `current_time`, and leverage no higher than `max_leverage_bps`.
+    #[instruction]
+    pub fn prove_rewards_eligible(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        min_collateral_usd: u64,
+        min_hold_seconds: i64,
+        max_leverage_bps: u64,
+        current_time: i64,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        
+        let large_enough = position.collateral_usd >= min_collateral_usd;
+        let held_long_enough = position.created_at > 0
+            && current_time - position.created_at >= min_hold_seconds;
+        let leverage_ok = position.debt_usd == 0 || position.leverage_bps <= max_leverage_bps;
+        
+        (large_enough && held_long_enough && leverage_ok).reveal()
+    }
+
+    /// Counts legs holding collateral. Leg mints are unique (enforced at init),
+    /// so each funded leg is a distinct mint.
+    #[instruction]
+    pub fn prove_min_collateral_diversity(
+        position_ctxt: Enc<Mxe, MultiCollateralPosition>,
+        min_distinct_mints: u8,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        let mut funded_legs: u8 = 0;
+        
+        let mut i = 0;
+        while i < 4 {
+            if position.legs[i].amount_usd > 0 {
+                funded_legs = funded_legs + 1;
+            }
+            i = i + 1;
+        }
+        
+        (funded_legs >= min_distinct_mints).reveal()
+    }
+
+    /// True when at least `min_healthy` of the first `position_count`
+    /// positions are individually healthy, i.e. carry no debt or have health
+    /// at or above `threshold_bps`. Padding slots past `position_count` never
+    /// count.
+    #[instruction]
+    pub fn prove_min_healthy_count(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        position_count: u8,
+        threshold_bps: u64,
+        min_healthy: u8,
+    ) -> bool {
+        let pos_array = positions.to_arcis();
+        let mut healthy_count: u8 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            let in_use = (i as u8) < position_count;
+            let is_healthy =
+                pos_array[i].debt_usd == 0 || pos_array[i].health_factor_bps >= threshold_bps;
+            if in_use && is_healthy {
+                healthy_count = healthy_count + 1;
+            }
+            i = i + 1;
+        }
+        
+        (healthy_count >= min_healthy).reveal()
+    }
+
+    /// True when the two positions' collateral notionals differ by at most
+    /// `tolerance_bps` of the larger one.
+    #[instruction]
+    pub fn prove_position_parity(
+        position_a_ctxt: Enc<Mxe, EncryptedPosition>,
+        position_b_ctxt: Enc<Mxe, EncryptedPosition>,
+        tolerance_bps: u64,
+    ) -> bool {
+        let notional_a = position_a_ctxt.to_arcis().collateral_usd;
+        let notional_b = position_b_ctxt.to_arcis().collateral_usd;
+        
+        let (larger, smaller) = if notional_a >= notional_b {
+            (notional_a, notional_b)
+        } else {
+            (notional_b, notional_a)
+        };
+        
+        ((larger - smaller) * 10000 <= larger * tolerance_bps).reveal()
+    }
+
+    /// True when the intent's latest execution returned at least the
+    /// single-hop quote for what it consumed, quoted from the two tokens'
+    /// feeds. Also reveals whether the feeds price the intent's tokens; the
+    /// program discards the proof when they don't.
+    #[instruction]
+    pub fn prove_route_optimal(
+        intent_ctxt: Enc<Mxe, SwapIntent>,
+        route_output: u64,
+        from_price: u64,
+        to_price: u64,
+        from_mint: [u8; 32],
+        to_mint: [u8; 32],
+    ) -> (bool, bool) {
+        let intent = intent_ctxt.to_arcis();
+        let feeds_match = intent.from_token == from_mint && intent.to_token == to_mint;
+        
+        let to_price = if to_price > 0 { to_price } else { 1 };
+        let quote = (intent.last_filled_in as u128 * from_price as u128) / to_price as u128;
+        let optimal = intent.last_filled_in > 0 && route_output as u128 >= quote;
+        
+        (optimal.reveal(), feeds_match.reveal())
+    }
+
+    /// If any position is already below 100% health, applies its liquidation's
+    /// price impact to the rest and reveals how many more fall below 100%.
+    /// Reveals 0 when nothing is liquidating.
+    #[instruction]
+    pub fn estimate_cascade_risk(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        liquidation_price_impact_bps: u64,
+    ) -> u8 {
+        let pos_array = positions.to_arcis();
+        let floor: u64 = 10000;
+        
+        let mut any_liquidating = false;
+        let mut i = 0;
+        while i < 10 {
+            if pos_array[i].debt_usd > 0 && pos_array[i].health_factor_bps < floor {
+                any_liquidating = true;
+            }
+            i = i + 1;
+        }
+        
+        let mut additional_at_risk: u8 = 0;
+        let mut j = 0;
+        while j < 10 {
+            // The impact is a price move, so it scales health rather than
+            // shifting it by a flat number of bps.
+            let health = pos_array[j].health_factor_bps;
+            let adjusted_health = (health * (10000 - liquidation_price_impact_bps)) / 10000;
+            let was_safe = pos_array[j].debt_usd > 0 && health >= floor;
+            if any_liquidating && was_safe && adjusted_health < floor {
+                additional_at_risk = additional_at_risk + 1;
+            }
+            j = j + 1;
+        }
+        
+        additional_at_risk.reveal()
+    }
+
+    #[instruction]
+    pub fn init_order_activity(
+        mxe: Mxe,
+        owner: [u8; 32],
+    ) -> Enc<Mxe, OrderActivity> {
+        let activity = OrderActivity {
+            placements: 0,
+            cancels: 0,
+            fills: 0,
+        };
+        mxe.from_arcis(activity)
+    }
+
+    /// Folds activity counted on-chain since the last call into the
+    /// encrypted totals. The folded counts are handed back so the callback
+    /// clears exactly what was added.
+    #[instruction]
+    pub fn record_order_activity(
+        activity_ctxt: Enc<Mxe, OrderActivity>,
+        placed: u64,
+        cancelled: u64,
+        filled: u64,
+    ) -> (Enc<Mxe, OrderActivity>, u64, u64, u64) {
+        let mut activity = activity_ctxt.to_arcis();
+        
+        activity.placements = activity.placements + placed;
+        activity.cancels = activity.cancels + cancelled;
+        activity.fills = activity.fills + filled;
+        
+        (activity_ctxt.owner.from_arcis(activity), placed, cancelled, filled)
+    }
+
+    /// True when cancels stay within `max_cancel_to_fill_bps` of fills,
+    /// counting activity not yet folded in. An owner who cancels without
+    /// ever filling fails.
+    #[instruction]
+    pub fn prove_not_spoofing(
+        activity_ctxt: Enc<Mxe, OrderActivity>,
+        pending_cancels: u64,
+        pending_fills: u64,
+        max_cancel_to_fill_bps: u64,
+    ) -> bool {
+        let activity = activity_ctxt.to_arcis();
+        let cancels = activity.cancels + pending_cancels;
+        let fills = activity.fills + pending_fills;
+        
+        (cancels * 10000 <= fills * max_cancel_to_fill_bps).reveal()
+    }
+
+    /// True when every sampled health reading is above `floor_bps`. Slots
+    /// not yet written are skipped, and a position with no readings fails.
+    #[instruction]
+    pub fn prove_floor_held(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        floor_bps: u64,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        
+        let mut min_sample: u16 = 65535;
+        let mut i = 0;
+        while i < 8 {
+            if (i as u8) < position.health_sample_count && position.health_samples[i] < min_sample {
+                min_sample = position.health_samples[i];
+            }
+            i = i + 1;
+        }
+        
+        (position.health_sample_count > 0 && min_sample as u64 > floor_bps).reveal()
+    }
+
+    /// True when the swap's `amount_in` stays under `max_impact_bps` of
+    /// `available_liquidity`, without revealing the size.
+    #[instruction]
+    pub fn prove_swap_low_impact(
+        intent_ctxt: Enc<Mxe, SwapIntent>,
+        available_liquidity: u64,
+        max_impact_bps: u64,
+    ) -> bool {
+        let intent = intent_ctxt.to_arcis();
+        
+        (intent.amount_in * 10000 < available_liquidity * max_impact_bps).reveal()
+    }
+
+    /// True when the user's health ranks in the top third of the user plus
+    /// the first `peer_count` peers, all read from stored positions. Debt-free
+    /// positions rank above any health factor, and peers tied with the user
+    /// don't count as ahead, so the ranking is deterministic.
+    #[instruction]
+    pub fn prove_top_tier(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        peers: Enc<Mxe, [EncryptedPosition; 9]>,
+        peer_count: u8,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        let peer_array = peers.to_arcis();
+        let user_unlevered = position.debt_usd == 0;
+        
+        let mut ahead: u64 = 0;
+        let mut i = 0;
+        while i < 9 {
+            let peer = &peer_array[i];
+            let in_use = (i as u8) < peer_count;
+            let peer_unlevered = peer.debt_usd == 0;
+            let outranks = if user_unlevered {
+                false
+            } else {
+                peer_unlevered || peer.health_factor_bps > position.health_factor_bps
+            };
+            if in_use && outranks {
+                ahead = ahead + 1;
+            }
+            i = i + 1;
+        }
+        
+        (ahead * 3 < (peer_count as u64) + 1).reveal()
+    }
+
+    /// True when two orders, typically resting on different venues, trade the
+    /// same token on opposite sides at prices that cross within
+    /// `price_tolerance_bps`. Amounts are ignored and no price is revealed.
+    #[instruction]
+    pub fn prove_terms_match(
+        order_a_ctxt: Enc<Mxe, DarkPoolOrder>,
+        order_b_ctxt: Enc<Mxe, DarkPoolOrder>,
+        price_tolerance_bps: u64,
+    ) -> bool {
+        let order_a = order_a_ctxt.to_arcis();
+        let order_b = order_b_ctxt.to_arcis();
+        
+        let tokens_match = order_a.token_mint == order_b.token_mint;
+        let opposite_sides = order_a.side != order_b.side;
+        let prices_compatible = if order_a.side == 0 {
+            prices_cross(order_a.limit_price, order_b.limit_price, price_tolerance_bps)
+        } else {
+            prices_cross(order_b.limit_price, order_a.limit_price, price_tolerance_bps)
+        };
+        
+        (tokens_match && opposite_sides && prices_compatible).reveal()
+    }
+
+    /// True when more than `cooldown_seconds` have passed since the last
+    /// liquidation. Positions that were never liquidated pass.
+    #[instruction]
+    pub fn prove_liquidation_cooldown_passed(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        current_time: i64,
+        cooldown_seconds: i64,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        let never_liquidated = position.last_liquidation_at == 0;
+        
+        (never_liquidated || current_time > position.last_liquidation_at + cooldown_seconds).reveal()
+    }
+
+    /// Health check with accrued but unpaid funding applied to collateral
+    /// first. Positive `pending_funding_usd` is owed by the position and
+    /// negative is owed to it; collateral can't go below zero.
+    #[instruction]
+    pub fn prove_health_with_funding(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        pending_funding_usd: i64,
+        threshold_bps: u64,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        
+        let owed = if pending_funding_usd > 0 { pending_funding_usd as u64 } else { 0 };
+        let received = if pending_funding_usd < 0 { (0 - pending_funding_usd) as u64 } else { 0 };
+        let credited = position.collateral_usd + received;
+        let adjusted_collateral = if credited > owed { credited - owed } else { 0 };
+        
+        let is_healthy = if position.debt_usd > 0 {
+            (adjusted_collateral * 10000) / position.debt_usd >= threshold_bps
+        } else {
+            true
+        };
+        
+        is_healthy.reveal()
+    }
+
+    /// Buckets `(buy_volume - sell_volume) / total_volume` over the displayed
+    /// size of the first `order_count` orders that haven't expired at
+    /// `current_time`, so iceberg reserves stay out of it. Reveals
+    /// -3 (sell-heavy) through 3 (buy-heavy): under 10% is 0, under 40% is
+    /// 1, under 70% is 2, and 3 beyond that, signed by the heavier side.
+    #[instruction]
+    pub fn compute_book_imbalance(
+        orders: Enc<Mxe, [DarkPoolOrder; 10]>,
+        order_count: u8,
+        current_time: i64,
+    ) -> i8 {
+        let book = orders.to_arcis();
+        let mut buy_volume: u64 = 0;
+        let mut sell_volume: u64 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            let live = (i as u8) < order_count && current_time < book[i].expires_at;
+            let shown = visible_amount(&book[i]);
+            if live && book[i].side == 0 {
+                buy_volume = buy_volume + shown;
+            }
+            if live && book[i].side == 1 {
+                sell_volume = sell_volume + shown;
+            }
+            i = i + 1;
+        }
+        
+        let total_volume = buy_volume + sell_volume;
+        let buy_heavy = buy_volume >= sell_volume;
+        let difference = if buy_heavy { buy_volume - sell_volume } else { sell_volume - buy_volume };
+        
+        let magnitude: u8 = if difference * 10 < total_volume {
+            0
+        } else if difference * 10 < total_volume * 4 {
+            1
+        } else if difference * 10 < total_volume * 7 {
+            2
+        } else {
+            3
+        };
+        
+        let imbalance_bucket: i8 = if buy_heavy { magnitude as i8 } else { 0 - (magnitude as i8) };
+        imbalance_bucket.reveal()
+    }
+
+    /// True when total debt across the portfolio stays under
+    /// `credit_limit_usd`. Only the comparison is revealed.
+    #[instruction]
+    pub fn prove_under_credit_line(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        credit_limit_usd: u64,
+    ) -> bool {
+        let pos_array = positions.to_arcis();
+        let mut total_debt: u64 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            total_debt = total_debt + pos_array[i].debt_usd;
+            i = i + 1;
+        }
+        
+        (total_debt < credit_limit_usd).reveal()
+    }
+
+    /// Rebate tier from the maker's realized volume in the current window.
+    /// A window with no fills since it lapsed counts as zero volume.
+    #[instruction]
+    pub fn prove_realized_volume_tier(
+        volume_ctxt: Enc<Mxe, MakerVolume>,
+        window_expired: bool,
+        tier_1_volume: u64,
+        tier_2_volume: u64,
+        tier_3_volume: u64,
+    ) -> u8 {
+        let volume = volume_ctxt.to_arcis();
+        let realized = if window_expired { 0 } else { volume.realized_volume };
+        
+        let tier: u8 = if realized >= tier_3_volume {
+            3
+        } else if realized >= tier_2_volume {
+            2
+        } else if realized >= tier_1_volume {
+            1
+        } else {
+            0
+        };
+        
+        tier.reveal()
+    }
+
+    /// Reveals whether every funded leg carries a clean source flag. Empty
+    /// legs don't contribute collateral and are ignored.
+    #[instruction]
+    pub fn prove_clean_source(
+        position_ctxt: Enc<Mxe, MultiCollateralPosition>,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        
+        let mut clean = true;
+        let mut i = 0;
+        while i < 4 {
+            if position.legs[i].amount_usd > 0 && position.legs[i].source_flag != 0 {
+                clean = false;
+            }
+            i = i + 1;
+        }
+        
+        clean.reveal()
+    }
+
+    /// True when net directional exposure stays within `max_bias_bps` of the
+    /// gross exposure. Exposure is collateral scaled by leverage; an empty
+    /// portfolio counts as neutral.
+    #[instruction]
+    pub fn prove_portfolio_neutral(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        max_bias_bps: u64,
+    ) -> bool {
+        let pos_array = positions.to_arcis();
+        let mut long_exposure: u64 = 0;
+        let mut short_exposure: u64 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            let exposure = (pos_array[i].collateral_usd * pos_array[i].leverage_bps) / 10000;
+            if pos_array[i].direction == 1 {
+                short_exposure = short_exposure + exposure;
+            } else {
+                long_exposure = long_exposure + exposure;
+            }
+            i = i + 1;
+        }
+        
+        let net_exposure = if long_exposure > short_exposure {
+            long_exposure - short_exposure
+        } else {
+            short_exposure - long_exposure
+        };
+        let gross_exposure = long_exposure + short_exposure;
+        
+        (net_exposure * 10000 <= gross_exposure * max_bias_bps).reveal()
+    }
+
+    /// True when no single protocol holds more than `max_debt_concentration_bps`
+    /// of total debt. Each position's protocol total is summed across the
+    /// portfolio, so split positions on one protocol still count together.
+    #[instruction]
+    pub fn prove_debt_diversified(
+        positions: Enc<Shared, [EncryptedPosition; 10]>,
+        max_debt_concentration_bps: u64,
+    ) -> bool {
+        let pos_array = positions.to_arcis();
+        let mut total_debt: u64 = 0;
+        let mut largest_protocol_debt: u64 = 0;
+        
+        let mut i = 0;
+        while i < 10 {
+            total_debt = total_debt + pos_array[i].debt_usd;
+            
+            let mut protocol_debt: u64 = 0;
+            let mut j = 0;
+            while j < 10 {
+                if pos_array[j].protocol_id == pos_array[i].protocol_id {
+                    protocol_debt = protocol_debt + pos_array[j].debt_usd;
+                }
+                j = j + 1;
+            }
+            if protocol_debt > largest_protocol_debt {
+                largest_protocol_debt = protocol_debt;
+            }
+            i = i + 1;
+        }
+        
+        (largest_protocol_debt * 10000 <= total_debt * max_debt_concentration_bps).reveal()
+    }
+
+    /// True unless the two intents reverse each other (A->B then B->A) and
+    /// were executed within `wash_window_seconds` of each other. The
+    /// execution times are the ones `execute_private_swap_callback` recorded.
+    #[instruction]
+    pub fn prove_not_round_trip(
+        intent_a_ctxt: Enc<Mxe, SwapIntent>,
+        intent_b_ctxt: Enc<Mxe, SwapIntent>,
+        executed_at_a: i64,
+        executed_at_b: i64,
+        wash_window_seconds: i64,
+    ) -> bool {
+        let intent_a = intent_a_ctxt.to_arcis();
+        let intent_b = intent_b_ctxt.to_arcis();
+        
+        let reversing = intent_a.from_token == intent_b.to_token
+            && intent_a.to_token == intent_b.from_token;
+        let separation = if executed_at_a > executed_at_b {
+            executed_at_a - executed_at_b
+        } else {
+            executed_at_b - executed_at_a
+        };
+        
+        (!(reversing && separation < wash_window_seconds)).reveal()
+    }
+
+    /// Threshold proof against the health EMA rather than the latest reading,
+    /// so one spurious oracle tick can't flip it. Falls back to the latest
+    /// reading until the EMA is seeded.
+    #[instruction]
+    pub fn prove_smoothed_health_threshold(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        threshold_bps: u64,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        
+        let smoothed_health = if position.ema_seeded {
+            position.ema_health_bps
+        } else {
+            position.health_factor_bps
+        };
+        
+        (smoothed_health >= threshold_bps).reveal()
+    }
+
+    /// True when the position was last updated before `stale_before`.
+    /// Positions that were never updated count as stale.
+    #[instruction]
+    pub fn prove_position_stale(
+        position_ctxt: Enc<Mxe, EncryptedPosition>,
+        stale_before: i64,
+    ) -> bool {
+        let position = position_ctxt.to_arcis();
+        (position.last_updated < stale_before).reveal()
+    }
+
+    /// Closes `close_fraction_bps` of a position, shrinking collateral and
+    /// debt by the same share so health stays where it was. The debt repaid
+    /// is encrypted to the owner.
+    #[instruction]
+    pub fn partial_close_position(
+        owner: Shared,
+        stored_ctxt: Enc<Mxe, EncryptedPosition>,
+        close_fraction_bps: u64,
+    ) -> (Enc<Mxe, EncryptedPosition>, Enc<Shared, u64>) {
+        let mut position = stored_ctxt.to_arcis();
+        
+        let collateral_released = (position.collateral_usd * close_fraction_bps) / 10000;
+        let debt_repaid = (position.debt_usd * close_fraction_bps) / 10000;
+        
+        position.collateral_usd = position.collateral_usd - collateral_released;
+        position.debt_usd = position.debt_usd - debt_repaid;
+        position.health_factor_bps = health_ratio_bps(position.collateral_usd, position.debt_usd);
+        position.leverage_bps = leverage_ratio_bps(position.collateral_usd, position.debt_usd);
+        
+        (stored_ctxt.owner.from_arcis(position), owner.from_arcis(debt_repaid))
+    }
+}
 }
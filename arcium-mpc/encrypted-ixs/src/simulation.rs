@@ -0,0 +1,381 @@
+//! Plaintext mirrors of the circuit arithmetic, for testing without an Arcium
+//! cluster. The per-value arithmetic is the circuits' own, spliced in by
+//! `shared_arithmetic!`; the functions here only replace the encrypted inputs
+//! with cleartext ones and return what the circuit would reveal.
+
+shared_arithmetic! {
+    /// The shared arithmetic, compiled as plain public functions.
+    pub mod arithmetic {}
+}
+
+pub use arithmetic::*;
+
+/// Cleartext counterpart of the `EncryptedPosition` fields the circuits read.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    pub collateral_usd: u64,
+    pub debt_usd: u64,
+    pub health_factor_bps: u64,
+    pub leverage_bps: u64,
+}
+
+impl Position {
+    /// A position with health and leverage derived from its two legs.
+    pub fn new(collateral_usd: u64, debt_usd: u64) -> Self {
+        Position {
+            collateral_usd,
+            debt_usd,
+            health_factor_bps: health_ratio_bps(collateral_usd, debt_usd),
+            leverage_bps: leverage_ratio_bps(collateral_usd, debt_usd),
+        }
+    }
+}
+
+/// What `update_health_factor` stores for a position whose feed matches:
+/// health from the oracle price, with the priced leg rederived from it.
+pub fn update_health_factor(
+    mut position: Position,
+    liquidation_price: u64,
+    oracle_price: u64,
+    is_short: bool,
+) -> Position {
+    if position.debt_usd == 0 || liquidation_price == 0 {
+        return position;
+    }
+
+    let health = priced_health_bps(oracle_price, liquidation_price, is_short);
+    position.health_factor_bps = health;
+    if is_short {
+        position.debt_usd = (position.collateral_usd * 10000) / health;
+    } else {
+        position.collateral_usd = (position.debt_usd * health) / 10000;
+    }
+    position.leverage_bps = leverage_ratio_bps(position.collateral_usd, position.debt_usd);
+    position
+}
+
+/// The coarsened level and whether the full-precision level is the max.
+pub fn calculate_liquidation_risk(
+    position: &Position,
+    price_impact_bps: u64,
+    band_width_bps: u64,
+    reveal_precision: u8,
+) -> (u8, bool) {
+    let risk_level = risk_band(impacted_health(position.health_factor_bps, price_impact_bps), band_width_bps);
+
+    (coarsen_bucket(risk_level, reveal_precision), risk_level == 4)
+}
+
+pub fn prove_health_threshold(
+    position: &Position,
+    threshold_bps: u64,
+    hysteresis_bps: u64,
+    was_triggered: bool,
+    safety_buffer_bps: u64,
+) -> bool {
+    clears_health_threshold(
+        position.health_factor_bps,
+        threshold_bps,
+        hysteresis_bps,
+        was_triggered,
+        safety_buffer_bps,
+    )
+}
+
+pub fn compute_solvency_ratio(positions: &[Position], reveal_precision: u8) -> u8 {
+    let total_collateral: u64 = positions.iter().map(|p| p.collateral_usd).sum();
+    let total_debt: u64 = positions.iter().map(|p| p.debt_usd).sum();
+
+    coarsen_bucket(solvency_bucket(total_collateral, total_debt), reveal_precision)
+}
+
+pub fn batch_weighted_risk_score(positions: &[Position], threshold_bps: u64, reveal_precision: u8) -> u8 {
+    let score: u64 = positions
+        .iter()
+        .map(|p| health_shortfall(p.health_factor_bps, p.debt_usd, threshold_bps))
+        .sum();
+
+    coarsen_bucket(weighted_risk_bucket(score, threshold_bps), reveal_precision)
+}
+
+pub fn compute_max_liquidatable(position: &Position, close_factor_bps: u64, reveal_precision: u8) -> u8 {
+    coarsen_bucket(repay_bucket(position.debt_usd, close_factor_bps), reveal_precision)
+}
+
+pub fn compute_margin_call_buffer(
+    position: &Position,
+    current_price: u64,
+    projected_price: u64,
+    margin_call_health_bps: u64,
+    reveal_precision: u8,
+) -> u8 {
+    let buffer = margin_call_buffer(
+        position.collateral_usd,
+        position.debt_usd,
+        current_price,
+        projected_price,
+        margin_call_health_bps,
+    );
+
+    coarsen_bucket(magnitude_bucket(buffer), reveal_precision)
+}
+
+/// Histogram `compute_age_distribution` reveals for positions created at
+/// `created_at`; 0 marks a position without a creation time.
+pub fn compute_age_distribution(created_at: &[i64], current_time: i64, reveal_precision: u8) -> [u8; 4] {
+    let mut histogram = [0u8; 4];
+    for &created in created_at.iter().filter(|&&created| created > 0) {
+        histogram[coarsen_bucket(age_bucket(current_time - created), reveal_precision) as usize] += 1;
+    }
+    histogram
+}
+
+pub fn compute_fee_tier(position: &Position, thresholds_bps: [u64; 3]) -> u8 {
+    fee_tier(position.health_factor_bps, position.debt_usd, thresholds_bps)
+}
+
+/// Slot `match_book` selects from takers that all cross the resting order;
+/// `None` when there are none.
+pub fn match_book(takers: &[BookRank], size_priority: bool, resting_is_buy: bool) -> Option<usize> {
+    let mut selected: Option<usize> = None;
+    for (i, candidate) in takers.iter().enumerate() {
+        let outranks = match selected {
+            Some(best) => book_outranks(candidate, &takers[best], size_priority, resting_is_buy),
+            None => true,
+        };
+        if outranks {
+            selected = Some(i);
+        }
+    }
+    selected
+}
+
+/// Execution price `calculate_execution_price` matches at, or `None` when the
+/// orders don't cross or the midpoint is worse for the taker than the reference.
+pub fn calculate_execution_price(
+    buy_price: u64,
+    sell_price: u64,
+    price_tolerance_bps: u64,
+    reference_price: u64,
+    taker_is_buy: bool,
+    round_toward: u8,
+) -> Option<u64> {
+    let execution_price = midpoint_price(buy_price, sell_price, round_toward);
+    let is_matched = prices_cross(buy_price, sell_price, price_tolerance_bps)
+        && beats_reference_price(execution_price, reference_price, taker_is_buy);
+
+    if is_matched { Some(execution_price) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn liquidation_risk_reaches_max_level_under_impact() {
+        // 120% health minus a 1600 bps impact lands below the first band.
+        let position = Position::new(12_000, 10_000);
+        assert_eq!(position.health_factor_bps, 12_000);
+        assert_eq!(calculate_liquidation_risk(&position, 1_600, 500, 1), (4, true));
+        assert_eq!(calculate_liquidation_risk(&position, 0, 500, 1), (0, false));
+        assert_eq!(calculate_liquidation_risk(&position, 20_000, 500, 1), (4, true));
+    }
+
+    #[test]
+    fn leverage_saturates_without_collateral() {
+        assert_eq!(Position::new(0, 0).leverage_bps, 10_000);
+        assert_eq!(Position::new(0, 500).leverage_bps, u64::MAX);
+        assert_eq!(Position::new(10_000, 5_000).leverage_bps, 5_000);
+    }
+
+    #[test]
+    fn health_follows_the_oracle_price() {
+        let position = Position::new(15_000, 10_000);
+
+        // A long liquidated at 80 is at 125% with the price at 100.
+        let long = update_health_factor(position, 80, 100, false);
+        assert_eq!(long.health_factor_bps, 12_500);
+        assert_eq!(long.collateral_usd, 12_500);
+        assert_eq!(long.health_factor_bps, health_ratio_bps(long.collateral_usd, long.debt_usd));
+
+        // A short liquidated at 120 is at 120% with the price at 100.
+        let short = update_health_factor(position, 120, 100, true);
+        assert_eq!(short.health_factor_bps, 12_000);
+        assert_eq!(short.debt_usd, 12_500);
+
+        // At the liquidation price both sides sit exactly at the floor.
+        assert_eq!(update_health_factor(position, 100, 100, false).health_factor_bps, 10_000);
+        assert_eq!(update_health_factor(position, 100, 100, true).health_factor_bps, 10_000);
+        // Debt-free positions aren't repriced.
+        assert_eq!(update_health_factor(Position::new(1_000, 0), 80, 100, false), Position::new(1_000, 0));
+    }
+
+    #[test]
+    fn risk_band_boundaries() {
+        assert_eq!(risk_band(12_000, 500), 0);
+        assert_eq!(risk_band(11_999, 500), 1);
+        assert_eq!(risk_band(10_500, 500), 3);
+        assert_eq!(risk_band(10_499, 500), 4);
+    }
+
+    #[test]
+    fn risk_bands_scale_with_band_width() {
+        // Level `4 - n` starts at `10000 + n * width` whatever the width.
+        for width in [100, 500, 2_500] {
+            assert_eq!(risk_band(10_000 + 4 * width, width), 0);
+            assert_eq!(risk_band(10_000 + 4 * width - 1, width), 1);
+            assert_eq!(risk_band(10_000 + 2 * width, width), 2);
+            assert_eq!(risk_band(10_000 + width, width), 3);
+            assert_eq!(risk_band(10_000 + width - 1, width), 4);
+        }
+        // The same health lands in a safer band when the bands are narrower.
+        assert_eq!(risk_band(11_000, 100), 0);
+        assert_eq!(risk_band(11_000, 500), 2);
+        assert_eq!(risk_band(11_000, 2_500), 4);
+    }
+
+    #[test]
+    fn health_threshold_hysteresis() {
+        let position = Position::new(10_800, 10_000);
+        assert!(prove_health_threshold(&position, 11_000, 500, false, 0));
+        assert!(!prove_health_threshold(&position, 11_000, 500, true, 0));
+        assert!(!prove_health_threshold(&position, 11_000, 500, false, 400));
+    }
+
+    #[test]
+    fn price_tolerance_lets_near_prices_cross() {
+        assert!(prices_cross(100, 100, 0));
+        assert!(!prices_cross(99, 100, 0));
+        assert!(prices_cross(99, 100, 100));
+    }
+
+    #[test]
+    fn maker_rebate_survives_large_notionals_and_pays_whole_quanta() {
+        assert_eq!(maker_rebate(100, 1_000, 10), 100);
+        // A 10^10 * 10^10 notional overflows u64 before the bps are applied.
+        assert_eq!(maker_rebate(10_000_000_000, 10_000_000_000, 10), 100_000_000_000_000_000);
+        assert_eq!(maker_rebate(u64::MAX, u64::MAX, 10_000), u64::MAX);
+        assert_eq!(releasable_rebate(12_345, 10_000), 10_000);
+        assert_eq!(releasable_rebate(9_999, 10_000), 0);
+    }
+
+    #[test]
+    fn one_bps_gap_crosses_within_five_bps_tolerance() {
+        // 9_999 sits 1 bps under 10_000.
+        assert!(!prices_cross(9_999, 10_000, 0));
+        assert!(prices_cross(9_999, 10_000, 5));
+        // 9_994 is 6 bps under, just outside the tolerance.
+        assert!(!prices_cross(9_994, 10_000, 5));
+    }
+
+    #[test]
+    fn same_owner_orders_never_match() {
+        // Prices cross, so only the owner check stands between these and a fill.
+        let is_crossable = prices_cross(100, 100, 0);
+        assert_eq!(match_failure_reason(is_crossable, false, false), 0);
+        assert_eq!(match_failure_reason(is_crossable, true, false), 2);
+        // A self-trade is reported as such even when the prices don't cross.
+        assert_eq!(match_failure_reason(false, true, false), 2);
+    }
+
+    #[test]
+    fn odd_midpoint_rounds_per_mode() {
+        // 100 + 103 = 203, halfway between 101 and 102.
+        assert_eq!(midpoint_price(100, 103, 0), 101);
+        assert_eq!(midpoint_price(100, 103, 1), 102);
+        assert_eq!(midpoint_price(100, 103, 2), 102);
+        // 100 + 101 = 201, halfway between 100 and 101; nearest keeps the even tick.
+        assert_eq!(midpoint_price(100, 101, 2), 100);
+        // Even sums are exact in every mode.
+        assert_eq!(midpoint_price(100, 102, 0), 101);
+        assert_eq!(midpoint_price(100, 102, 1), 101);
+        assert_eq!(midpoint_price(100, 102, 2), 101);
+    }
+
+    #[test]
+    fn priority_modes_select_different_orders() {
+        let takers = [
+            BookRank { limit_price: 99, visible_amount: 10, sequence: 0, priority_fee: 0 },
+            BookRank { limit_price: 100, visible_amount: 50, sequence: 1, priority_fee: 0 },
+        ];
+        // A resting buy gets the cheaper seller under price-time priority and
+        // the larger one under size priority.
+        assert_eq!(match_book(&takers, false, true), Some(0));
+        assert_eq!(match_book(&takers, true, true), Some(1));
+        assert_eq!(match_book(&[], false, true), None);
+    }
+
+    #[test]
+    fn higher_fee_takes_a_same_price_resting_order() {
+        let takers = [
+            BookRank { limit_price: 100, visible_amount: 10, sequence: 0, priority_fee: 5 },
+            BookRank { limit_price: 100, visible_amount: 10, sequence: 1, priority_fee: 9 },
+            BookRank { limit_price: 99, visible_amount: 10, sequence: 2, priority_fee: 50 },
+        ];
+        // Against a resting sell the better price still comes first; the fee
+        // only breaks the tie at 100.
+        assert_eq!(match_book(&takers, false, false), Some(1));
+    }
+
+    #[test]
+    fn midpoint_worse_than_reference_is_rejected() {
+        // 95/105 crosses with a midpoint of 100.
+        assert_eq!(calculate_execution_price(105, 95, 0, 101, true, 0), Some(100));
+        // A buyer who could get 98 elsewhere isn't filled at 100.
+        assert_eq!(calculate_execution_price(105, 95, 0, 98, true, 0), None);
+        // Nor is a seller who could get 102 elsewhere.
+        assert_eq!(calculate_execution_price(105, 95, 0, 102, false, 0), None);
+        assert_eq!(calculate_execution_price(105, 95, 0, 100, false, 0), Some(100));
+    }
+
+    #[test]
+    fn reveal_precision_coarsens_buckets_alike() {
+        let healthy = [Position::new(16_000, 10_000)];
+        assert_eq!(compute_solvency_ratio(&healthy, 1), 5);
+        assert_eq!(compute_solvency_ratio(&healthy, 2), 2);
+
+        let position = Position::new(5_000_000, 2_000_000);
+        assert_eq!(compute_max_liquidatable(&position, 5_000, 1), 5);
+        assert_eq!(compute_max_liquidatable(&position, 5_000, 2), 2);
+
+        // Level 3 and the max share a bucket at precision 2, but the max
+        // flag still tells them apart.
+        let strained = Position::new(10_600, 10_000);
+        assert_eq!(calculate_liquidation_risk(&strained, 0, 500, 1), (3, false));
+        assert_eq!(calculate_liquidation_risk(&strained, 0, 500, 2), (1, false));
+        assert_eq!(calculate_liquidation_risk(&strained, 1_000, 500, 2), (2, true));
+
+        // 2M of debt at 200% needs 4M; after the price halves that is 3M short.
+        let margined = Position::new(2_000_000, 2_000_000);
+        assert_eq!(compute_margin_call_buffer(&margined, 100, 50, 20_000, 1), 5);
+        assert_eq!(compute_margin_call_buffer(&margined, 100, 50, 20_000, 2), 2);
+
+        let now = 10_000_000;
+        let created = [now - 3_600, now - 86_400 * 3, now - 86_400 * 10, now - 86_400 * 60, 0];
+        assert_eq!(compute_age_distribution(&created, now, 1), [1, 1, 1, 1]);
+        assert_eq!(compute_age_distribution(&created, now, 2), [2, 2, 0, 0]);
+        assert_eq!(compute_age_distribution(&created, now, 4), [4, 0, 0, 0]);
+    }
+
+    #[test]
+    fn weighted_score_ignores_debt_free_positions() {
+        let positions = [
+            Position::new(5_000, 10_000),
+            Position::new(0, 0),
+            Position::new(20_000, 10_000),
+        ];
+        // One shortfall of 6000 against a worst case of 110_000.
+        assert_eq!(batch_weighted_risk_score(&positions, 11_000, 1), 1);
+        assert_eq!(batch_weighted_risk_score(&positions[1..], 11_000, 1), 0);
+    }
+
+    #[test]
+    fn fee_tier_rises_as_health_falls() {
+        let thresholds = [20_000, 15_000, 11_000];
+        assert_eq!(compute_fee_tier(&Position::new(30_000, 10_000), thresholds), 0);
+        assert_eq!(compute_fee_tier(&Position::new(16_000, 10_000), thresholds), 1);
+        assert_eq!(compute_fee_tier(&Position::new(12_000, 10_000), thresholds), 2);
+        assert_eq!(compute_fee_tier(&Position::new(10_500, 10_000), thresholds), 3);
+        assert_eq!(compute_fee_tier(&Position::new(1_000, 0), thresholds), 0);
+    }
+}
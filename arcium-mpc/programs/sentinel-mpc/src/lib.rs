@@ -11,7 +11,7 @@
 
 use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
-use arcium_client::idl::arcium::types::CallbackAccount;
+use arcium_client::idl::arcium::types::{CallbackAccount, CircuitSource};
 
 declare_id!("SENTpLHjqfWKdZ8RUgjvzwYRNQ5cuEAXeNBFcYew7LD");
 
@@ -23,10 +23,237 @@ const COMP_DEF_OFFSET_MATCH_ORDERS: u32 = comp_def_offset("match_dark_pool_order
 const COMP_DEF_OFFSET_PRIVATE_SWAP: u32 = comp_def_offset("execute_private_swap");
 const COMP_DEF_OFFSET_BATCH_HEALTH: u32 = comp_def_offset("batch_health_check");
 const COMP_DEF_OFFSET_LIQUIDATION_RISK: u32 = comp_def_offset("calculate_liquidation_risk");
+const COMP_DEF_OFFSET_EXTEND_EXPIRY: u32 = comp_def_offset("extend_order_expiry");
+const COMP_DEF_OFFSET_INIT_MAKER_VOLUME: u32 = comp_def_offset("init_maker_volume");
+const COMP_DEF_OFFSET_VOLUME_TIER: u32 = comp_def_offset("prove_volume_tier");
+const COMP_DEF_OFFSET_PRICE_IN_BAND: u32 = comp_def_offset("prove_price_in_band");
+const COMP_DEF_OFFSET_CONVERT_STOP: u32 = comp_def_offset("convert_stop_to_market");
+const COMP_DEF_OFFSET_SOLVENCY_RATIO: u32 = comp_def_offset("compute_solvency_ratio");
+const COMP_DEF_OFFSET_INIT_MULTI_COLLATERAL: u32 = comp_def_offset("init_multi_collateral_position");
+const COMP_DEF_OFFSET_UPDATE_MULTI_COLLATERAL: u32 = comp_def_offset("update_multi_collateral_position");
+const COMP_DEF_OFFSET_WEIGHTED_HEALTH: u32 = comp_def_offset("prove_weighted_health");
+const COMP_DEF_OFFSET_SELECT_KEEPER: u32 = comp_def_offset("select_keeper");
+const COMP_DEF_OFFSET_AGGREGATE_LEVERAGE: u32 = comp_def_offset("prove_aggregate_leverage_bounded");
+const COMP_DEF_OFFSET_MULTI_MARKET: u32 = comp_def_offset("preview_multi_market");
+const COMP_DEF_OFFSET_COLLATERAL_COMPOSITION: u32 = comp_def_offset("prove_collateral_composition");
+const COMP_DEF_OFFSET_COLLATERAL_TOPUP: u32 = comp_def_offset("apply_collateral_topup");
+const COMP_DEF_OFFSET_RECORD_LIQUIDATION: u32 = comp_def_offset("record_liquidation");
+const COMP_DEF_OFFSET_PROVE_CLEAN_HISTORY: u32 = comp_def_offset("prove_clean_history");
+const COMP_DEF_OFFSET_PROVE_HEALTH_THRESHOLDS: u32 = comp_def_offset("prove_health_thresholds");
+const COMP_DEF_OFFSET_PREVIEW_MATCH: u32 = comp_def_offset("preview_match");
+const COMP_DEF_OFFSET_PROVE_PRICE_IMPROVEMENT: u32 = comp_def_offset("prove_price_improvement");
+const COMP_DEF_OFFSET_MIGRATE_POSITION_SCHEMA: u32 = comp_def_offset("migrate_position_schema");
+const COMP_DEF_OFFSET_MIGRATE_POSITION_SCHEMA_V1: u32 = comp_def_offset("migrate_position_schema_v1");
+const COMP_DEF_OFFSET_MIGRATE_POSITION_SCHEMA_V2: u32 = comp_def_offset("migrate_position_schema_v2");
+const COMP_DEF_OFFSET_STRESS_TEST_PORTFOLIO: u32 = comp_def_offset("stress_test_portfolio");
+const COMP_DEF_OFFSET_COMMIT_DELAYED_REVEAL: u32 = comp_def_offset("commit_delayed_reveal");
+const COMP_DEF_OFFSET_FINALIZE_DELAYED_REVEAL: u32 = comp_def_offset("finalize_delayed_reveal");
+const COMP_DEF_OFFSET_MATCH_BOOK: u32 = comp_def_offset("match_book");
+const COMP_DEF_OFFSET_BATCH_WEIGHTED_RISK_SCORE: u32 = comp_def_offset("batch_weighted_risk_score");
+const COMP_DEF_OFFSET_COMPUTE_MAX_LIQUIDATABLE: u32 = comp_def_offset("compute_max_liquidatable");
+const COMP_DEF_OFFSET_COMPARE_PROTOCOL_EXPOSURE: u32 = comp_def_offset("compare_protocol_exposure");
+const COMP_DEF_OFFSET_UPDATE_AND_PROVE_HEALTH: u32 = comp_def_offset("update_and_prove_health");
+const COMP_DEF_OFFSET_COMPUTE_AGE_DISTRIBUTION: u32 = comp_def_offset("compute_age_distribution");
+const COMP_DEF_OFFSET_HANDLE_EXPIRING_ORDER: u32 = comp_def_offset("handle_expiring_order");
+const COMP_DEF_OFFSET_PROVE_UNLEVERAGED: u32 = comp_def_offset("prove_unleveraged");
+const COMP_DEF_OFFSET_BATCH_INIT_POSITIONS: u32 = comp_def_offset("batch_init_encrypted_positions");
+const COMP_DEF_OFFSET_PROVE_MIN_COLLATERAL: u32 = comp_def_offset("prove_min_collateral");
+const COMP_DEF_OFFSET_PROVE_NO_FRONT_RUNNING: u32 = comp_def_offset("prove_no_front_running");
+const COMP_DEF_OFFSET_COMPUTE_RISK_ADJUSTED_SCORE: u32 = comp_def_offset("compute_risk_adjusted_score");
+const COMP_DEF_OFFSET_PROVE_UNCORRELATED: u32 = comp_def_offset("prove_uncorrelated");
+const COMP_DEF_OFFSET_PROVE_ARMS_LENGTH: u32 = comp_def_offset("prove_arms_length");
+const COMP_DEF_OFFSET_COMPUTE_FEE_TIER: u32 = comp_def_offset("compute_fee_tier");
+const COMP_DEF_OFFSET_PROVE_RISK_CAP_RESPECTED: u32 = comp_def_offset("prove_risk_cap_respected");
+const COMP_DEF_OFFSET_INIT_RISK_CAP_TRACKER: u32 = comp_def_offset("init_risk_cap_tracker");
+const COMP_DEF_OFFSET_AGGREGATE_PORTFOLIO_RISK: u32 = comp_def_offset("aggregate_portfolio_risk");
+const COMP_DEF_OFFSET_MATCH_AND_TOPUP: u32 = comp_def_offset("match_and_topup");
+const COMP_DEF_OFFSET_PROVE_REWARDS_ELIGIBLE: u32 = comp_def_offset("prove_rewards_eligible");
+const COMP_DEF_OFFSET_PROVE_MIN_COLLATERAL_DIVERSITY: u32 = comp_def_offset("prove_min_collateral_diversity");
+const COMP_DEF_OFFSET_PROVE_MIN_HEALTHY_COUNT: u32 = comp_def_offset("prove_min_healthy_count");
+const COMP_DEF_OFFSET_PROVE_POSITION_PARITY: u32 = comp_def_offset("prove_position_parity");
+const COMP_DEF_OFFSET_PROVE_ROUTE_OPTIMAL: u32 = comp_def_offset("prove_route_optimal");
+const COMP_DEF_OFFSET_ESTIMATE_CASCADE_RISK: u32 = comp_def_offset("estimate_cascade_risk");
+const COMP_DEF_OFFSET_INIT_ORDER_ACTIVITY: u32 = comp_def_offset("init_order_activity");
+const COMP_DEF_OFFSET_RECORD_ORDER_ACTIVITY: u32 = comp_def_offset("record_order_activity");
+const COMP_DEF_OFFSET_PROVE_NOT_SPOOFING: u32 = comp_def_offset("prove_not_spoofing");
+const COMP_DEF_OFFSET_PROVE_FLOOR_HELD: u32 = comp_def_offset("prove_floor_held");
+const COMP_DEF_OFFSET_PROVE_SWAP_LOW_IMPACT: u32 = comp_def_offset("prove_swap_low_impact");
+const COMP_DEF_OFFSET_PROVE_TOP_TIER: u32 = comp_def_offset("prove_top_tier");
+const COMP_DEF_OFFSET_AUTO_DELEVERAGE: u32 = comp_def_offset("apply_auto_deleverage");
+const COMP_DEF_OFFSET_PROVE_TERMS_MATCH: u32 = comp_def_offset("prove_terms_match");
+const COMP_DEF_OFFSET_PROVE_LIQUIDATION_COOLDOWN: u32 = comp_def_offset("prove_liquidation_cooldown_passed");
+const COMP_DEF_OFFSET_PROVE_HEALTH_WITH_FUNDING: u32 = comp_def_offset("prove_health_with_funding");
+const COMP_DEF_OFFSET_BOOK_IMBALANCE: u32 = comp_def_offset("compute_book_imbalance");
+const COMP_DEF_OFFSET_CONFIDENCE_SLIPPAGE: u32 = comp_def_offset("compute_slippage_from_confidence");
+const COMP_DEF_OFFSET_PROVE_UNDER_CREDIT_LINE: u32 = comp_def_offset("prove_under_credit_line");
+const COMP_DEF_OFFSET_REALIZED_VOLUME_TIER: u32 = comp_def_offset("prove_realized_volume_tier");
+const COMP_DEF_OFFSET_PROVE_CLEAN_SOURCE: u32 = comp_def_offset("prove_clean_source");
+const COMP_DEF_OFFSET_LIQUIDATION_RISK_WEIGHTED: u32 = comp_def_offset("calculate_liquidation_risk_weighted");
+const COMP_DEF_OFFSET_PROVE_PORTFOLIO_NEUTRAL: u32 = comp_def_offset("prove_portfolio_neutral");
+const COMP_DEF_OFFSET_PROVE_DEBT_DIVERSIFIED: u32 = comp_def_offset("prove_debt_diversified");
+const COMP_DEF_OFFSET_COMPUTE_MARGIN_CALL_BUFFER: u32 = comp_def_offset("compute_margin_call_buffer");
+const COMP_DEF_OFFSET_PROVE_NOT_ROUND_TRIP: u32 = comp_def_offset("prove_not_round_trip");
+const COMP_DEF_OFFSET_PROVE_SMOOTHED_HEALTH_THRESHOLD: u32 = comp_def_offset("prove_smoothed_health_threshold");
+const COMP_DEF_OFFSET_PROVE_POSITION_STALE: u32 = comp_def_offset("prove_position_stale");
+const COMP_DEF_OFFSET_CHECK_CONDITIONAL_TRIGGER: u32 = comp_def_offset("check_conditional_trigger");
+const COMP_DEF_OFFSET_PARTIAL_CLOSE_POSITION: u32 = comp_def_offset("partial_close_position");
 
 const SIGN_PDA_SEED: &[u8] = b"sentinel_sign";
 const POSITION_PDA_SEED: &[u8] = b"sentinel_position";
 const DARK_POOL_SEED: &[u8] = b"sentinel_dark_pool";
+const MAKER_VOLUME_SEED: &[u8] = b"sentinel_maker_volume";
+const PROTOCOL_STATE_SEED: &[u8] = b"sentinel_protocol_state";
+const PROTOCOL_CONFIG_SEED: &[u8] = b"sentinel_protocol_config";
+const KEEPER_REGISTRY_SEED: &[u8] = b"sentinel_keeper_registry";
+const ALERT_CONFIG_SEED: &[u8] = b"sentinel_alert_config";
+const TOPUP_AUTH_SEED: &[u8] = b"sentinel_topup_auth";
+const DELAYED_REVEAL_SEED: &[u8] = b"sentinel_delayed_reveal";
+const MATCH_SEQUENCE_SEED: &[u8] = b"sentinel_match_sequence";
+const SUBMITTED_ORDER_SEED: &[u8] = b"sentinel_submitted_order";
+const FEE_REBATE_POOL_SEED: &[u8] = b"sentinel_fee_rebate_pool";
+const BUNDLE_RECEIPT_SEED: &[u8] = b"sentinel_bundle_receipt";
+const REBATE_VAULT_SEED: &[u8] = b"sentinel_rebate_vault";
+const OWNER_REGISTRY_SEED: &[u8] = b"sentinel_owner_registry";
+const ORDER_ACTIVITY_SEED: &[u8] = b"sentinel_order_activity";
+const RISK_CAP_TRACKER_SEED: &[u8] = b"sentinel_risk_cap_tracker";
+const AUTO_DELEVERAGE_SEED: &[u8] = b"sentinel_auto_deleverage";
+const COMPUTATION_STATUS_SEED: &[u8] = b"sentinel_computation_status";
+const ENCRYPTED_STATE_SEED: &[u8] = b"sentinel_encrypted_state";
+const ORACLE_FEED_SEED: &[u8] = b"sentinel_oracle_feed";
+const SETTLEMENT_AUTHORITY_SEED: &[u8] = b"sentinel_settlement_authority";
+const SWAP_INTENT_SEED: &[u8] = b"sentinel_swap_intent";
+const MULTI_COLLATERAL_SEED: &[u8] = b"sentinel_multi_collateral";
+const PENDING_COMPUTATIONS_SEED: &[u8] = b"sentinel_pending_computations";
+
+const VOLUME_TIER_THRESHOLDS: [u64; 3] = [1_000_000_000, 10_000_000_000, 100_000_000_000];
+/// Length of the window realized maker volume is counted over.
+const REALIZED_VOLUME_WINDOW_SECONDS: i64 = 30 * 24 * 60 * 60;
+/// Lamports maker rebates are paid out in. The circuit keeps anything short of
+/// a whole quantum encrypted, so a payout doesn't reveal the fill's notional.
+const REBATE_RELEASE_QUANTUM: u64 = 100_000;
+
+const MAX_GUARDIANS: usize = 3;
+
+/// Position cap each owner starts with until the admin changes it.
+const DEFAULT_MAX_POSITIONS_PER_OWNER: u32 = 100;
+
+const MAX_BAND_WIDTH_BPS: u64 = 10000;
+const MAX_PRICE_TOLERANCE_BPS: u64 = 100;
+const MAX_MATCH_COOLDOWN_SECONDS: i64 = 3600;
+const MAX_ESCALATION_DELAY_SECONDS: i64 = 86400;
+/// Most adjacent buckets a bucketed reveal may merge into one.
+const MAX_REVEAL_PRECISION: u8 = 4;
+const MAX_WASH_WINDOW_SECONDS: i64 = 86400;
+const MAX_ORACLE_AGE_SECONDS: i64 = 3600;
+/// Retries an aborted computation gets before it's marked permanently failed.
+const MAX_COMPUTATION_RETRIES: u8 = 5;
+/// Wait after queueing before the first retry; doubles with each retry after that.
+const RETRY_BASE_BACKOFF_SECONDS: i64 = 30;
+/// Health below which `aggregate_portfolio_risk` counts a position as at risk.
+/// Fixed so an owner can't loosen it to keep their running max down.
+const RISK_CAP_THRESHOLD_BPS: u64 = 12000;
+/// Idle time after which `prove_position_stale` reports a position as reapable.
+const POSITION_STALE_TTL_SECONDS: i64 = 90 * 24 * 60 * 60;
+/// Computation kinds a protocol can put a fee surcharge on.
+const MAX_FEE_SURCHARGES: usize = 4;
+const MAX_FEE_SURCHARGE_BPS: u64 = 10000;
+
+/// `ProtocolConfig` slot holding the dark pool venue's settings.
+const DARK_POOL_CONFIG_ID: u8 = u8::MAX;
+
+/// `failure_reason` codes reported on `DarkPoolOrdersMatched`.
+pub const MATCH_FAILURE_NONE: u8 = 0;
+pub const MATCH_FAILURE_NOT_CROSSED: u8 = 1;
+pub const MATCH_FAILURE_SELF_TRADE: u8 = 2;
+pub const MATCH_FAILURE_FILL_OR_KILL: u8 = 3;
+
+/// Time-in-force codes for dark pool orders.
+pub const TIF_GOOD_TIL_CANCELLED: u8 = 0;
+pub const TIF_IMMEDIATE_OR_CANCEL: u8 = 1;
+pub const TIF_FILL_OR_KILL: u8 = 2;
+
+/// Which tick an odd-sum midpoint rounds to; buyer-favoring by default.
+pub const ROUND_TOWARD_BUYER: u8 = 0;
+pub const ROUND_TOWARD_SELLER: u8 = 1;
+pub const ROUND_TOWARD_NEAREST: u8 = 2;
+
+const MAX_COLLATERAL_LEGS: usize = 4;
+
+/// Field elements an `EncryptedState` can hold; covers `DarkPoolOrder`, the
+/// largest struct a callback persists.
+const MAX_ENCRYPTED_STATE_LEN: usize = 128;
+
+const MAX_KEEPERS: usize = 8;
+/// Slots a `select_keeper` assignment lasts (about a minute). Only one
+/// selection runs per window, so the draw can't be re-rolled within it.
+const KEEPER_ASSIGNMENT_SLOTS: u64 = 150;
+
+const MAX_BATCH_CANCEL: usize = 10;
+const MAX_BATCH_PROTOCOL_CONFIGS: usize = 8;
+
+const MAX_MULTI_MARKET_PAIRS: u8 = 3;
+const MAX_BOOK_DEPTH: usize = 5;
+/// Orders `compute_book_imbalance` reads in one computation.
+const MAX_IMBALANCE_ORDERS: usize = 10;
+const MAX_BATCH_POSITIONS: usize = 5;
+/// Correlated mint pairs `prove_uncorrelated` can check against.
+const MAX_CORRELATED_PAIRS: usize = 4;
+/// Position slots `prove_min_healthy_count` reads.
+const MAX_HEALTHY_COUNT_POSITIONS: u8 = 10;
+/// Peer slots in a `prove_top_tier` comparison.
+const MAX_TOP_TIER_PEERS: usize = 9;
+/// Fewest peers `prove_top_tier` compares against, so "top third" can't be
+/// won against a group of one or two.
+const MIN_TOP_TIER_PEERS: usize = 5;
+/// Slot reported by `match_book` when no resting order crosses the taker.
+pub const BOOK_NO_MATCH: u8 = u8::MAX;
+
+/// Conservative Arcium fee estimates per circuit tier, in lamports. Light covers
+/// single-value proofs and inits, medium covers two-order and multi-leg
+/// circuits, heavy covers the 10-position portfolio and multi-market batches.
+#[constant]
+pub const LIGHT_COMPUTATION_COST_LAMPORTS: u64 = 5_000_000;
+#[constant]
+pub const MEDIUM_COMPUTATION_COST_LAMPORTS: u64 = 10_000_000;
+#[constant]
+pub const HEAVY_COMPUTATION_COST_LAMPORTS: u64 = 25_000_000;
+
+const MAX_RISK_LEVEL: u8 = 4;
+
+/// Must match the `schema_version` written by the circuits. Bumped on every
+/// `EncryptedPosition` layout change; each older version has a migration.
+const CURRENT_POSITION_SCHEMA_VERSION: u8 = 3;
+
+/// Programs whose accounts may be registered as a price feed. Only layouts
+/// `pyth_field` can parse belong here.
+const RECOGNIZED_ORACLE_PROGRAMS: [Pubkey; 2] = [
+    anchor_lang::solana_program::pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH"), // Pyth legacy oracle
+    anchor_lang::solana_program::pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ"), // Pyth receiver
+];
+
+/// SPL Token program, for topups drawn from a delegated token account.
+const SPL_TOKEN_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// SPL Token `Transfer` instruction tag.
+const SPL_TOKEN_TRANSFER_TAG: u8 = 3;
+
+/// Byte offset of `timestamp` in a Pyth legacy price account.
+const PYTH_LEGACY_TIMESTAMP_OFFSET: usize = 96;
+/// `PriceUpdateV2` header before `verification_level`: discriminator + write authority.
+const PYTH_RECEIVER_HEADER_LEN: usize = 40;
+/// Byte offsets of the aggregate `price` and `conf` in a Pyth legacy price account.
+const PYTH_LEGACY_PRICE_OFFSET: usize = 208;
+const PYTH_LEGACY_CONF_OFFSET: usize = 216;
+/// Offsets of `price` and `conf` within a `PriceFeedMessage`, after the feed id.
+const PYTH_MESSAGE_PRICE_OFFSET: usize = 32;
+const PYTH_MESSAGE_CONF_OFFSET: usize = 40;
+/// Offset of `publish_time` within a `PriceFeedMessage`: feed id, price, conf, exponent.
+const PYTH_MESSAGE_PUBLISH_TIME_OFFSET: usize = 52;
+/// Ceiling on slippage widened by oracle confidence, in bps.
+pub const MAX_CONFIDENCE_SLIPPAGE_BPS: u64 = 500;
+
+/// How long before `expires_at` a keeper may convert an opted-in order to market.
+const EXPIRY_GRACE_WINDOW_SECONDS: i64 = 300;
 
 #[arcium_program]
 pub mod sentinel_mpc {
@@ -52,438 +279,15754 @@ pub mod sentinel_mpc {
         Ok(())
     }
 
-    pub fn init_encrypted_position(
-        ctx: Context<InitEncryptedPosition>,
-        computation_offset: u64,
-        position_id: [u8; 32],
-        protocol: u8,
-    ) -> Result<()> {
-        let args = vec![
-            Argument::PlaintextBytes32(position_id),
-            Argument::PlaintextU8(protocol),
-        ];
-
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    pub fn init_extend_expiry_comp_def(ctx: Context<InitExtendExpiryCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![InitEncryptedPositionCallback::callback_ix(&[])],
-        )?;
+    pub fn init_maker_volume_comp_def(ctx: Context<InitMakerVolumeCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "init_encrypted_position")]
-    pub fn init_encrypted_position_callback(
-        ctx: Context<InitEncryptedPositionCallback>,
-        output: ComputationOutputs<InitEncryptedPositionOutput>,
-    ) -> Result<()> {
-        let _position = match output {
-            ComputationOutputs::Success(InitEncryptedPositionOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    pub fn init_volume_tier_comp_def(ctx: Context<InitVolumeTierCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        emit!(PositionInitialized {
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    pub fn init_update_health_comp_def(ctx: Context<InitUpdateHealthCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    pub fn update_health_factor(
-        ctx: Context<UpdateHealthFactor>,
-        computation_offset: u64,
-        position_id: [u8; 32],
-    ) -> Result<()> {
-        let args = vec![
-            Argument::PlaintextBytes32(position_id),
-        ];
+    pub fn init_match_orders_comp_def(ctx: Context<InitMatchOrdersCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    pub fn init_batch_health_comp_def(ctx: Context<InitBatchHealthCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![UpdateHealthFactorCallback::callback_ix(&[])],
-        )?;
+    pub fn init_liquidation_risk_comp_def(ctx: Context<InitLiquidationRiskCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "update_health_factor")]
-    pub fn update_health_factor_callback(
-        ctx: Context<UpdateHealthFactorCallback>,
-        output: ComputationOutputs<UpdateHealthFactorOutput>,
-    ) -> Result<()> {
-        let _health = match output {
-            ComputationOutputs::Success(UpdateHealthFactorOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    pub fn init_price_in_band_comp_def(ctx: Context<InitPriceInBandCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        emit!(HealthFactorUpdated {
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    pub fn init_convert_stop_comp_def(ctx: Context<InitConvertStopCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    pub fn prove_health_threshold(
-        ctx: Context<ProveHealthThreshold>,
-        computation_offset: u64,
-        threshold_bps: u64,
-    ) -> Result<()> {
-        let args = vec![
-            Argument::PlaintextU64(threshold_bps),
-        ];
+    pub fn init_solvency_ratio_comp_def(ctx: Context<InitSolvencyRatioCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    pub fn init_multi_collateral_comp_def(ctx: Context<InitMultiCollateralCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![ProveHealthThresholdCallback::callback_ix(&[])],
-        )?;
+    pub fn init_update_multi_collateral_comp_def(ctx: Context<InitUpdateMultiCollateralCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "prove_health_threshold")]
-    pub fn prove_health_threshold_callback(
-        ctx: Context<ProveHealthThresholdCallback>,
-        output: ComputationOutputs<ProveHealthThresholdOutput>,
-    ) -> Result<()> {
-        let is_healthy = match output {
-            ComputationOutputs::Success(ProveHealthThresholdOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    pub fn init_weighted_health_comp_def(ctx: Context<InitWeightedHealthCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        emit!(HealthThresholdProved {
-            is_healthy,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    pub fn init_select_keeper_comp_def(ctx: Context<InitSelectKeeperCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    pub fn init_dark_pool_order(
-        ctx: Context<InitDarkPoolOrder>,
-        computation_offset: u64,
-        order_id: [u8; 32],
-        side: u8,
-        expires_at: i64,
-    ) -> Result<()> {
-        let args = vec![
-            Argument::PlaintextBytes32(order_id),
-            Argument::PlaintextU8(side),
-            Argument::PlaintextI64(expires_at),
-        ];
+    pub fn init_aggregate_leverage_comp_def(ctx: Context<InitAggregateLeverageCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    pub fn init_multi_market_comp_def(ctx: Context<InitMultiMarketCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![InitDarkPoolOrderCallback::callback_ix(&[])],
-        )?;
+    pub fn init_collateral_composition_comp_def(ctx: Context<InitCollateralCompositionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "init_dark_pool_order")]
-    pub fn init_dark_pool_order_callback(
-        ctx: Context<InitDarkPoolOrderCallback>,
-        output: ComputationOutputs<InitDarkPoolOrderOutput>,
-    ) -> Result<()> {
-        let _order = match output {
-            ComputationOutputs::Success(InitDarkPoolOrderOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    pub fn init_collateral_topup_comp_def(ctx: Context<InitCollateralTopupCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        emit!(DarkPoolOrderCreated {
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    pub fn init_record_liquidation_comp_def(ctx: Context<InitRecordLiquidationCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    pub fn match_dark_pool_orders(
-        ctx: Context<MatchDarkPoolOrders>,
-        computation_offset: u64,
-        buy_order_id: [u8; 32],
-        sell_order_id: [u8; 32],
-    ) -> Result<()> {
-        let args = vec![
-            Argument::PlaintextBytes32(buy_order_id),
-            Argument::PlaintextBytes32(sell_order_id),
-        ];
+    pub fn init_prove_clean_history_comp_def(ctx: Context<InitProveCleanHistoryCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    pub fn init_prove_health_thresholds_comp_def(ctx: Context<InitProveHealthThresholdsCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![MatchDarkPoolOrdersCallback::callback_ix(&[])],
-        )?;
+    pub fn init_preview_match_comp_def(ctx: Context<InitPreviewMatchCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "match_dark_pool_orders")]
-    pub fn match_dark_pool_orders_callback(
-        ctx: Context<MatchDarkPoolOrdersCallback>,
-        output: ComputationOutputs<MatchDarkPoolOrdersOutput>,
-    ) -> Result<()> {
-        let match_result = match output {
-            ComputationOutputs::Success(MatchDarkPoolOrdersOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    pub fn init_prove_price_improvement_comp_def(ctx: Context<InitProvePriceImprovementCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        emit!(DarkPoolOrdersMatched {
-            is_matched: match_result,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    pub fn init_migrate_position_schema_comp_def(ctx: Context<InitMigratePositionSchemaCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    pub fn execute_private_swap(
-        ctx: Context<ExecutePrivateSwap>,
-        computation_offset: u64,
-        intent_id: [u8; 32],
-        max_slippage_bps: u64,
-    ) -> Result<()> {
-        let args = vec![
-            Argument::PlaintextBytes32(intent_id),
-            Argument::PlaintextU64(max_slippage_bps),
-        ];
+    pub fn init_migrate_position_schema_v1_comp_def(ctx: Context<InitMigratePositionSchemaV1CompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+    pub fn init_migrate_position_schema_v2_comp_def(ctx: Context<InitMigratePositionSchemaV2CompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![ExecutePrivateSwapCallback::callback_ix(&[])],
-        )?;
+    pub fn init_stress_test_portfolio_comp_def(ctx: Context<InitStressTestPortfolioCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "execute_private_swap")]
-    pub fn execute_private_swap_callback(
-        ctx: Context<ExecutePrivateSwapCallback>,
-        output: ComputationOutputs<ExecutePrivateSwapOutput>,
-    ) -> Result<()> {
-        let swap_success = match output {
-            ComputationOutputs::Success(ExecutePrivateSwapOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
-        };
+    pub fn init_commit_delayed_reveal_comp_def(ctx: Context<InitCommitDelayedRevealCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
 
-        emit!(PrivateSwapExecuted {
-            success: swap_success,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    pub fn init_finalize_delayed_reveal_comp_def(ctx: Context<InitFinalizeDelayedRevealCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
         Ok(())
     }
 
-    pub fn batch_health_check(
-        ctx: Context<BatchHealthCheck>,
-        computation_offset: u64,
-        position_count: u8,
-    ) -> Result<()> {
-        let args = vec![
-            Argument::PlaintextU8(position_count),
-        ];
+    pub fn init_match_book_comp_def(ctx: Context<InitMatchBookCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_batch_weighted_risk_score_comp_def(ctx: Context<InitBatchWeightedRiskScoreCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_compute_max_liquidatable_comp_def(ctx: Context<InitComputeMaxLiquidatableCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_compare_protocol_exposure_comp_def(ctx: Context<InitCompareProtocolExposureCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_update_and_prove_health_comp_def(ctx: Context<InitUpdateAndProveHealthCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_compute_age_distribution_comp_def(ctx: Context<InitComputeAgeDistributionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_handle_expiring_order_comp_def(ctx: Context<InitHandleExpiringOrderCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_unleveraged_comp_def(ctx: Context<InitProveUnleveragedCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_batch_init_positions_comp_def(ctx: Context<InitBatchInitPositionsCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_min_collateral_comp_def(ctx: Context<InitProveMinCollateralCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_no_front_running_comp_def(ctx: Context<InitProveNoFrontRunningCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_compute_risk_adjusted_score_comp_def(ctx: Context<InitComputeRiskAdjustedScoreCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_uncorrelated_comp_def(ctx: Context<InitProveUncorrelatedCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_arms_length_comp_def(ctx: Context<InitProveArmsLengthCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_compute_fee_tier_comp_def(ctx: Context<InitComputeFeeTierCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_risk_cap_respected_comp_def(ctx: Context<InitProveRiskCapRespectedCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_risk_cap_tracker_comp_def(ctx: Context<InitInitRiskCapTrackerCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_aggregate_portfolio_risk_comp_def(ctx: Context<InitAggregatePortfolioRiskCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_match_and_topup_comp_def(ctx: Context<InitMatchAndTopupCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_rewards_eligible_comp_def(ctx: Context<InitProveRewardsEligibleCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_min_collateral_diversity_comp_def(ctx: Context<InitProveMinCollateralDiversityCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_min_healthy_count_comp_def(ctx: Context<InitProveMinHealthyCountCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_position_parity_comp_def(ctx: Context<InitProvePositionParityCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_route_optimal_comp_def(ctx: Context<InitProveRouteOptimalCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_estimate_cascade_risk_comp_def(ctx: Context<InitEstimateCascadeRiskCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_order_activity_comp_def(ctx: Context<InitInitOrderActivityCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_record_order_activity_comp_def(ctx: Context<InitRecordOrderActivityCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_not_spoofing_comp_def(ctx: Context<InitProveNotSpoofingCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_floor_held_comp_def(ctx: Context<InitProveFloorHeldCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_swap_low_impact_comp_def(ctx: Context<InitProveSwapLowImpactCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_top_tier_comp_def(ctx: Context<InitProveTopTierCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_auto_deleverage_comp_def(ctx: Context<InitAutoDeleverageCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_terms_match_comp_def(ctx: Context<InitProveTermsMatchCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_liquidation_cooldown_comp_def(ctx: Context<InitProveLiquidationCooldownCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_health_with_funding_comp_def(ctx: Context<InitProveHealthWithFundingCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_book_imbalance_comp_def(ctx: Context<InitComputeBookImbalanceCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_confidence_slippage_comp_def(ctx: Context<InitComputeSlippageFromConfidenceCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_under_credit_line_comp_def(ctx: Context<InitProveUnderCreditLineCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_realized_volume_tier_comp_def(ctx: Context<InitProveRealizedVolumeTierCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_clean_source_comp_def(ctx: Context<InitProveCleanSourceCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_liquidation_risk_weighted_comp_def(ctx: Context<InitCalculateLiquidationRiskWeightedCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_portfolio_neutral_comp_def(ctx: Context<InitProvePortfolioNeutralCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_debt_diversified_comp_def(ctx: Context<InitProveDebtDiversifiedCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_compute_margin_call_buffer_comp_def(ctx: Context<InitComputeMarginCallBufferCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_not_round_trip_comp_def(ctx: Context<InitProveNotRoundTripCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_smoothed_health_threshold_comp_def(ctx: Context<InitProveSmoothedHealthThresholdCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_prove_position_stale_comp_def(ctx: Context<InitProvePositionStaleCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_check_conditional_trigger_comp_def(ctx: Context<InitCheckConditionalTriggerCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_partial_close_position_comp_def(ctx: Context<InitPartialClosePositionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, true, 0, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_encrypted_position(
+        ctx: Context<InitEncryptedPosition>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        protocol: u8,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU8(protocol),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
+        ];
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        let owner_registry = &mut ctx.accounts.owner_registry;
+        owner_registry.owner = ctx.accounts.payer.key();
+        owner_registry.bump = ctx.bumps.owner_registry;
+        owner_registry.reserve(1, ctx.accounts.protocol_state.max_positions_per_owner)?;
+
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.owner = ctx.accounts.payer.key();
+        position_account.position_id = position_id;
+        position_account.protocol_id = protocol;
+        position_account.guardians = [Pubkey::default(); MAX_GUARDIANS];
+        position_account.guardian_threshold = 0;
+        position_account.last_risk_level = 0;
+        position_account.risk_updated_at = 0;
+        position_account.schema_version = CURRENT_POSITION_SCHEMA_VERSION;
+        position_account.oracle = ctx.accounts.oracle_feed.oracle;
+        position_account.risk_flagged_at = 0;
+        position_account.liquidatable_after = 0;
+        position_account.fee_tier = 0;
+        position_account.refresh_health_on_settle = false;
+        position_account.health_refresh_pending = false;
+        position_account.bump = ctx.bumps.position_account;
 
         queue_computation(
             ctx.accounts,
             computation_offset,
             args,
             None,
-            vec![BatchHealthCheckCallback::callback_ix(&[])],
+            vec![InitEncryptedPositionCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
         )?;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "batch_health_check")]
-    pub fn batch_health_check_callback(
-        ctx: Context<BatchHealthCheckCallback>,
-        output: ComputationOutputs<BatchHealthCheckOutput>,
+    #[arcium_callback(encrypted_ix = "init_encrypted_position")]
+    pub fn init_encrypted_position_callback(
+        ctx: Context<InitEncryptedPositionCallback>,
+        output: ComputationOutputs<InitEncryptedPositionOutput>,
     ) -> Result<()> {
-        let at_risk_count = match output {
-            ComputationOutputs::Success(BatchHealthCheckOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let _position = match output {
+            ComputationOutputs::Success(InitEncryptedPositionOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
         };
 
-        emit!(BatchHealthChecked {
-            at_risk_count,
+        emit!(PositionInitialized {
             timestamp: Clock::get()?.unix_timestamp,
         });
         Ok(())
     }
 
-    pub fn calculate_liquidation_risk(
-        ctx: Context<CalculateLiquidationRisk>,
+    /// Reprices the position against its bound feed. The caller supplies
+    /// nothing but the position, so anyone may refresh it.
+    pub fn update_health_factor(
+        ctx: Context<UpdateHealthFactor>,
         computation_offset: u64,
         position_id: [u8; 32],
-        price_impact_bps: u64,
     ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.protocol_config.max_oracle_age_seconds,
+        )?;
+
+        // The operation fee follows the position's last revealed fee tier and
+        // backs maker rebates like any other collected fee.
+        let fee = ctx
+            .accounts
+            .protocol_config
+            .fee_schedule
+            .fee_for_tier(ctx.accounts.position_account.fee_tier);
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.rebate_vault,
+            &ctx.accounts.system_program,
+            fee,
+        )?;
+
+        let (oracle_price, _) = oracle_price_and_confidence(&ctx.accounts.oracle)?;
+
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+        ctx.accounts.computation_status.begin(
+            ComputationKind::UpdateHealth,
+            position_id,
+            ctx.bumps.computation_status,
+            Clock::get()?.unix_timestamp,
+        )?;
+
         let args = vec![
             Argument::PlaintextBytes32(position_id),
-            Argument::PlaintextU64(price_impact_bps),
+            Argument::PlaintextU64(oracle_price),
+            Argument::PlaintextBytes32(ctx.accounts.oracle_feed.mint.to_bytes()),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.ema_smoothing_bps),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
         ];
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
 
         queue_computation(
             ctx.accounts,
             computation_offset,
             args,
             None,
-            vec![CalculateLiquidationRiskCallback::callback_ix(&[])],
+            vec![UpdateHealthFactorCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_account.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.computation_status.key(),
+                    is_writable: true,
+                },
+            ])],
         )?;
         Ok(())
     }
 
-    #[arcium_callback(encrypted_ix = "calculate_liquidation_risk")]
-    pub fn calculate_liquidation_risk_callback(
-        ctx: Context<CalculateLiquidationRiskCallback>,
-        output: ComputationOutputs<CalculateLiquidationRiskOutput>,
+    #[arcium_callback(encrypted_ix = "update_health_factor")]
+    pub fn update_health_factor_callback(
+        ctx: Context<UpdateHealthFactorCallback>,
+        output: ComputationOutputs<UpdateHealthFactorOutput>,
     ) -> Result<()> {
-        let risk_level = match output {
-            ComputationOutputs::Success(CalculateLiquidationRiskOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (position, feed_matches) = match output {
+            ComputationOutputs::Success(UpdateHealthFactorOutput {
+                field_0: UpdateHealthFactorOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
         };
+        if !feed_matches {
+            return reject_callback(ErrorCode::OracleMintMismatch);
+        }
 
-        emit!(LiquidationRiskCalculated {
-            risk_level,
+        ctx.accounts
+            .position_state
+            .store(position.nonce, &position.ciphertexts)?;
+        ctx.accounts.computation_status.complete();
+
+        // An aborted follow-up leaves the flag set so a keeper can retry it.
+        ctx.accounts.position_account.health_refresh_pending = false;
+
+        emit!(HealthFactorUpdated {
             timestamp: Clock::get()?.unix_timestamp,
         });
         Ok(())
     }
-}
-
 
-#[event]
-pub struct PositionInitialized {
-    pub timestamp: i64,
-}
+    pub fn prove_health_threshold(
+        ctx: Context<ProveHealthThreshold>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        threshold_bps: u64,
+        safety_buffer_bps: Option<u64>,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.protocol_config.max_oracle_age_seconds,
+        )?;
+
+        let safety_buffer_bps = safety_buffer_bps.unwrap_or(0);
+        require!(safety_buffer_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let alert_config = &ctx.accounts.alert_config;
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(threshold_bps),
+            Argument::PlaintextU64(alert_config.hysteresis_bps),
+            Argument::PlaintextBool(alert_config.triggered),
+            Argument::PlaintextU64(safety_buffer_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveHealthThresholdCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.alert_config.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_health_threshold")]
+    pub fn prove_health_threshold_callback(
+        ctx: Context<ProveHealthThresholdCallback>,
+        output: ComputationOutputs<ProveHealthThresholdOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let is_healthy = match output {
+            ComputationOutputs::Success(ProveHealthThresholdOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts.alert_config.triggered = !is_healthy;
+
+        emit!(HealthThresholdProved {
+            is_healthy,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn init_dark_pool_order(
+        ctx: Context<InitDarkPoolOrder>,
+        computation_offset: u64,
+        order_id: [u8; 32],
+        side: u8,
+        expires_at: i64,
+        order_hash: [u8; 32],
+        tif: u8,
+        conditional: bool,
+        priority_fee: u64,
+        trigger_position_id: [u8; 32],
+        trigger_health_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(tif <= TIF_FILL_OR_KILL, ErrorCode::InvalidTimeInForce);
+        // A zero trigger level can never be crossed, so a conditional order
+        // needs both fields; anything else takes neither.
+        let has_trigger = trigger_position_id != [0u8; 32] && trigger_health_bps > 0;
+        let no_trigger = trigger_position_id == [0u8; 32] && trigger_health_bps == 0;
+        require!(
+            if conditional { has_trigger } else { no_trigger },
+            ErrorCode::InvalidConditionalTrigger
+        );
+
+        let submitted_order = &mut ctx.accounts.submitted_order;
+        require!(submitted_order.order_id == [0u8; 32], ErrorCode::DuplicateOrder);
+        submitted_order.order_hash = order_hash;
+        submitted_order.order_id = order_id;
+        submitted_order.bump = ctx.bumps.submitted_order;
+
+        let args = vec![
+            Argument::PlaintextBytes32(order_id),
+            Argument::PlaintextU8(side),
+            Argument::PlaintextI64(expires_at),
+            Argument::PlaintextBytes32(ctx.accounts.payer.key().to_bytes()),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
+            Argument::PlaintextU8(tif),
+            Argument::PlaintextBytes32(trigger_position_id),
+            Argument::PlaintextU64(trigger_health_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        let dark_pool_state = &mut ctx.accounts.dark_pool_state;
+        dark_pool_state.bump = ctx.bumps.dark_pool_state;
+        let sequence = dark_pool_state.next_sequence;
+        dark_pool_state.next_sequence = sequence + 1;
+
+        let order_account = &mut ctx.accounts.order_account;
+        order_account.owner = ctx.accounts.payer.key();
+        order_account.order_id = order_id;
+        order_account.sequence = sequence;
+        order_account.expires_at = expires_at;
+        order_account.order_hash = order_hash;
+        order_account.last_match_attempt = 0;
+        order_account.unsettled_fill_amount = 0;
+        order_account.unsettled_execution_price = 0;
+        order_account.unsettled_counterparty = [0u8; 32];
+        order_account.last_counterparty = [0u8; 32];
+        order_account.tif = tif;
+        order_account.conditional = conditional;
+        order_account.trigger_fired = false;
+        order_account.priority_fee = priority_fee;
+        order_account.refresh_position_id = [0u8; 32];
+        order_account.bump = ctx.bumps.order_account;
+
+        // The fee is escrowed on the order up front so match_book can rank on
+        // it and the taker's fill can collect it in full.
+        if priority_fee > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: ctx.accounts.order_account.to_account_info(),
+                    },
+                ),
+                priority_fee,
+            )?;
+        }
+
+        ctx.accounts.order_activity.pending_placements += 1;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitDarkPoolOrderCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_dark_pool_order")]
+    pub fn init_dark_pool_order_callback(
+        ctx: Context<InitDarkPoolOrderCallback>,
+        output: ComputationOutputs<InitDarkPoolOrderOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let _order = match output {
+            ComputationOutputs::Success(InitDarkPoolOrderOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(DarkPoolOrderCreated {
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn match_dark_pool_orders(
+        ctx: Context<MatchDarkPoolOrders>,
+        computation_offset: u64,
+        buy_order_id: [u8; 32],
+        sell_order_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        // The resting (earlier) order is the maker; its volume is credited
+        // inside the same computation as the match so it can't be inflated.
+        let maker = if ctx.accounts.buy_order.sequence < ctx.accounts.sell_order.sequence {
+            ctx.accounts.buy_order.owner
+        } else {
+            ctx.accounts.sell_order.owner
+        };
+        require_keys_eq!(ctx.accounts.maker_volume.maker, maker, ErrorCode::Unauthorized);
+        require!(
+            ctx.accounts.buy_order.unsettled_fill_amount == 0,
+            ErrorCode::SettlementPending
+        );
+
+        let caller = ctx.accounts.payer.key();
+        require_priority_fee_consent(&ctx.accounts.buy_order, &ctx.accounts.sell_order, &caller)?;
+
+        // Throttle repeated attempts with the caller's own orders; a
+        // successful match clears the timestamp in the callback.
+        let now = Clock::get()?.unix_timestamp;
+        let cooldown = ctx.accounts.dark_pool_config.match_cooldown_seconds;
+        for order in [&mut ctx.accounts.buy_order, &mut ctx.accounts.sell_order] {
+            require!(order.is_matchable(), ErrorCode::ConditionalOrderNotTriggered);
+            order.stamp_match_attempt(&caller, now, cooldown)?;
+        }
+
+        // Roll the realized-volume window before this match's fill lands in
+        // it. The new start is only recorded once the callback stores the
+        // reset volume.
+        let maker_volume = &mut ctx.accounts.maker_volume;
+        let reset_realized_window =
+            now >= maker_volume.window_started_at + REALIZED_VOLUME_WINDOW_SECONDS;
+        maker_volume.pending_window_start = if reset_realized_window { now } else { 0 };
+
+        ctx.accounts
+            .volume_state
+            .bind(ctx.accounts.maker_volume.key(), ctx.bumps.volume_state);
+        ctx.accounts
+            .buy_order_state
+            .bind(ctx.accounts.buy_order.key(), ctx.bumps.buy_order_state);
+        ctx.accounts
+            .sell_order_state
+            .bind(ctx.accounts.sell_order.key(), ctx.bumps.sell_order_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(buy_order_id),
+            Argument::PlaintextBytes32(sell_order_id),
+            Argument::PlaintextBytes32(maker.to_bytes()),
+            Argument::PlaintextU64(ctx.accounts.dark_pool_config.price_tolerance_bps),
+            Argument::PlaintextU8(ctx.accounts.dark_pool_config.midpoint_round_toward),
+            Argument::PlaintextU64(ctx.accounts.dark_pool_config.maker_rebate_bps),
+            Argument::PlaintextU64(REBATE_RELEASE_QUANTUM),
+            Argument::PlaintextBool(ctx.accounts.dark_pool_config.settlement_enabled),
+            Argument::PlaintextBool(reset_realized_window),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+        ctx.accounts.match_sequence_counter.bump = ctx.bumps.match_sequence_counter;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![MatchDarkPoolOrdersCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.maker_volume.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.volume_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.match_sequence_counter.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.buy_order.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.sell_order.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.rebate_vault.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.buy_activity.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.sell_activity.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.buy_order_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.sell_order_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.treasury.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.buy_marker.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.sell_marker.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "match_dark_pool_orders")]
+    pub fn match_dark_pool_orders_callback(
+        ctx: Context<MatchDarkPoolOrdersCallback>,
+        output: ComputationOutputs<MatchDarkPoolOrdersOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (
+            match_result,
+            failure_reason,
+            maker_volume,
+            rebate,
+            buy_order,
+            sell_order,
+            buy_replenished,
+            sell_replenished,
+            fill_amount,
+            execution_price,
+            buy_exhausted,
+            sell_exhausted,
+        ) = match output {
+            ComputationOutputs::Success(MatchDarkPoolOrdersOutput {
+                field_0:
+                    MatchDarkPoolOrdersOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                        field_6,
+                        field_7,
+                        field_8,
+                        field_9,
+                        field_10,
+                        field_11,
+                    },
+            }) => (
+                field_0, field_1, field_2, field_3, field_4, field_5, field_6, field_7, field_8,
+                field_9, field_10, field_11,
+            ),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        // Volume and the unreleased rebate remainder carry over to the next
+        // match. Fills, replenished iceberg slices and cancelled IOC
+        // remainders only stick once the orders are written back.
+        ctx.accounts
+            .volume_state
+            .store(maker_volume.nonce, &maker_volume.ciphertexts)?;
+        ctx.accounts
+            .buy_order_state
+            .store(buy_order.nonce, &buy_order.ciphertexts)?;
+        ctx.accounts
+            .sell_order_state
+            .store(sell_order.nonce, &sell_order.ciphertexts)?;
+
+        record_match(
+            MatchParties {
+                buy_order: &mut ctx.accounts.buy_order,
+                sell_order: &mut ctx.accounts.sell_order,
+                buy_activity: &mut ctx.accounts.buy_activity,
+                sell_activity: &mut ctx.accounts.sell_activity,
+                maker_volume: &mut ctx.accounts.maker_volume,
+                rebate_vault: &mut ctx.accounts.rebate_vault,
+                match_sequence_counter: &mut ctx.accounts.match_sequence_counter,
+            },
+            &MatchOutcome {
+                is_matched: match_result,
+                failure_reason,
+                rebate,
+                buy_replenished,
+                sell_replenished,
+                fill_amount,
+                execution_price,
+            },
+            Clock::get()?.unix_timestamp,
+        );
+        if match_result {
+            collect_priority_fee(
+                &mut ctx.accounts.buy_order,
+                &mut ctx.accounts.sell_order,
+                &ctx.accounts.treasury,
+            )?;
+        }
+        release_exhausted_markers(
+            ctx.program_id,
+            [
+                (&ctx.accounts.buy_order, &ctx.accounts.buy_marker, buy_exhausted),
+                (&ctx.accounts.sell_order, &ctx.accounts.sell_marker, sell_exhausted),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn execute_private_swap(
+        ctx: Context<ExecutePrivateSwap>,
+        computation_offset: u64,
+        intent_id: [u8; 32],
+        actual_output: u64,
+        base_slippage_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_oracle_fresh(&ctx.accounts.oracle, MAX_ORACLE_AGE_SECONDS)?;
+        let (oracle_price, confidence) = oracle_price_and_confidence(&ctx.accounts.oracle)?;
+
+        let swap_intent = &mut ctx.accounts.swap_intent;
+        swap_intent.intent_id = intent_id;
+        swap_intent.bump = ctx.bumps.swap_intent;
+        ctx.accounts
+            .intent_state
+            .bind(ctx.accounts.swap_intent.key(), ctx.bumps.intent_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(intent_id),
+            Argument::PlaintextU64(actual_output),
+            Argument::PlaintextU64(base_slippage_bps),
+            Argument::PlaintextU64(oracle_price),
+            Argument::PlaintextU64(confidence),
+            Argument::PlaintextU64(MAX_CONFIDENCE_SLIPPAGE_BPS),
+            Argument::PlaintextBytes32(ctx.accounts.oracle_feed.mint.to_bytes()),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ExecutePrivateSwapCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.swap_intent.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.intent_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "execute_private_swap")]
+    pub fn execute_private_swap_callback(
+        ctx: Context<ExecutePrivateSwapCallback>,
+        output: ComputationOutputs<ExecutePrivateSwapOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (intent, swap_success, remaining_bucket, executed_output, feed_matches) = match output {
+            ComputationOutputs::Success(ExecutePrivateSwapOutput {
+                field_0:
+                    ExecutePrivateSwapOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                    },
+            }) => (field_0, field_1, field_2, field_3, field_4),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+        if !feed_matches {
+            return reject_callback(ErrorCode::OracleMintMismatch);
+        }
+
+        // A partial fill leaves the reduced intent for the next execution; a
+        // full fill leaves it with nothing left to swap.
+        ctx.accounts
+            .intent_state
+            .store(intent.nonce, &intent.ciphertexts)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        // `prove_route_optimal` checks the latest execution against this output.
+        if swap_success {
+            let swap_intent = &mut ctx.accounts.swap_intent;
+            swap_intent.last_output = executed_output;
+            swap_intent.last_executed_at = timestamp;
+        }
+        emit!(PrivateSwapExecuted {
+            success: swap_success,
+            timestamp,
+        });
+        // A partial fill leaves a reduced intent for later executions.
+        if swap_success && remaining_bucket > 0 {
+            emit!(PartialSwapExecuted {
+                remaining_bucket,
+                timestamp,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn batch_health_check(
+        ctx: Context<BatchHealthCheck>,
+        computation_offset: u64,
+        position_count: u8,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextU8(position_count),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![BatchHealthCheckCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "batch_health_check")]
+    pub fn batch_health_check_callback(
+        ctx: Context<BatchHealthCheckCallback>,
+        output: ComputationOutputs<BatchHealthCheckOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let at_risk_count = match output {
+            ComputationOutputs::Success(BatchHealthCheckOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(BatchHealthChecked {
+            at_risk_count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Permissionless: the price impact comes from the protocol config and the
+    /// feed is the one bound to the position, so the risk level it writes can't
+    /// be steered by whoever calls it.
+    pub fn calculate_liquidation_risk(
+        ctx: Context<CalculateLiquidationRisk>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.protocol_config.max_oracle_age_seconds,
+        )?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.liquidation_price_impact_bps),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.band_width_bps),
+            Argument::PlaintextU8(ctx.accounts.protocol_config.reveal_precision),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CalculateLiquidationRiskCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_account.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.protocol_config.key(),
+                    is_writable: false,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "calculate_liquidation_risk")]
+    pub fn calculate_liquidation_risk_callback(
+        ctx: Context<CalculateLiquidationRiskCallback>,
+        output: ComputationOutputs<CalculateLiquidationRiskOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (risk_level, at_max_risk) = match output {
+            ComputationOutputs::Success(CalculateLiquidationRiskOutput {
+                field_0: CalculateLiquidationRiskOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        let timestamp = Clock::get()?.unix_timestamp;
+
+        let escalation_delay = ctx.accounts.protocol_config.escalation_delay_seconds;
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.last_risk_level = risk_level;
+        position_account.risk_updated_at = timestamp;
+
+        // A position only becomes liquidatable once a second max-risk reading
+        // lands at least `escalation_delay` after the first, so a single bad
+        // feed update can't trigger liquidation. The stored level is coarsened,
+        // so the max reading comes from its own flag.
+        if at_max_risk {
+            if position_account.risk_flagged_at == 0 {
+                position_account.risk_flagged_at = timestamp;
+            } else if position_account.liquidatable_after == 0
+                && timestamp - position_account.risk_flagged_at >= escalation_delay
+            {
+                position_account.liquidatable_after = timestamp;
+            }
+        } else {
+            position_account.risk_flagged_at = 0;
+            position_account.liquidatable_after = 0;
+        }
+
+        emit!(LiquidationRiskCalculated {
+            risk_level,
+            timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn extend_order_expiry(
+        ctx: Context<ExtendOrderExpiry>,
+        computation_offset: u64,
+        order_id: [u8; 32],
+        new_expiry: i64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        require!(
+            new_expiry > ctx.accounts.order_account.expires_at,
+            ErrorCode::InvalidExpiryExtension
+        );
+
+        ctx.accounts
+            .order_state
+            .bind(ctx.accounts.order_account.key(), ctx.bumps.order_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(order_id),
+            Argument::PlaintextI64(new_expiry),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ExtendOrderExpiryCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.order_account.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.order_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "extend_order_expiry")]
+    pub fn extend_order_expiry_callback(
+        ctx: Context<ExtendOrderExpiryCallback>,
+        output: ComputationOutputs<ExtendOrderExpiryOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (order, is_extended, new_expiry) = match output {
+            ComputationOutputs::Success(ExtendOrderExpiryOutput {
+                field_0: ExtendOrderExpiryOutputStruct0 { field_0, field_1, field_2 },
+            }) => (field_0, field_1, field_2),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        if !is_extended {
+            return reject_callback(ErrorCode::InvalidExpiryExtension);
+        }
+
+        // Only the expiry moves; the sequence number is left untouched so the
+        // order keeps its time priority. The encrypted copy the circuits read
+        // moves with the plaintext one.
+        ctx.accounts
+            .order_state
+            .store(order.nonce, &order.ciphertexts)?;
+        let order_account = &mut ctx.accounts.order_account;
+        order_account.expires_at = new_expiry;
+
+        emit!(OrderExpiryExtended {
+            order_id: order_account.order_id,
+            new_expiry,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn init_maker_volume(
+        ctx: Context<InitMakerVolume>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let maker = ctx.accounts.payer.key();
+
+        let maker_volume = &mut ctx.accounts.maker_volume;
+        maker_volume.maker = maker;
+        maker_volume.match_count = 0;
+        maker_volume.claimable_rebate = 0;
+        maker_volume.owed_rebate = 0;
+        maker_volume.window_started_at = Clock::get()?.unix_timestamp;
+        maker_volume.pending_window_start = 0;
+        maker_volume.bump = ctx.bumps.maker_volume;
+
+        ctx.accounts
+            .volume_state
+            .bind(ctx.accounts.maker_volume.key(), ctx.bumps.volume_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(maker.to_bytes()),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitMakerVolumeCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.volume_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_maker_volume")]
+    pub fn init_maker_volume_callback(
+        ctx: Context<InitMakerVolumeCallback>,
+        output: ComputationOutputs<InitMakerVolumeOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let volume = match output {
+            ComputationOutputs::Success(InitMakerVolumeOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts
+            .volume_state
+            .store(volume.nonce, &volume.ciphertexts)?;
+        Ok(())
+    }
+
+    pub fn prove_volume_tier(
+        ctx: Context<ProveVolumeTier>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(ctx.accounts.maker_volume.maker.to_bytes()),
+            Argument::PlaintextU64(VOLUME_TIER_THRESHOLDS[0]),
+            Argument::PlaintextU64(VOLUME_TIER_THRESHOLDS[1]),
+            Argument::PlaintextU64(VOLUME_TIER_THRESHOLDS[2]),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveVolumeTierCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_volume_tier")]
+    pub fn prove_volume_tier_callback(
+        ctx: Context<ProveVolumeTierCallback>,
+        output: ComputationOutputs<ProveVolumeTierOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let tier = match output {
+            ComputationOutputs::Success(ProveVolumeTierOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(VolumeTierProved {
+            tier,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn set_position_guardians(
+        ctx: Context<SetPositionGuardians>,
+        _position_id: [u8; 32],
+        guardians: [Pubkey; MAX_GUARDIANS],
+        threshold: u8,
+    ) -> Result<()> {
+        let mut guardian_count: u8 = 0;
+        for (i, guardian) in guardians.iter().enumerate() {
+            if *guardian == Pubkey::default() {
+                continue;
+            }
+            require!(
+                !guardians[..i].contains(guardian),
+                ErrorCode::DuplicateGuardian
+            );
+            guardian_count += 1;
+        }
+
+        // A zero threshold with no guardians clears recovery entirely.
+        require!(
+            threshold <= guardian_count && (threshold > 0 || guardian_count == 0),
+            ErrorCode::InvalidGuardianThreshold
+        );
+
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.guardians = guardians;
+        position_account.guardian_threshold = threshold;
+
+        emit!(GuardiansUpdated {
+            position_id: position_account.position_id,
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Rotates the position owner. Guardians co-sign by passing their keys as
+    /// signer accounts in `remaining_accounts`.
+    pub fn guardian_recover(
+        ctx: Context<GuardianRecover>,
+        _position_id: [u8; 32],
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        let position_account = &mut ctx.accounts.position_account;
+        require!(
+            position_account.guardian_threshold > 0,
+            ErrorCode::InsufficientGuardianSignatures
+        );
+
+        let mut approvals = [false; MAX_GUARDIANS];
+        for account in ctx.remaining_accounts.iter().filter(|a| a.is_signer) {
+            if let Some(i) = position_account
+                .guardians
+                .iter()
+                .position(|g| *g != Pubkey::default() && *g == account.key())
+            {
+                approvals[i] = true;
+            }
+        }
+
+        let approval_count = approvals.iter().filter(|approved| **approved).count();
+        require!(
+            approval_count >= position_account.guardian_threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures
+        );
+
+        position_account.owner = new_owner;
+
+        // Recovery moves the slot without checking the new owner's limit, so a
+        // full registry can't block it.
+        ctx.accounts.previous_owner_registry.release();
+        let new_owner_registry = &mut ctx.accounts.new_owner_registry;
+        new_owner_registry.owner = new_owner;
+        new_owner_registry.bump = ctx.bumps.new_owner_registry;
+        new_owner_registry.position_count = new_owner_registry.position_count.saturating_add(1);
+
+        emit!(PositionOwnerRecovered {
+            position_id: position_account.position_id,
+            new_owner,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_price_in_band(
+        ctx: Context<ProvePriceInBand>,
+        computation_offset: u64,
+        buy_order_id: [u8; 32],
+        sell_order_id: [u8; 32],
+        band_low: u64,
+        band_high: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        require!(band_low <= band_high, ErrorCode::InvalidPriceBand);
+        // Only the pair's own match is attested, not any two orders' limits.
+        require!(
+            ctx.accounts.buy_order.last_counterparty == sell_order_id
+                && ctx.accounts.sell_order.last_counterparty == buy_order_id,
+            ErrorCode::OrdersNotMatched
+        );
+
+        let args = vec![
+            Argument::PlaintextBytes32(buy_order_id),
+            Argument::PlaintextBytes32(sell_order_id),
+            Argument::PlaintextU64(band_low),
+            Argument::PlaintextU64(band_high),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProvePriceInBandCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_price_in_band")]
+    pub fn prove_price_in_band_callback(
+        ctx: Context<ProvePriceInBandCallback>,
+        output: ComputationOutputs<ProvePriceInBandOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let in_band = match output {
+            ComputationOutputs::Success(ProvePriceInBandOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(PriceInBandProved {
+            in_band,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn convert_stop_to_market(
+        ctx: Context<ConvertStopToMarket>,
+        computation_offset: u64,
+        order_id: [u8; 32],
+        max_slippage_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        require!(max_slippage_bps <= 10000, ErrorCode::InvalidSlippage);
+
+        // The trigger is checked against the order mint's registered feed, not
+        // a price the owner picks.
+        require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.dark_pool_config.max_oracle_age_seconds,
+        )?;
+        let (market_price, _) = oracle_price_and_confidence(&ctx.accounts.oracle)?;
+
+        ctx.accounts
+            .order_state
+            .bind(ctx.accounts.order_account.key(), ctx.bumps.order_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(order_id),
+            Argument::PlaintextU64(market_price),
+            Argument::PlaintextBytes32(ctx.accounts.oracle_feed.mint.to_bytes()),
+            Argument::PlaintextU64(max_slippage_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ConvertStopToMarketCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.order_account.key(),
+                    is_writable: false,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.order_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "convert_stop_to_market")]
+    pub fn convert_stop_to_market_callback(
+        ctx: Context<ConvertStopToMarketCallback>,
+        output: ComputationOutputs<ConvertStopToMarketOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (order, is_converted, feed_matches) = match output {
+            ComputationOutputs::Success(ConvertStopToMarketOutput {
+                field_0: ConvertStopToMarketOutputStruct0 { field_0, field_1, field_2 },
+            }) => (field_0, field_1, field_2),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        if !feed_matches {
+            return reject_callback(ErrorCode::OracleMintMismatch);
+        }
+        if !is_converted {
+            return reject_callback(ErrorCode::StopNotTriggered);
+        }
+
+        // The marketable limit and `stop_triggered` have to land for the
+        // order to match.
+        ctx.accounts
+            .order_state
+            .store(order.nonce, &order.ciphertexts)?;
+
+        emit!(StopConverted {
+            order_id: ctx.accounts.order_account.order_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn initialize_protocol_state(ctx: Context<InitializeProtocolState>) -> Result<()> {
+        let protocol_state = &mut ctx.accounts.protocol_state;
+        protocol_state.admin = ctx.accounts.admin.key();
+        protocol_state.max_positions_per_owner = DEFAULT_MAX_POSITIONS_PER_OWNER;
+        protocol_state.bump = ctx.bumps.protocol_state;
+        Ok(())
+    }
+
+    pub fn set_max_positions_per_owner(
+        ctx: Context<SetMaxPositionsPerOwner>,
+        max_positions_per_owner: u32,
+    ) -> Result<()> {
+        require!(max_positions_per_owner > 0, ErrorCode::InvalidPositionLimit);
+        ctx.accounts.protocol_state.max_positions_per_owner = max_positions_per_owner;
+        Ok(())
+    }
+
+    /// Closes the shared sign PDA and returns its rent to the admin, for
+    /// operators tearing down a deployment. Refused while any computation is
+    /// still waiting on its callback; the next queue recreates it.
+    pub fn close_sign_pda(ctx: Context<CloseSignPda>) -> Result<()> {
+        ctx.accounts.pending_computations.require_idle()?;
+
+        emit!(SignPdaClosed {
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Closes a position and frees its slot in the owner's registry, returning
+    /// the rent to the owner.
+    pub fn close_position(ctx: Context<ClosePosition>, position_id: [u8; 32]) -> Result<()> {
+        // Satellite accounts are keyed by the position, so leaving them open
+        // would hand a reopened position id its old alert, topup and
+        // deleverage settings. A topup still in flight has to land first.
+        let owner = ctx.accounts.owner.to_account_info();
+        let topup_info = ctx.accounts.topup_authorization.to_account_info();
+        if topup_info.owner == ctx.program_id && !topup_info.data_is_empty() {
+            let authorization =
+                TopupAuthorization::try_deserialize(&mut &topup_info.data.borrow()[..])?;
+            require!(authorization.pending_topup_usd == 0, ErrorCode::TopupPending);
+        }
+        for satellite in [
+            ctx.accounts.alert_config.to_account_info(),
+            topup_info,
+            ctx.accounts.deleverage_config.to_account_info(),
+            ctx.accounts.position_state.to_account_info(),
+        ] {
+            if satellite.owner == ctx.program_id && !satellite.data_is_empty() {
+                close_program_account(&satellite, &owner)?;
+            }
+        }
+
+        let owner_registry = &mut ctx.accounts.owner_registry;
+        owner_registry.owner = ctx.accounts.owner.key();
+        owner_registry.bump = ctx.bumps.owner_registry;
+        owner_registry.release();
+
+        emit!(PositionClosed {
+            position_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn init_protocol_config(
+        ctx: Context<InitProtocolConfig>,
+        protocol_id: u8,
+        params: ProtocolConfigParams,
+    ) -> Result<()> {
+        params.validate()?;
+
+        let protocol_config = &mut ctx.accounts.protocol_config;
+        protocol_config.protocol_id = protocol_id;
+        protocol_config.bump = ctx.bumps.protocol_config;
+        protocol_config.apply(&params);
+
+        emit!(ProtocolConfigUpdated {
+            protocol_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn update_protocol_config(
+        ctx: Context<UpdateProtocolConfig>,
+        protocol_id: u8,
+        params: ProtocolConfigParams,
+    ) -> Result<()> {
+        params.validate()?;
+
+        ctx.accounts.protocol_config.apply(&params);
+
+        emit!(ProtocolConfigUpdated {
+            protocol_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Validates every entry before applying any, so an invalid entry rejects
+    /// the whole batch. `remaining_accounts` holds each entry's protocol
+    /// config, in the same order.
+    pub fn batch_update_protocol_configs<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchUpdateProtocolConfigs<'info>>,
+        entries: Vec<ProtocolConfigEntry>,
+    ) -> Result<()> {
+        require!(
+            entries.len() <= MAX_BATCH_PROTOCOL_CONFIGS,
+            ErrorCode::TooManyProtocolConfigs
+        );
+        require!(
+            ctx.remaining_accounts.len() == entries.len(),
+            ErrorCode::UnregisteredProtocol
+        );
+
+        for (i, (entry, config_info)) in entries.iter().zip(ctx.remaining_accounts.iter()).enumerate() {
+            let (expected, _) = Pubkey::find_program_address(
+                &[PROTOCOL_CONFIG_SEED, &[entry.protocol_id]],
+                ctx.program_id,
+            );
+            let registered = config_info.key() == expected
+                && config_info.owner == ctx.program_id
+                && !config_info.data_is_empty();
+            if !registered {
+                msg!("Unregistered protocol {} at batch index {}", entry.protocol_id, i);
+                return err!(ErrorCode::UnregisteredProtocol);
+            }
+            if entries[..i].iter().any(|other| other.protocol_id == entry.protocol_id) {
+                msg!("Duplicate protocol {} at batch index {}", entry.protocol_id, i);
+                return err!(ErrorCode::DuplicateProtocolConfig);
+            }
+            entry.params.validate().map_err(|e| {
+                msg!("Invalid config for protocol {} at batch index {}", entry.protocol_id, i);
+                e
+            })?;
+        }
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        for (entry, config_info) in entries.iter().zip(ctx.remaining_accounts.iter()) {
+            let mut protocol_config =
+                ProtocolConfig::try_deserialize(&mut &config_info.data.borrow()[..])?;
+            protocol_config.apply(&entry.params);
+            protocol_config.try_serialize(&mut &mut config_info.try_borrow_mut_data()?[..])?;
+
+            emit!(ProtocolConfigUpdated {
+                protocol_id: entry.protocol_id,
+                timestamp,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn compute_solvency_ratio(
+        ctx: Context<ComputeSolvencyRatio>,
+        computation_offset: u64,
+        _protocol_id: u8,
+        position_count: u8,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        charge_fee_surcharge(
+            &ctx.accounts.protocol_config,
+            ComputationKind::SolvencyRatio,
+            &ctx.accounts.payer,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+
+        let args = vec![
+            Argument::PlaintextU8(position_count),
+            Argument::PlaintextU8(ctx.accounts.protocol_config.reveal_precision),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ComputeSolvencyRatioCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "compute_solvency_ratio")]
+    pub fn compute_solvency_ratio_callback(
+        ctx: Context<ComputeSolvencyRatioCallback>,
+        output: ComputationOutputs<ComputeSolvencyRatioOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let ratio_bucket = match output {
+            ComputationOutputs::Success(ComputeSolvencyRatioOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(SolvencyRatioPublished {
+            ratio_bucket,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn init_multi_collateral_position(
+        ctx: Context<InitMultiCollateralPosition>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        mints: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let legs = collateral_leg_mints(&mints)?;
+
+        let multi_collateral = &mut ctx.accounts.multi_collateral;
+        multi_collateral.position_id = position_id;
+        multi_collateral.bump = ctx.bumps.multi_collateral;
+        ctx.accounts
+            .multi_collateral_state
+            .bind(ctx.accounts.multi_collateral.key(), ctx.bumps.multi_collateral_state);
+
+        let mut args = vec![
+            Argument::PlaintextBytes32(position_id),
+        ];
+        args.extend(legs.iter().map(|mint| Argument::PlaintextBytes32(*mint)));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitMultiCollateralPositionCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.multi_collateral_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_multi_collateral_position")]
+    pub fn init_multi_collateral_position_callback(
+        ctx: Context<InitMultiCollateralPositionCallback>,
+        output: ComputationOutputs<InitMultiCollateralPositionOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let position = match output {
+            ComputationOutputs::Success(InitMultiCollateralPositionOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts
+            .multi_collateral_state
+            .store(position.nonce, &position.ciphertexts)?;
+
+        emit!(PositionInitialized {
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Records a deposit into a multi-collateral position. The source flags
+    /// feed the clean-source proof, so they're taken from the attester
+    /// (the protocol admin) rather than the depositor.
+    pub fn update_multi_collateral_position(
+        ctx: Context<UpdateMultiCollateralPosition>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        owner_pubkey: [u8; 32],
+        owner_nonce: u128,
+        source_flags: [u8; 4],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let mut args = vec![
+            Argument::ArcisPubkey(owner_pubkey),
+            Argument::PlaintextU128(owner_nonce),
+            Argument::PlaintextBytes32(position_id),
+        ];
+        args.extend(source_flags.iter().map(|flag| Argument::PlaintextU8(*flag)));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![UpdateMultiCollateralPositionCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.multi_collateral_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "update_multi_collateral_position")]
+    pub fn update_multi_collateral_position_callback(
+        ctx: Context<UpdateMultiCollateralPositionCallback>,
+        output: ComputationOutputs<UpdateMultiCollateralPositionOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let position = match output {
+            ComputationOutputs::Success(UpdateMultiCollateralPositionOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        // The next update reads the legs and sticky source flags from here.
+        ctx.accounts
+            .multi_collateral_state
+            .store(position.nonce, &position.ciphertexts)
+    }
+
+    pub fn prove_weighted_health(
+        ctx: Context<ProveWeightedHealth>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        threshold_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(threshold_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveWeightedHealthCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_weighted_health")]
+    pub fn prove_weighted_health_callback(
+        ctx: Context<ProveWeightedHealthCallback>,
+        output: ComputationOutputs<ProveWeightedHealthOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let is_healthy = match output {
+            ComputationOutputs::Success(ProveWeightedHealthOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(WeightedHealthProved {
+            is_healthy,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn register_keeper(ctx: Context<ManageKeepers>, keeper: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.keeper_registry;
+        registry.bump = ctx.bumps.keeper_registry;
+
+        require!(!registry.is_keeper(&keeper), ErrorCode::KeeperAlreadyRegistered);
+        require!(
+            (registry.keeper_count as usize) < MAX_KEEPERS,
+            ErrorCode::KeeperRegistryFull
+        );
+
+        registry.keepers[registry.keeper_count as usize] = keeper;
+        registry.keeper_count += 1;
+        Ok(())
+    }
+
+    pub fn remove_keeper(ctx: Context<ManageKeepers>, keeper: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.keeper_registry;
+        let count = registry.keeper_count as usize;
+
+        let index = registry.keepers[..count]
+            .iter()
+            .position(|k| *k == keeper)
+            .ok_or(ErrorCode::KeeperNotRegistered)?;
+
+        // Swap-remove keeps the registered keepers packed at the front.
+        registry.keepers[index] = registry.keepers[count - 1];
+        registry.keepers[count - 1] = Pubkey::default();
+        registry.keeper_count -= 1;
+
+        // A removed keeper's assignment lapses with it.
+        if registry.assigned_keeper == keeper {
+            registry.assigned_keeper = Pubkey::default();
+        }
+        Ok(())
+    }
+
+    pub fn select_keeper(
+        ctx: Context<SelectKeeper>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let keeper_count = ctx.accounts.keeper_registry.keeper_count;
+        require!(keeper_count > 0, ErrorCode::NoKeepersRegistered);
+
+        let slot = Clock::get()?.slot;
+        let registry = &mut ctx.accounts.keeper_registry;
+        require!(
+            registry.selection_slot == 0
+                || slot >= registry.selection_slot.saturating_add(KEEPER_ASSIGNMENT_SLOTS),
+            ErrorCode::KeeperAlreadySelected
+        );
+        registry.selection_slot = slot;
+
+        // Seed from the most recent slot hash: layout is a u64 length prefix
+        // followed by (slot: u64, hash: [u8; 32]) entries, newest first.
+        let seed = {
+            let data = ctx.accounts.recent_slothashes.try_borrow_data()?;
+            u64::from_le_bytes(data[16..24].try_into().unwrap())
+        };
+
+        let args = vec![
+            Argument::PlaintextU64(seed),
+            Argument::PlaintextU8(keeper_count),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SelectKeeperCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.keeper_registry.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "select_keeper")]
+    pub fn select_keeper_callback(
+        ctx: Context<SelectKeeperCallback>,
+        output: ComputationOutputs<SelectKeeperOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (keeper_index, seed) = match output {
+            ComputationOutputs::Success(SelectKeeperOutput {
+                field_0: SelectKeeperOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        // Keeper-gated instructions only accept the assigned keeper until the
+        // assignment lapses.
+        let registry = &mut ctx.accounts.keeper_registry;
+        if keeper_index >= registry.keeper_count {
+            return reject_callback(ErrorCode::KeeperNotRegistered);
+        }
+        let keeper = registry.keepers[keeper_index as usize];
+        registry.assigned_keeper = keeper;
+        registry.assigned_slot = Clock::get()?.slot;
+
+        emit!(KeeperAssigned {
+            keeper_index,
+            keeper,
+            seed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn set_alert_config(
+        ctx: Context<SetAlertConfig>,
+        position_id: [u8; 32],
+        hysteresis_bps: u64,
+    ) -> Result<()> {
+        let alert_config = &mut ctx.accounts.alert_config;
+        alert_config.position_id = position_id;
+        alert_config.hysteresis_bps = hysteresis_bps;
+        alert_config.bump = ctx.bumps.alert_config;
+        Ok(())
+    }
+
+    pub fn set_refresh_health_on_settle(
+        ctx: Context<SetRefreshHealthOnSettle>,
+        _position_id: [u8; 32],
+        enabled: bool,
+    ) -> Result<()> {
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.refresh_health_on_settle = enabled;
+        if !enabled {
+            position_account.health_refresh_pending = false;
+        }
+        Ok(())
+    }
+
+    /// Links an opted-in position to one of the owner's orders. Settling the
+    /// order then has to flag that position for a health refresh.
+    pub fn link_refresh_position(
+        ctx: Context<LinkRefreshPosition>,
+        _order_id: [u8; 32],
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.order_account.refresh_position_id = position_id;
+        Ok(())
+    }
+
+    /// The owner's position count is already public in `OwnerRegistry`, so
+    /// this is checked on-chain rather than in MPC.
+    pub fn prove_min_position_count(
+        ctx: Context<ProveMinPositionCount>,
+        _owner: Pubkey,
+        min_count: u32,
+    ) -> Result<()> {
+        emit!(MinPositionCountProved {
+            satisfied: ctx.accounts.owner_registry.position_count >= min_count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Closes up to `MAX_BATCH_CANCEL` orders owned by the signer, along with
+    /// their duplicate-submission markers. `remaining_accounts` holds an
+    /// (order, marker) pair per entry in `order_ids`, in the same order; orders
+    /// that were already closed are skipped.
+    pub fn batch_cancel_orders<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchCancelOrders<'info>>,
+        order_ids: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(order_ids.len() <= MAX_BATCH_CANCEL, ErrorCode::TooManyOrders);
+        require!(
+            ctx.remaining_accounts.len() == order_ids.len() * 2,
+            ErrorCode::InvalidOrderAccount
+        );
+
+        let owner = ctx.accounts.owner.to_account_info();
+        let mut count: u8 = 0;
+
+        for (order_id, accounts) in order_ids.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let (order_info, marker_info) = (&accounts[0], &accounts[1]);
+            let (expected, _) =
+                Pubkey::find_program_address(&[DARK_POOL_SEED, order_id.as_ref()], ctx.program_id);
+            require_keys_eq!(order_info.key(), expected, ErrorCode::InvalidOrderAccount);
+
+            if order_info.owner != ctx.program_id || order_info.data_is_empty() {
+                continue;
+            }
+
+            let order = DarkPoolOrderAccount::try_deserialize(&mut &order_info.data.borrow()[..])?;
+            require_keys_eq!(order.owner, owner.key(), ErrorCode::Unauthorized);
+
+            let (expected_marker, _) = Pubkey::find_program_address(
+                &[SUBMITTED_ORDER_SEED, owner.key().as_ref(), order.order_hash.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(marker_info.key(), expected_marker, ErrorCode::InvalidOrderAccount);
+
+            close_program_account(order_info, &owner)?;
+            // A fully filled order's marker may already be closed and reused.
+            if marker_info.owner == ctx.program_id && !marker_info.data_is_empty() {
+                let submitted = SubmittedOrders::try_deserialize(&mut &marker_info.data.borrow()[..])?;
+                if submitted.order_id == *order_id {
+                    close_program_account(marker_info, &owner)?;
+                }
+            }
+            count += 1;
+        }
+
+        ctx.accounts.order_activity.pending_cancels += count as u64;
+
+        emit!(OrdersCancelled {
+            count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_aggregate_leverage_bounded(
+        ctx: Context<ProveAggregateLeverageBounded>,
+        computation_offset: u64,
+        position_count: u8,
+        max_leverage_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextU8(position_count),
+            Argument::PlaintextU64(max_leverage_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveAggregateLeverageBoundedCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_aggregate_leverage_bounded")]
+    pub fn prove_aggregate_leverage_bounded_callback(
+        ctx: Context<ProveAggregateLeverageBoundedCallback>,
+        output: ComputationOutputs<ProveAggregateLeverageBoundedOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let bounded = match output {
+            ComputationOutputs::Success(ProveAggregateLeverageBoundedOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(AggregateLeverageProved {
+            bounded,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Reveals which of up to three buy/sell pairs would cross. Like
+    /// `preview_match`, nothing is filled or persisted, so it takes no
+    /// match sequence.
+    pub fn preview_multi_market(
+        ctx: Context<PreviewMultiMarket>,
+        computation_offset: u64,
+        buy_order_ids: [[u8; 32]; 3],
+        sell_order_ids: [[u8; 32]; 3],
+        pair_count: u8,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        require!(pair_count <= MAX_MULTI_MARKET_PAIRS, ErrorCode::TooManyMarkets);
+
+        let mut args: Vec<Argument> = buy_order_ids
+            .iter()
+            .chain(sell_order_ids.iter())
+            .map(|id| Argument::PlaintextBytes32(*id))
+            .collect();
+        args.push(Argument::PlaintextU8(pair_count));
+        args.push(Argument::PlaintextU64(ctx.accounts.dark_pool_config.price_tolerance_bps));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![PreviewMultiMarketCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "preview_multi_market")]
+    pub fn preview_multi_market_callback(
+        ctx: Context<PreviewMultiMarketCallback>,
+        output: ComputationOutputs<PreviewMultiMarketOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let crossing_mask = match output {
+            ComputationOutputs::Success(PreviewMultiMarketOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(MultiMarketPreviewed {
+            crossing_mask,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_collateral_composition(
+        ctx: Context<ProveCollateralComposition>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        min_stable_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        require!(min_stable_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(min_stable_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveCollateralCompositionCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_collateral_composition")]
+    pub fn prove_collateral_composition_callback(
+        ctx: Context<ProveCollateralCompositionCallback>,
+        output: ComputationOutputs<ProveCollateralCompositionOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let satisfied = match output {
+            ComputationOutputs::Success(ProveCollateralCompositionOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(CompositionProved {
+            satisfied,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn estimate_computation_cost(
+        _ctx: Context<EstimateComputationCost>,
+        kind: ComputationKind,
+    ) -> Result<u64> {
+        Ok(kind.estimated_cost())
+    }
+
+    /// Pre-flight for a batch of computations: fails up front if the payer
+    /// can't cover the estimated cost of every queued kind.
+    pub fn prove_balance_sufficient(
+        ctx: Context<ProveBalanceSufficient>,
+        kinds: Vec<ComputationKind>,
+    ) -> Result<()> {
+        let total_cost = kinds
+            .iter()
+            .fold(0u64, |total, kind| total.saturating_add(kind.estimated_cost()));
+        require!(
+            ctx.accounts.payer.lamports() >= total_cost,
+            ErrorCode::InsufficientBalanceForBatch
+        );
+        Ok(())
+    }
+
+    /// `source` must delegate at least `max_topup_usd` to the authorization
+    /// PDA; topups are drawn from it into `destination`, the position's
+    /// collateral account.
+    pub fn authorize_auto_topup(
+        ctx: Context<AuthorizeAutoTopup>,
+        position_id: [u8; 32],
+        source: Pubkey,
+        destination: Pubkey,
+        max_topup_usd: u64,
+    ) -> Result<()> {
+        let authorization = &mut ctx.accounts.topup_authorization;
+        require!(authorization.pending_topup_usd == 0, ErrorCode::TopupPending);
+        authorization.position_id = position_id;
+        authorization.source = source;
+        authorization.destination = destination;
+        authorization.max_topup_usd = max_topup_usd;
+        authorization.used_topup_usd = 0;
+        authorization.pending_topup_usd = 0;
+        authorization.bump = ctx.bumps.topup_authorization;
+        Ok(())
+    }
+
+    pub fn execute_auto_topup(
+        ctx: Context<ExecuteAutoTopup>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        ctx.accounts
+            .keeper_registry
+            .require_assigned(&ctx.accounts.payer.key(), Clock::get()?.slot)?;
+
+        // The flag is only set by `calculate_liquidation_risk`, which reads the
+        // bound feed and the configured impact, and the reading has to be as
+        // fresh as the feed it came from.
+        let now = Clock::get()?.unix_timestamp;
+        let position_account = &ctx.accounts.position_account;
+        require!(
+            position_account.risk_flagged_at != 0
+                && now - position_account.risk_updated_at
+                    <= ctx.accounts.protocol_config.max_oracle_age_seconds,
+            ErrorCode::TopupNotAllowed
+        );
+
+        // Reserve against the cap up front so concurrent topups can't overshoot
+        // it; the callback releases the reservation if the computation aborts.
+        ctx.accounts.topup_authorization.reserve(amount)?;
+
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(amount),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.liquidation_price_impact_bps),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.band_width_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ExecuteAutoTopupCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_account.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.topup_authorization.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.source.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.destination.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.token_program.key(),
+                    is_writable: false,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "apply_collateral_topup")]
+    pub fn apply_collateral_topup_callback(
+        ctx: Context<ExecuteAutoTopupCallback>,
+        output: ComputationOutputs<ApplyCollateralTopupOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let (position, amount, risk_level) = match output {
+            ComputationOutputs::Success(ApplyCollateralTopupOutput {
+                field_0: ApplyCollateralTopupOutputStruct0 { field_0, field_1, field_2 },
+            }) => (field_0, field_1, field_2),
+            _ => {
+                // Nothing moved, so hand the reservation back to the cap.
+                let amount = ctx.accounts.topup_authorization.release();
+                emit!(AutoTopupAborted { amount, timestamp });
+                return Ok(());
+            }
+        };
+
+        let authorization = &ctx.accounts.topup_authorization;
+        transfer_delegated_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.source,
+            &ctx.accounts.destination,
+            &authorization.to_account_info(),
+            &[TOPUP_AUTH_SEED, authorization.position_id.as_ref(), &[authorization.bump]],
+            amount,
+        )?;
+        ctx.accounts.topup_authorization.pending_topup_usd = 0;
+
+        ctx.accounts
+            .position_state
+            .store(position.nonce, &position.ciphertexts)?;
+
+        // The topup's own reading replaces the one that allowed it, so a
+        // rescued position stops qualifying for further topups.
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.last_risk_level = risk_level;
+        position_account.risk_updated_at = timestamp;
+        if risk_level < MAX_RISK_LEVEL {
+            position_account.risk_flagged_at = 0;
+            position_account.liquidatable_after = 0;
+        }
+
+        emit!(AutoTopupExecuted { amount, timestamp });
+        Ok(())
+    }
+
+    /// Last step of the liquidation flow: once `calculate_liquidation_risk`
+    /// has escalated a position to liquidatable, a keeper records the
+    /// liquidation into its encrypted history.
+    pub fn record_liquidation(
+        ctx: Context<RecordLiquidation>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        ctx.accounts
+            .keeper_registry
+            .require_assigned(&ctx.accounts.payer.key(), Clock::get()?.slot)?;
+        require!(
+            ctx.accounts.position_account.risk_flagged_at != 0
+                && ctx.accounts.position_account.liquidatable_after != 0,
+            ErrorCode::PositionNotLiquidatable
+        );
+
+        // Recording consumes the escalation: another liquidation can only be
+        // recorded after the position escalates again, so one liquidatable
+        // window can't be counted twice.
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.risk_flagged_at = 0;
+        position_account.liquidatable_after = 0;
+
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.liquidation_price_impact_bps),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.band_width_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RecordLiquidationCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "record_liquidation")]
+    pub fn record_liquidation_callback(
+        ctx: Context<RecordLiquidationCallback>,
+        output: ComputationOutputs<RecordLiquidationOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (position, recorded) = match output {
+            ComputationOutputs::Success(RecordLiquidationOutput {
+                field_0: RecordLiquidationOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+        if !recorded {
+            return reject_callback(ErrorCode::PositionNotLiquidatable);
+        }
+
+        ctx.accounts
+            .position_state
+            .store(position.nonce, &position.ciphertexts)?;
+
+        emit!(LiquidationRecorded {
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_clean_history(
+        ctx: Context<ProveCleanHistory>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveCleanHistoryCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_clean_history")]
+    pub fn prove_clean_history_callback(
+        ctx: Context<ProveCleanHistoryCallback>,
+        output: ComputationOutputs<ProveCleanHistoryOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let clean = match output {
+            ComputationOutputs::Success(ProveCleanHistoryOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(CleanHistoryProved {
+            clean,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_health_thresholds(
+        ctx: Context<ProveHealthThresholds>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        thresholds_bps: [u64; 3],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        require!(
+            thresholds_bps[0] > thresholds_bps[1] && thresholds_bps[1] > thresholds_bps[2],
+            ErrorCode::InvalidThresholdOrder
+        );
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(thresholds_bps[0]),
+            Argument::PlaintextU64(thresholds_bps[1]),
+            Argument::PlaintextU64(thresholds_bps[2]),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveHealthThresholdsCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_health_thresholds")]
+    pub fn prove_health_thresholds_callback(
+        ctx: Context<ProveHealthThresholdsCallback>,
+        output: ComputationOutputs<ProveHealthThresholdsOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let results = match output {
+            ComputationOutputs::Success(ProveHealthThresholdsOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        // Bit i is set when threshold i is met.
+        let results_mask = results
+            .iter()
+            .enumerate()
+            .fold(0u8, |mask, (i, met)| if *met { mask | (1 << i) } else { mask });
+
+        emit!(MultiThresholdProved {
+            results_mask,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Only the owner of one of the two orders may preview, so nobody can
+    /// probe the book with someone else's orders.
+    pub fn preview_match(
+        ctx: Context<PreviewMatch>,
+        computation_offset: u64,
+        buy_order_id: [u8; 32],
+        sell_order_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let payer = ctx.accounts.payer.key();
+        require!(
+            ctx.accounts.buy_order.owner == payer || ctx.accounts.sell_order.owner == payer,
+            ErrorCode::Unauthorized
+        );
+
+        let args = vec![
+            Argument::PlaintextBytes32(buy_order_id),
+            Argument::PlaintextBytes32(sell_order_id),
+            Argument::PlaintextU64(ctx.accounts.dark_pool_config.price_tolerance_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![PreviewMatchCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "preview_match")]
+    pub fn preview_match_callback(
+        ctx: Context<PreviewMatchCallback>,
+        output: ComputationOutputs<PreviewMatchOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let would_match = match output {
+            ComputationOutputs::Success(PreviewMatchOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(MatchPreviewed {
+            would_match,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Proves the intent's latest execution beat `quoted_price` by at least
+    /// `min_improvement_bps`. The execution is the one the intent recorded,
+    /// so the prover can't claim a price it didn't get.
+    pub fn prove_price_improvement(
+        ctx: Context<ProvePriceImprovement>,
+        computation_offset: u64,
+        intent_id: [u8; 32],
+        quoted_price: u64,
+        min_improvement_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(ctx.accounts.swap_intent.last_output > 0, ErrorCode::SwapNotExecuted);
+
+        require!(min_improvement_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let args = vec![
+            Argument::PlaintextBytes32(intent_id),
+            Argument::PlaintextU64(ctx.accounts.swap_intent.last_output),
+            Argument::PlaintextU64(quoted_price),
+            Argument::PlaintextU64(min_improvement_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProvePriceImprovementCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_price_improvement")]
+    pub fn prove_price_improvement_callback(
+        ctx: Context<ProvePriceImprovementCallback>,
+        output: ComputationOutputs<ProvePriceImprovementOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let improved = match output {
+            ComputationOutputs::Success(ProvePriceImprovementOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(PriceImprovementProved {
+            improved,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn migrate_position_schema(
+        ctx: Context<MigratePositionSchema>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        // The circuit reads the unversioned layout, so running it on any
+        // other would scramble the position.
+        require_migratable_schema(&ctx.accounts.position_account, 0)?;
+
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![MigratePositionSchemaCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_account.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "migrate_position_schema")]
+    pub fn migrate_position_schema_callback(
+        ctx: Context<MigratePositionSchemaCallback>,
+        output: ComputationOutputs<MigratePositionSchemaOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let position = match output {
+            ComputationOutputs::Success(MigratePositionSchemaOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts
+            .position_state
+            .store(position.nonce, &position.ciphertexts)?;
+
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.schema_version = CURRENT_POSITION_SCHEMA_VERSION;
+
+        emit!(PositionSchemaMigrated {
+            position_id: position_account.position_id,
+            schema_version: position_account.schema_version,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn migrate_position_schema_v1(
+        ctx: Context<MigratePositionSchemaV1>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_migratable_schema(&ctx.accounts.position_account, 1)?;
+
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![MigratePositionSchemaV1Callback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_account.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "migrate_position_schema_v1")]
+    pub fn migrate_position_schema_v1_callback(
+        ctx: Context<MigratePositionSchemaV1Callback>,
+        output: ComputationOutputs<MigratePositionSchemaV1Output>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let position = match output {
+            ComputationOutputs::Success(MigratePositionSchemaV1Output { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts
+            .position_state
+            .store(position.nonce, &position.ciphertexts)?;
+
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.schema_version = CURRENT_POSITION_SCHEMA_VERSION;
+
+        emit!(PositionSchemaMigrated {
+            position_id: position_account.position_id,
+            schema_version: position_account.schema_version,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn migrate_position_schema_v2(
+        ctx: Context<MigratePositionSchemaV2>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_migratable_schema(&ctx.accounts.position_account, 2)?;
+
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+
+        let args = vec![Argument::PlaintextBytes32(position_id)];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![MigratePositionSchemaV2Callback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_account.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "migrate_position_schema_v2")]
+    pub fn migrate_position_schema_v2_callback(
+        ctx: Context<MigratePositionSchemaV2Callback>,
+        output: ComputationOutputs<MigratePositionSchemaV2Output>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let position = match output {
+            ComputationOutputs::Success(MigratePositionSchemaV2Output { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts
+            .position_state
+            .store(position.nonce, &position.ciphertexts)?;
+
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.schema_version = CURRENT_POSITION_SCHEMA_VERSION;
+
+        emit!(PositionSchemaMigrated {
+            position_id: position_account.position_id,
+            schema_version: position_account.schema_version,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn stress_test_portfolio(
+        ctx: Context<StressTestPortfolio>,
+        computation_offset: u64,
+        _protocol_id: u8,
+        position_count: u8,
+        price_shock_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        charge_fee_surcharge(
+            &ctx.accounts.protocol_config,
+            ComputationKind::StressTestPortfolio,
+            &ctx.accounts.payer,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+
+        require!(price_shock_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let args = vec![
+            Argument::PlaintextU8(position_count),
+            Argument::PlaintextU64(price_shock_bps),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.band_width_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![StressTestPortfolioCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "stress_test_portfolio")]
+    pub fn stress_test_portfolio_callback(
+        ctx: Context<StressTestPortfolioCallback>,
+        output: ComputationOutputs<StressTestPortfolioOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let liquidations_under_shock = match output {
+            ComputationOutputs::Success(StressTestPortfolioOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(StressTestCompleted {
+            liquidations_under_shock,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn commit_delayed_reveal(
+        ctx: Context<CommitDelayedReveal>,
+        computation_offset: u64,
+        reveal_id: [u8; 32],
+        reveal_after: i64,
+        result_pubkey: [u8; 32],
+        result_nonce: u128,
+        encrypted_result: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(
+            reveal_after > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidRevealTime
+        );
+
+        let args = vec![
+            Argument::ArcisPubkey(result_pubkey),
+            Argument::PlaintextU128(result_nonce),
+            Argument::EncryptedU64(encrypted_result),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        let delayed_reveal = &mut ctx.accounts.delayed_reveal;
+        delayed_reveal.owner = ctx.accounts.payer.key();
+        delayed_reveal.reveal_id = reveal_id;
+        delayed_reveal.reveal_after = reveal_after;
+        delayed_reveal.committed = false;
+        delayed_reveal.nonce = 0;
+        delayed_reveal.ciphertext = [0; 32];
+        delayed_reveal.finalized = false;
+        delayed_reveal.bump = ctx.bumps.delayed_reveal;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CommitDelayedRevealCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.delayed_reveal.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        emit!(DelayedRevealCommitted {
+            reveal_id,
+            reveal_after,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "commit_delayed_reveal")]
+    pub fn commit_delayed_reveal_callback(
+        ctx: Context<CommitDelayedRevealCallback>,
+        output: ComputationOutputs<CommitDelayedRevealOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let committed = match output {
+            ComputationOutputs::Success(CommitDelayedRevealOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        // Finalizing decrypts exactly this ciphertext, so what gets revealed
+        // is fixed at commit time.
+        let delayed_reveal = &mut ctx.accounts.delayed_reveal;
+        delayed_reveal.nonce = committed.nonce;
+        delayed_reveal.ciphertext = committed.ciphertexts[0];
+        delayed_reveal.committed = true;
+        Ok(())
+    }
+
+    pub fn finalize_delayed_reveal(
+        ctx: Context<FinalizeDelayedReveal>,
+        computation_offset: u64,
+        _reveal_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let delayed_reveal = &ctx.accounts.delayed_reveal;
+        require!(delayed_reveal.committed, ErrorCode::RevealNotCommitted);
+        require!(!delayed_reveal.finalized, ErrorCode::RevealAlreadyFinalized);
+        require!(
+            Clock::get()?.unix_timestamp >= delayed_reveal.reveal_after,
+            ErrorCode::RevealTooEarly
+        );
+
+        let args = vec![
+            Argument::PlaintextU128(delayed_reveal.nonce),
+            Argument::EncryptedU64(delayed_reveal.ciphertext),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![FinalizeDelayedRevealCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.delayed_reveal.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "finalize_delayed_reveal")]
+    pub fn finalize_delayed_reveal_callback(
+        ctx: Context<FinalizeDelayedRevealCallback>,
+        output: ComputationOutputs<FinalizeDelayedRevealOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let result = match output {
+            ComputationOutputs::Success(FinalizeDelayedRevealOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        let delayed_reveal = &mut ctx.accounts.delayed_reveal;
+        if delayed_reveal.finalized {
+            return reject_callback(ErrorCode::RevealAlreadyFinalized);
+        }
+        delayed_reveal.finalized = true;
+
+        emit!(DelayedRevealFinalized {
+            reveal_id: delayed_reveal.reveal_id,
+            result,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Selects the taker that should fill a resting order under the venue's
+    /// priority policy. Taker order accounts are passed in `remaining_accounts`
+    /// in the same order as `taker_order_ids`. Takers bid with the priority
+    /// fee they escrowed when placed; the winner pays it only once its match
+    /// fills.
+    pub fn match_book<'info>(
+        ctx: Context<'_, '_, '_, 'info, MatchBook<'info>>,
+        computation_offset: u64,
+        resting_order_id: [u8; 32],
+        taker_order_ids: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        require!(taker_order_ids.len() <= MAX_BOOK_DEPTH, ErrorCode::TooManyOrders);
+        require!(
+            ctx.remaining_accounts.len() == taker_order_ids.len(),
+            ErrorCode::InvalidOrderAccount
+        );
+        require!(
+            ctx.accounts.resting_order.is_matchable(),
+            ErrorCode::ConditionalOrderNotTriggered
+        );
+
+        let mut ids = [[0u8; 32]; MAX_BOOK_DEPTH];
+        let mut sequences = [u64::MAX; MAX_BOOK_DEPTH];
+        let mut fees = [0u64; MAX_BOOK_DEPTH];
+        for (i, (order_id, order_info)) in taker_order_ids
+            .iter()
+            .zip(ctx.remaining_accounts.iter())
+            .enumerate()
+        {
+            let (expected, _) =
+                Pubkey::find_program_address(&[DARK_POOL_SEED, order_id.as_ref()], ctx.program_id);
+            require_keys_eq!(order_info.key(), expected, ErrorCode::InvalidOrderAccount);
+
+            let order = DarkPoolOrderAccount::try_deserialize(&mut &order_info.data.borrow()[..])?;
+            require!(order.is_matchable(), ErrorCode::ConditionalOrderNotTriggered);
+            ids[i] = *order_id;
+            sequences[i] = order.sequence;
+            fees[i] = order.priority_fee;
+        }
+
+        let config = &ctx.accounts.dark_pool_config;
+        let mut args = vec![Argument::PlaintextBytes32(resting_order_id)];
+        args.extend(ids.iter().map(|id| Argument::PlaintextBytes32(*id)));
+        args.extend(sequences.iter().map(|sequence| Argument::PlaintextU64(*sequence)));
+        args.extend(fees.iter().map(|fee| Argument::PlaintextU64(*fee)));
+        args.push(Argument::PlaintextU8(taker_order_ids.len() as u8));
+        args.push(Argument::PlaintextBool(config.size_priority));
+        args.push(Argument::PlaintextU64(config.price_tolerance_bps));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+        ctx.accounts.match_sequence_counter.bump = ctx.bumps.match_sequence_counter;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![MatchBookCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.match_sequence_counter.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "match_book")]
+    pub fn match_book_callback(
+        ctx: Context<MatchBookCallback>,
+        output: ComputationOutputs<MatchBookOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let selected_slot = match output {
+            ComputationOutputs::Success(MatchBookOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        // Selection only ranks the takers; the priority fee is collected when
+        // the selected taker's match fills.
+        emit!(BookMatchSelected {
+            selected_slot,
+            match_sequence: ctx.accounts.match_sequence_counter.advance(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn batch_weighted_risk_score(
+        ctx: Context<BatchWeightedRiskScore>,
+        computation_offset: u64,
+        _protocol_id: u8,
+        position_count: u8,
+        threshold_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        charge_fee_surcharge(
+            &ctx.accounts.protocol_config,
+            ComputationKind::BatchWeightedRiskScore,
+            &ctx.accounts.payer,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+        )?;
+
+        let args = vec![
+            Argument::PlaintextU8(position_count),
+            Argument::PlaintextU64(threshold_bps),
+            Argument::PlaintextU8(ctx.accounts.protocol_config.reveal_precision),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![BatchWeightedRiskScoreCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "batch_weighted_risk_score")]
+    pub fn batch_weighted_risk_score_callback(
+        ctx: Context<BatchWeightedRiskScoreCallback>,
+        output: ComputationOutputs<BatchWeightedRiskScoreOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let score_bucket = match output {
+            ComputationOutputs::Success(BatchWeightedRiskScoreOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(WeightedRiskScored {
+            score_bucket,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn compute_max_liquidatable(
+        ctx: Context<ComputeMaxLiquidatable>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.close_factor_bps),
+            Argument::PlaintextU8(ctx.accounts.protocol_config.reveal_precision),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ComputeMaxLiquidatableCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "compute_max_liquidatable")]
+    pub fn compute_max_liquidatable_callback(
+        ctx: Context<ComputeMaxLiquidatableCallback>,
+        output: ComputationOutputs<ComputeMaxLiquidatableOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let amount_bucket = match output {
+            ComputationOutputs::Success(ComputeMaxLiquidatableOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(MaxLiquidatableComputed {
+            amount_bucket,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn compare_protocol_exposure(
+        ctx: Context<CompareProtocolExposure>,
+        computation_offset: u64,
+        position_count: u8,
+        protocol_a: u8,
+        protocol_b: u8,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextU8(position_count),
+            Argument::PlaintextU8(protocol_a),
+            Argument::PlaintextU8(protocol_b),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CompareProtocolExposureCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "compare_protocol_exposure")]
+    pub fn compare_protocol_exposure_callback(
+        ctx: Context<CompareProtocolExposureCallback>,
+        output: ComputationOutputs<CompareProtocolExposureOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let larger = match output {
+            ComputationOutputs::Success(CompareProtocolExposureOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(ProtocolExposureCompared {
+            larger,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Registers, or replaces, the price feed for `mint`. Admin only; the feed
+    /// has to be one `pyth_field` can parse, so nothing unreadable gets bound.
+    pub fn register_oracle_feed(ctx: Context<RegisterOracleFeed>, mint: Pubkey) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        require!(
+            RECOGNIZED_ORACLE_PROGRAMS.contains(oracle.owner),
+            ErrorCode::UnrecognizedOracle
+        );
+        oracle_price_and_confidence(oracle)?;
+
+        let oracle_feed = &mut ctx.accounts.oracle_feed;
+        oracle_feed.mint = mint;
+        oracle_feed.oracle = oracle.key();
+        oracle_feed.bump = ctx.bumps.oracle_feed;
+
+        emit!(OracleFeedRegistered {
+            mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Points a position at a registered price feed. Callable by the position
+    /// owner or the protocol admin, e.g. when a protocol migrates oracles or
+    /// to bind positions opened before feeds were bound at init. Health
+    /// updates check the feed's mint against the position's own, so binding
+    /// the wrong asset only stalls them.
+    pub fn rebind_position_oracle(
+        ctx: Context<RebindPositionOracle>,
+        _position_id: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.position_account.oracle = ctx.accounts.oracle_feed.oracle;
+
+        emit!(OracleRebound {
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Proves the feed bound to a position was published within the
+    /// protocol's `max_oracle_age_seconds`. Publish times are public, so this
+    /// runs on-chain rather than through a circuit.
+    pub fn prove_oracle_fresh(
+        ctx: Context<ProveOracleFresh>,
+        _position_id: [u8; 32],
+    ) -> Result<()> {
+        let publish_time = require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.protocol_config.max_oracle_age_seconds,
+        )?;
+
+        emit!(OracleFreshnessProved {
+            publish_time,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn init_fee_rebate_pool(ctx: Context<InitFeeRebatePool>) -> Result<()> {
+        ctx.accounts.fee_rebate_pool.bump = ctx.bumps.fee_rebate_pool;
+        Ok(())
+    }
+
+    pub fn update_and_prove_health(
+        ctx: Context<UpdateAndProveHealth>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        threshold_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.protocol_config.max_oracle_age_seconds,
+        )?;
+        let (oracle_price, _) = oracle_price_and_confidence(&ctx.accounts.oracle)?;
+
+        // Bundling pays the same tier fee as `update_health_factor`. The
+        // discount share is escrowed in the rebate pool for the callback to
+        // refund; the rest backs maker rebates like any other collected fee.
+        let fee = ctx
+            .accounts
+            .protocol_config
+            .fee_schedule
+            .fee_for_tier(ctx.accounts.position_account.fee_tier);
+        let discount_lamports = fee * ctx.accounts.protocol_config.bundle_discount_bps / 10000;
+        collect_fee(
+            &ctx.accounts.payer,
+            &mut ctx.accounts.rebate_vault,
+            &ctx.accounts.system_program,
+            fee - discount_lamports,
+        )?;
+        if discount_lamports > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: ctx.accounts.fee_rebate_pool.to_account_info(),
+                    },
+                ),
+                discount_lamports,
+            )?;
+        }
+
+        let bundle_receipt = &mut ctx.accounts.bundle_receipt;
+        bundle_receipt.payer = ctx.accounts.payer.key();
+        bundle_receipt.bundled = true;
+        bundle_receipt.discount_lamports = discount_lamports;
+        bundle_receipt.bump = ctx.bumps.bundle_receipt;
+
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(oracle_price),
+            Argument::PlaintextBytes32(ctx.accounts.oracle_feed.mint.to_bytes()),
+            Argument::PlaintextU64(threshold_bps),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.ema_smoothing_bps),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![UpdateAndProveHealthCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.bundle_receipt.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.fee_rebate_pool.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.payer.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "update_and_prove_health")]
+    pub fn update_and_prove_health_callback(
+        ctx: Context<UpdateAndProveHealthCallback>,
+        output: ComputationOutputs<UpdateAndProveHealthOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        // The discount came out of this computation's own fee, so it's
+        // refunded like the fee is kept: whether or not the computation lands.
+        let mut discount_lamports = 0;
+        if ctx.accounts.bundle_receipt.bundled {
+            discount_lamports = ctx.accounts.bundle_receipt.discount_lamports;
+            **ctx.accounts.fee_rebate_pool.to_account_info().try_borrow_mut_lamports()? -=
+                discount_lamports;
+            **ctx.accounts.payer.try_borrow_mut_lamports()? += discount_lamports;
+        }
+
+        let (position, feed_matches, is_healthy) = match output {
+            ComputationOutputs::Success(UpdateAndProveHealthOutput {
+                field_0: UpdateAndProveHealthOutputStruct0 { field_0, field_1, field_2 },
+            }) => (field_0, field_1, field_2),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+        if !feed_matches {
+            return reject_callback(ErrorCode::OracleMintMismatch);
+        }
+
+        ctx.accounts
+            .position_state
+            .store(position.nonce, &position.ciphertexts)?;
+
+        emit!(BundledHealthProved {
+            is_healthy,
+            discount_lamports,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn compute_age_distribution(
+        ctx: Context<ComputeAgeDistribution>,
+        computation_offset: u64,
+        _protocol_id: u8,
+        position_count: u8,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextU8(position_count),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
+            Argument::PlaintextU8(ctx.accounts.protocol_config.reveal_precision),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ComputeAgeDistributionCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "compute_age_distribution")]
+    pub fn compute_age_distribution_callback(
+        ctx: Context<ComputeAgeDistributionCallback>,
+        output: ComputationOutputs<ComputeAgeDistributionOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let histogram = match output {
+            ComputationOutputs::Success(ComputeAgeDistributionOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(AgeDistributionComputed {
+            histogram,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// The market price is read from the registered feed for the order's
+    /// token, which the circuit checks against the order itself.
+    pub fn handle_expiring_order(
+        ctx: Context<HandleExpiringOrder>,
+        computation_offset: u64,
+        order_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        ctx.accounts
+            .keeper_registry
+            .require_assigned(&ctx.accounts.payer.key(), Clock::get()?.slot)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let expires_at = ctx.accounts.order_account.expires_at;
+        require!(
+            now < expires_at && now >= expires_at - EXPIRY_GRACE_WINDOW_SECONDS,
+            ErrorCode::OrderNotExpiring
+        );
+
+        require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.dark_pool_config.max_oracle_age_seconds,
+        )?;
+        let (market_price, _) = oracle_price_and_confidence(&ctx.accounts.oracle)?;
+
+        ctx.accounts
+            .order_state
+            .bind(ctx.accounts.order_account.key(), ctx.bumps.order_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(order_id),
+            Argument::PlaintextU64(market_price),
+            Argument::PlaintextBytes32(ctx.accounts.oracle_feed.mint.to_bytes()),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![HandleExpiringOrderCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.order_account.key(),
+                    is_writable: false,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.order_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "handle_expiring_order")]
+    pub fn handle_expiring_order_callback(
+        ctx: Context<HandleExpiringOrderCallback>,
+        output: ComputationOutputs<HandleExpiringOrderOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (order, is_converted, feed_matches) = match output {
+            ComputationOutputs::Success(HandleExpiringOrderOutput {
+                field_0: HandleExpiringOrderOutputStruct0 { field_0, field_1, field_2 },
+            }) => (field_0, field_1, field_2),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        if !feed_matches {
+            return reject_callback(ErrorCode::OracleMintMismatch);
+        }
+        if !is_converted {
+            return reject_callback(ErrorCode::ExpiryConversionNotEnabled);
+        }
+
+        ctx.accounts
+            .order_state
+            .store(order.nonce, &order.ciphertexts)?;
+
+        emit!(ExpiryConversionTriggered {
+            order_id: ctx.accounts.order_account.order_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_unleveraged(
+        ctx: Context<ProveUnleveraged>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveUnleveragedCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_unleveraged")]
+    pub fn prove_unleveraged_callback(
+        ctx: Context<ProveUnleveragedCallback>,
+        output: ComputationOutputs<ProveUnleveragedOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let unleveraged = match output {
+            ComputationOutputs::Success(ProveUnleveragedOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(UnleveragedProved {
+            unleveraged,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Initializes up to `MAX_BATCH_POSITIONS` positions in one computation.
+    /// `remaining_accounts` holds a (protocol config, position account) pair per
+    /// entry, in the same order as `position_ids`. Every protocol is checked
+    /// before anything is created, so one unregistered protocol fails the batch.
+    pub fn batch_init_encrypted_positions<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchInitEncryptedPositions<'info>>,
+        computation_offset: u64,
+        position_ids: Vec<[u8; 32]>,
+        protocols: Vec<u8>,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        require!(position_ids.len() <= MAX_BATCH_POSITIONS, ErrorCode::TooManyPositions);
+        require!(
+            protocols.len() == position_ids.len()
+                && ctx.remaining_accounts.len() == position_ids.len() * 2,
+            ErrorCode::InvalidPositionAccount
+        );
+
+        let config_infos = ctx.remaining_accounts.chunks(2).map(|accounts| &accounts[0]);
+        if let Some((i, error)) = unusable_protocol_index(ctx.program_id, &protocols, config_infos) {
+            msg!("Protocol {} at batch index {}: {}", protocols[i], i, error);
+            return Err(error.into());
+        }
+
+        let owner_registry = &mut ctx.accounts.owner_registry;
+        owner_registry.owner = ctx.accounts.payer.key();
+        owner_registry.bump = ctx.bumps.owner_registry;
+        owner_registry.reserve(
+            position_ids.len() as u32,
+            ctx.accounts.protocol_state.max_positions_per_owner,
+        )?;
+
+        let payer = ctx.accounts.payer.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let mut padded_protocols = [0u8; MAX_BATCH_POSITIONS];
+
+        for (i, (position_id, accounts)) in position_ids
+            .iter()
+            .zip(ctx.remaining_accounts.chunks(2))
+            .enumerate()
+        {
+            let position_info = &accounts[1];
+            let (expected, bump) =
+                Pubkey::find_program_address(&[POSITION_PDA_SEED, position_id.as_ref()], ctx.program_id);
+            require_keys_eq!(position_info.key(), expected, ErrorCode::InvalidPositionAccount);
+
+            create_position_account(
+                &payer,
+                &system_program,
+                position_info,
+                ctx.program_id,
+                PositionAccount {
+                    owner: payer.key(),
+                    position_id: *position_id,
+                    protocol_id: protocols[i],
+                    guardians: [Pubkey::default(); MAX_GUARDIANS],
+                    guardian_threshold: 0,
+                    last_risk_level: 0,
+                    risk_updated_at: 0,
+                    schema_version: CURRENT_POSITION_SCHEMA_VERSION,
+                    oracle: ctx.accounts.oracle_feed.oracle,
+                    risk_flagged_at: 0,
+                    liquidatable_after: 0,
+                    fee_tier: 0,
+                    refresh_health_on_settle: false,
+                    health_refresh_pending: false,
+                    bump,
+                },
+            )?;
+            padded_protocols[i] = protocols[i];
+        }
+
+        let mut args: Vec<Argument> = padded_protocols
+            .iter()
+            .map(|protocol| Argument::PlaintextU8(*protocol))
+            .collect();
+        args.push(Argument::PlaintextI64(Clock::get()?.unix_timestamp));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![BatchInitEncryptedPositionsCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+
+        emit!(PositionsBatchInitialized {
+            count: position_ids.len() as u8,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "batch_init_encrypted_positions")]
+    pub fn batch_init_encrypted_positions_callback(
+        ctx: Context<BatchInitEncryptedPositionsCallback>,
+        output: ComputationOutputs<BatchInitEncryptedPositionsOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let _positions = match output {
+            ComputationOutputs::Success(BatchInitEncryptedPositionsOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(PositionInitialized {
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_min_collateral(
+        ctx: Context<ProveMinCollateral>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        min_collateral_usd: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(min_collateral_usd),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveMinCollateralCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_min_collateral")]
+    pub fn prove_min_collateral_callback(
+        ctx: Context<ProveMinCollateralCallback>,
+        output: ComputationOutputs<ProveMinCollateralOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let satisfied = match output {
+            ComputationOutputs::Success(ProveMinCollateralOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(MinCollateralProved {
+            satisfied,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn pause_protocol(ctx: Context<PauseProtocol>, protocol_id: u8, paused: bool) -> Result<()> {
+        ctx.accounts.protocol_config.protocol_paused = paused;
+
+        emit!(ProtocolPauseChanged {
+            protocol_id,
+            paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_no_front_running(
+        ctx: Context<ProveNoFrontRunning>,
+        computation_offset: u64,
+        intent_id: [u8; 32],
+        execution_timestamp: i64,
+        max_delay_seconds: i64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(intent_id),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
+            Argument::PlaintextI64(execution_timestamp),
+            Argument::PlaintextI64(max_delay_seconds),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveNoFrontRunningCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_no_front_running")]
+    pub fn prove_no_front_running_callback(
+        ctx: Context<ProveNoFrontRunningCallback>,
+        output: ComputationOutputs<ProveNoFrontRunningOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let compliant = match output {
+            ComputationOutputs::Success(ProveNoFrontRunningOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(NoFrontRunningProved {
+            compliant,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn init_rebate_vault(ctx: Context<InitRebateVault>) -> Result<()> {
+        let rebate_vault = &mut ctx.accounts.rebate_vault;
+        rebate_vault.collected_fees = 0;
+        rebate_vault.committed_rebates = 0;
+        rebate_vault.bump = ctx.bumps.rebate_vault;
+        Ok(())
+    }
+
+    /// Deposits collected venue fees into the vault, making them available to
+    /// back maker rebates.
+    pub fn deposit_rebate_fees(ctx: Context<DepositRebateFees>, amount: u64) -> Result<()> {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.depositor.to_account_info(),
+                    to: ctx.accounts.rebate_vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        ctx.accounts.rebate_vault.collected_fees += amount;
+        Ok(())
+    }
+
+    pub fn claim_maker_rebate(ctx: Context<ClaimMakerRebate>) -> Result<()> {
+        // Fees deposited since the last match may back what's still owed.
+        let maker_volume = &mut ctx.accounts.maker_volume;
+        maker_volume.credit_rebate(&mut ctx.accounts.rebate_vault, 0);
+        let amount = maker_volume.claimable_rebate;
+        maker_volume.claimable_rebate = 0;
+
+        // A claim can't take the vault below rent exemption.
+        let vault_info = ctx.accounts.rebate_vault.to_account_info();
+        let reserve = Rent::get()?.minimum_balance(vault_info.data_len());
+        require!(
+            vault_info.lamports().saturating_sub(reserve) >= amount,
+            ErrorCode::InsufficientLiquidity
+        );
+
+        **vault_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.maker.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        let vault = &mut ctx.accounts.rebate_vault;
+        vault.collected_fees -= amount;
+        vault.committed_rebates -= amount;
+
+        emit!(MakerRebateClaimed {
+            maker: ctx.accounts.maker.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn compute_risk_adjusted_score(
+        ctx: Context<ComputeRiskAdjustedScore>,
+        computation_offset: u64,
+        owner_pubkey: [u8; 32],
+        owner_nonce: u128,
+        position_count: u8,
+        mark_prices: [u64; 10],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let mut args = vec![
+            Argument::ArcisPubkey(owner_pubkey),
+            Argument::PlaintextU128(owner_nonce),
+            Argument::PlaintextU8(position_count),
+        ];
+        args.extend(mark_prices.iter().map(|price| Argument::PlaintextU64(*price)));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ComputeRiskAdjustedScoreCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "compute_risk_adjusted_score")]
+    pub fn compute_risk_adjusted_score_callback(
+        ctx: Context<ComputeRiskAdjustedScoreCallback>,
+        output: ComputationOutputs<ComputeRiskAdjustedScoreOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        // The score is encrypted to the owner; no event so fund performance
+        // isn't published on-chain.
+        match output {
+            ComputationOutputs::Success(ComputeRiskAdjustedScoreOutput { field_0: _score }) => Ok(()),
+            _ => reject_callback(ErrorCode::AbortedComputation),
+        }
+    }
+
+    pub fn prove_uncorrelated(
+        ctx: Context<ProveUncorrelated>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        correlated_pairs: Vec<[[u8; 32]; 2]>,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require!(
+            correlated_pairs.len() <= MAX_CORRELATED_PAIRS,
+            ErrorCode::TooManyCorrelatedPairs
+        );
+
+        let mut args = vec![Argument::PlaintextBytes32(position_id)];
+        for i in 0..MAX_CORRELATED_PAIRS {
+            let pair = correlated_pairs.get(i).copied().unwrap_or_default();
+            args.push(Argument::PlaintextBytes32(pair[0]));
+            args.push(Argument::PlaintextBytes32(pair[1]));
+        }
+        args.push(Argument::PlaintextU8(correlated_pairs.len() as u8));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveUncorrelatedCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_uncorrelated")]
+    pub fn prove_uncorrelated_callback(
+        ctx: Context<ProveUncorrelatedCallback>,
+        output: ComputationOutputs<ProveUncorrelatedOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let uncorrelated = match output {
+            ComputationOutputs::Success(ProveUncorrelatedOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(CorrelationChecked {
+            uncorrelated,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_arms_length(
+        ctx: Context<ProveArmsLength>,
+        computation_offset: u64,
+        buy_order_id: [u8; 32],
+        sell_order_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(buy_order_id),
+            Argument::PlaintextBytes32(sell_order_id),
+            Argument::PlaintextI64(ctx.accounts.dark_pool_config.wash_window_seconds),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveArmsLengthCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_arms_length")]
+    pub fn prove_arms_length_callback(
+        ctx: Context<ProveArmsLengthCallback>,
+        output: ComputationOutputs<ProveArmsLengthOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let legitimate = match output {
+            ComputationOutputs::Success(ProveArmsLengthOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(ArmsLengthProved {
+            legitimate,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn compute_fee_tier(
+        ctx: Context<ComputeFeeTier>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        let thresholds = ctx.accounts.protocol_config.fee_schedule.health_thresholds_bps;
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(thresholds[0]),
+            Argument::PlaintextU64(thresholds[1]),
+            Argument::PlaintextU64(thresholds[2]),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ComputeFeeTierCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_account.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "compute_fee_tier")]
+    pub fn compute_fee_tier_callback(
+        ctx: Context<ComputeFeeTierCallback>,
+        output: ComputationOutputs<ComputeFeeTierOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let fee_tier = match output {
+            ComputationOutputs::Success(ComputeFeeTierOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        let position_account = &mut ctx.accounts.position_account;
+        position_account.fee_tier = fee_tier;
+
+        emit!(FeeTierComputed {
+            position_id: position_account.position_id,
+            fee_tier,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn init_risk_cap_tracker(
+        ctx: Context<InitRiskCapTracker>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let tracker = &mut ctx.accounts.risk_cap_tracker;
+        tracker.owner = ctx.accounts.payer.key();
+        tracker.bump = ctx.bumps.risk_cap_tracker;
+
+        ctx.accounts
+            .tracker_state
+            .bind(ctx.accounts.risk_cap_tracker.key(), ctx.bumps.tracker_state);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            vec![],
+            None,
+            vec![InitRiskCapTrackerCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.tracker_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_risk_cap_tracker")]
+    pub fn init_risk_cap_tracker_callback(
+        ctx: Context<InitRiskCapTrackerCallback>,
+        output: ComputationOutputs<InitRiskCapTrackerOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let tracker = match output {
+            ComputationOutputs::Success(InitRiskCapTrackerOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts
+            .tracker_state
+            .store(tracker.nonce, &tracker.ciphertexts)?;
+        Ok(())
+    }
+
+    /// Aggregates the owner's positions and folds the at-risk count into their
+    /// tracker's running max, which `prove_risk_cap_respected` later checks.
+    pub fn aggregate_portfolio_risk(
+        ctx: Context<AggregatePortfolioRisk>,
+        computation_offset: u64,
+        owner_pubkey: [u8; 32],
+        owner_nonce: u128,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::ArcisPubkey(owner_pubkey),
+            Argument::PlaintextU128(owner_nonce),
+            Argument::PlaintextBytes32(ctx.accounts.risk_cap_tracker.key().to_bytes()),
+            Argument::PlaintextU64(RISK_CAP_THRESHOLD_BPS),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AggregatePortfolioRiskCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.tracker_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "aggregate_portfolio_risk")]
+    pub fn aggregate_portfolio_risk_callback(
+        ctx: Context<AggregatePortfolioRiskCallback>,
+        output: ComputationOutputs<AggregatePortfolioRiskOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let tracker = match output {
+            ComputationOutputs::Success(AggregatePortfolioRiskOutput {
+                field_0:
+                    AggregatePortfolioRiskOutputStruct0 {
+                        field_0: _metrics,
+                        field_1,
+                    },
+            }) => field_1,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        // The metrics are encrypted to the owner; only the tracker is kept.
+        ctx.accounts
+            .tracker_state
+            .store(tracker.nonce, &tracker.ciphertexts)?;
+
+        emit!(PortfolioRiskAggregated {
+            tracker: ctx.accounts.tracker_state.subject,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_risk_cap_respected(
+        ctx: Context<ProveRiskCapRespected>,
+        computation_offset: u64,
+        cap: u8,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(ctx.accounts.risk_cap_tracker.key().to_bytes()),
+            Argument::PlaintextU8(cap),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveRiskCapRespectedCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_risk_cap_respected")]
+    pub fn prove_risk_cap_respected_callback(
+        ctx: Context<ProveRiskCapRespectedCallback>,
+        output: ComputationOutputs<ProveRiskCapRespectedOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let respected = match output {
+            ComputationOutputs::Success(ProveRiskCapRespectedOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(RiskCapProved {
+            respected,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn match_and_topup(
+        ctx: Context<MatchAndTopup>,
+        computation_offset: u64,
+        buy_order_id: [u8; 32],
+        sell_order_id: [u8; 32],
+        position_id: [u8; 32],
+        collateral_delta: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        // The later order is the taker, and only the taker's own position can
+        // be topped up.
+        let taker = if ctx.accounts.buy_order.sequence > ctx.accounts.sell_order.sequence {
+            ctx.accounts.buy_order.owner
+        } else {
+            ctx.accounts.sell_order.owner
+        };
+        require_keys_eq!(ctx.accounts.position_account.owner, taker, ErrorCode::Unauthorized);
+
+        // The maker is credited exactly as in `match_dark_pool_orders`.
+        let maker = if ctx.accounts.buy_order.sequence < ctx.accounts.sell_order.sequence {
+            ctx.accounts.buy_order.owner
+        } else {
+            ctx.accounts.sell_order.owner
+        };
+        require_keys_eq!(ctx.accounts.maker_volume.maker, maker, ErrorCode::Unauthorized);
+        require!(
+            ctx.accounts.buy_order.unsettled_fill_amount == 0,
+            ErrorCode::SettlementPending
+        );
+
+        let caller = ctx.accounts.payer.key();
+        require_priority_fee_consent(&ctx.accounts.buy_order, &ctx.accounts.sell_order, &caller)?;
+        let now = Clock::get()?.unix_timestamp;
+        let cooldown = ctx.accounts.dark_pool_config.match_cooldown_seconds;
+        for order in [&mut ctx.accounts.buy_order, &mut ctx.accounts.sell_order] {
+            require!(order.is_matchable(), ErrorCode::ConditionalOrderNotTriggered);
+            order.stamp_match_attempt(&caller, now, cooldown)?;
+        }
+
+        let maker_volume = &mut ctx.accounts.maker_volume;
+        let reset_realized_window =
+            now >= maker_volume.window_started_at + REALIZED_VOLUME_WINDOW_SECONDS;
+        maker_volume.pending_window_start = if reset_realized_window { now } else { 0 };
+
+        // The topup is funded from the position's delegated source, reserved
+        // here and paid in the callback only if the orders match.
+        ctx.accounts.topup_authorization.reserve(collateral_delta)?;
+
+        ctx.accounts
+            .volume_state
+            .bind(ctx.accounts.maker_volume.key(), ctx.bumps.volume_state);
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+        ctx.accounts
+            .buy_order_state
+            .bind(ctx.accounts.buy_order.key(), ctx.bumps.buy_order_state);
+        ctx.accounts
+            .sell_order_state
+            .bind(ctx.accounts.sell_order.key(), ctx.bumps.sell_order_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(buy_order_id),
+            Argument::PlaintextBytes32(sell_order_id),
+            Argument::PlaintextBytes32(maker.to_bytes()),
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(ctx.accounts.dark_pool_config.price_tolerance_bps),
+            Argument::PlaintextU8(ctx.accounts.dark_pool_config.midpoint_round_toward),
+            Argument::PlaintextU64(ctx.accounts.dark_pool_config.maker_rebate_bps),
+            Argument::PlaintextU64(REBATE_RELEASE_QUANTUM),
+            Argument::PlaintextBool(ctx.accounts.dark_pool_config.settlement_enabled),
+            Argument::PlaintextBool(reset_realized_window),
+            Argument::PlaintextU64(collateral_delta),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+        ctx.accounts.match_sequence_counter.bump = ctx.bumps.match_sequence_counter;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![MatchAndTopupCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.match_sequence_counter.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.buy_order.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.sell_order.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.topup_authorization.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.source.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.destination.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.token_program.key(),
+                    is_writable: false,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.buy_order_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.sell_order_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.maker_volume.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.volume_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.rebate_vault.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.buy_activity.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.sell_activity.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.treasury.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.buy_marker.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.sell_marker.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "match_and_topup")]
+    pub fn match_and_topup_callback(
+        ctx: Context<MatchAndTopupCallback>,
+        output: ComputationOutputs<MatchAndTopupOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let (
+            match_result,
+            failure_reason,
+            maker_volume,
+            rebate,
+            buy_order,
+            sell_order,
+            buy_replenished,
+            sell_replenished,
+            fill_amount,
+            execution_price,
+            buy_exhausted,
+            sell_exhausted,
+            position,
+        ) = match output {
+            ComputationOutputs::Success(MatchAndTopupOutput {
+                field_0:
+                    MatchAndTopupOutputStruct0 {
+                        field_0,
+                        field_1,
+                        field_2,
+                        field_3,
+                        field_4,
+                        field_5,
+                        field_6,
+                        field_7,
+                        field_8,
+                        field_9,
+                        field_10,
+                        field_11,
+                        field_12,
+                    },
+            }) => (
+                field_0, field_1, field_2, field_3, field_4, field_5, field_6, field_7, field_8,
+                field_9, field_10, field_11, field_12,
+            ),
+            _ => {
+                let amount = ctx.accounts.topup_authorization.release();
+                emit!(AutoTopupAborted { amount, timestamp });
+                return Ok(());
+            }
+        };
+
+        if match_result {
+            let authorization = &ctx.accounts.topup_authorization;
+            transfer_delegated_tokens(
+                &ctx.accounts.token_program,
+                &ctx.accounts.source,
+                &ctx.accounts.destination,
+                &authorization.to_account_info(),
+                &[TOPUP_AUTH_SEED, authorization.position_id.as_ref(), &[authorization.bump]],
+                authorization.pending_topup_usd,
+            )?;
+            ctx.accounts.topup_authorization.pending_topup_usd = 0;
+
+            ctx.accounts
+                .position_state
+                .store(position.nonce, &position.ciphertexts)?;
+        } else {
+            ctx.accounts.topup_authorization.release();
+        }
+
+        ctx.accounts
+            .volume_state
+            .store(maker_volume.nonce, &maker_volume.ciphertexts)?;
+        ctx.accounts
+            .buy_order_state
+            .store(buy_order.nonce, &buy_order.ciphertexts)?;
+        ctx.accounts
+            .sell_order_state
+            .store(sell_order.nonce, &sell_order.ciphertexts)?;
+
+        record_match(
+            MatchParties {
+                buy_order: &mut ctx.accounts.buy_order,
+                sell_order: &mut ctx.accounts.sell_order,
+                buy_activity: &mut ctx.accounts.buy_activity,
+                sell_activity: &mut ctx.accounts.sell_activity,
+                maker_volume: &mut ctx.accounts.maker_volume,
+                rebate_vault: &mut ctx.accounts.rebate_vault,
+                match_sequence_counter: &mut ctx.accounts.match_sequence_counter,
+            },
+            &MatchOutcome {
+                is_matched: match_result,
+                failure_reason,
+                rebate,
+                buy_replenished,
+                sell_replenished,
+                fill_amount,
+                execution_price,
+            },
+            timestamp,
+        );
+
+        // The circuit only applies the topup on a match.
+        if match_result {
+            collect_priority_fee(
+                &mut ctx.accounts.buy_order,
+                &mut ctx.accounts.sell_order,
+                &ctx.accounts.treasury,
+            )?;
+            emit!(HealthFactorUpdated { timestamp });
+        }
+        release_exhausted_markers(
+            ctx.program_id,
+            [
+                (&ctx.accounts.buy_order, &ctx.accounts.buy_marker, buy_exhausted),
+                (&ctx.accounts.sell_order, &ctx.accounts.sell_marker, sell_exhausted),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn prove_rewards_eligible(
+        ctx: Context<ProveRewardsEligible>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        min_collateral_usd: u64,
+        min_hold_seconds: i64,
+        max_leverage_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(min_collateral_usd),
+            Argument::PlaintextI64(min_hold_seconds),
+            Argument::PlaintextU64(max_leverage_bps),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveRewardsEligibleCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_rewards_eligible")]
+    pub fn prove_rewards_eligible_callback(
+        ctx: Context<ProveRewardsEligibleCallback>,
+        output: ComputationOutputs<ProveRewardsEligibleOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let eligible = match output {
+            ComputationOutputs::Success(ProveRewardsEligibleOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(RewardsEligibilityProved {
+            eligible,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Moves the tokens for a recorded match through the dark pool's settlement
+    /// program. Accounts that program needs go in `remaining_accounts`. The
+    /// match stays unsettled until this succeeds, so it can be retried.
+    pub fn settle_match<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleMatch<'info>>,
+        _buy_order_id: [u8; 32],
+        _sell_order_id: [u8; 32],
+    ) -> Result<()> {
+        let buy_order = &ctx.accounts.buy_order;
+        require!(buy_order.unsettled_fill_amount > 0, ErrorCode::NothingToSettle);
+        require!(
+            buy_order.unsettled_counterparty == ctx.accounts.sell_order.order_id,
+            ErrorCode::InvalidOrderAccount
+        );
+
+        let fill_amount = buy_order.unsettled_fill_amount;
+        let execution_price = buy_order.unsettled_execution_price;
+
+        let mut data = anchor_lang::solana_program::hash::hash(b"global:settle_match").to_bytes()[..8].to_vec();
+        data.extend_from_slice(&fill_amount.to_le_bytes());
+        data.extend_from_slice(&execution_price.to_le_bytes());
+
+        // The settlement authority PDA signs, so the settlement program can
+        // tell a real settlement from anyone calling it with the same data.
+        let mut metas = vec![
+            AccountMeta::new_readonly(ctx.accounts.settlement_authority.key(), true),
+            AccountMeta::new_readonly(ctx.accounts.buy_order.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.sell_order.key(), false),
+        ];
+        let mut infos = vec![
+            ctx.accounts.settlement_authority.to_account_info(),
+            ctx.accounts.buy_order.to_account_info(),
+            ctx.accounts.sell_order.to_account_info(),
+        ];
+        for account in ctx.remaining_accounts {
+            metas.push(AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            });
+            infos.push(account.clone());
+        }
+        infos.push(ctx.accounts.settlement_program.to_account_info());
+
+        anchor_lang::solana_program::program::invoke_signed(
+            &anchor_lang::solana_program::instruction::Instruction {
+                program_id: ctx.accounts.settlement_program.key(),
+                accounts: metas,
+                data,
+            },
+            &infos,
+            &[&[SETTLEMENT_AUTHORITY_SEED, &[ctx.bumps.settlement_authority]]],
+        )?;
+
+        let buy_order = &mut ctx.accounts.buy_order;
+        buy_order.unsettled_fill_amount = 0;
+        buy_order.unsettled_execution_price = 0;
+        buy_order.unsettled_counterparty = [0u8; 32];
+
+        // The follow-up computation can't be queued from here without the full
+        // Arcium account set, so the position is flagged for a keeper instead.
+        // Settlement never waits on the refresh computation itself.
+        request_health_refresh(&ctx.accounts.buy_order, ctx.accounts.buy_position.as_deref_mut())?;
+        request_health_refresh(&ctx.accounts.sell_order, ctx.accounts.sell_position.as_deref_mut())?;
+
+        emit!(MatchSettled {
+            buy_order_id: ctx.accounts.buy_order.order_id,
+            sell_order_id: ctx.accounts.sell_order.order_id,
+            fill_amount,
+            execution_price,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_min_collateral_diversity(
+        ctx: Context<ProveMinCollateralDiversity>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        min_distinct_mints: u8,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        require!(
+            min_distinct_mints as usize <= MAX_COLLATERAL_LEGS,
+            ErrorCode::TooManyLegs
+        );
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU8(min_distinct_mints),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveMinCollateralDiversityCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_min_collateral_diversity")]
+    pub fn prove_min_collateral_diversity_callback(
+        ctx: Context<ProveMinCollateralDiversityCallback>,
+        output: ComputationOutputs<ProveMinCollateralDiversityOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let satisfied = match output {
+            ComputationOutputs::Success(ProveMinCollateralDiversityOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(CollateralDiversityProved {
+            satisfied,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_min_healthy_count(
+        ctx: Context<ProveMinHealthyCount>,
+        computation_offset: u64,
+        position_count: u8,
+        threshold_bps: u64,
+        min_healthy: u8,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(
+            position_count <= MAX_HEALTHY_COUNT_POSITIONS,
+            ErrorCode::TooManyPositions
+        );
+
+        let args = vec![
+            Argument::PlaintextU8(position_count),
+            Argument::PlaintextU64(threshold_bps),
+            Argument::PlaintextU8(min_healthy),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveMinHealthyCountCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_min_healthy_count")]
+    pub fn prove_min_healthy_count_callback(
+        ctx: Context<ProveMinHealthyCountCallback>,
+        output: ComputationOutputs<ProveMinHealthyCountOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let satisfied = match output {
+            ComputationOutputs::Success(ProveMinHealthyCountOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(MinHealthyCountProved {
+            satisfied,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_position_parity(
+        ctx: Context<ProvePositionParity>,
+        computation_offset: u64,
+        position_a_id: [u8; 32],
+        position_b_id: [u8; 32],
+        tolerance_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_a)?;
+        require_current_schema(&ctx.accounts.position_b)?;
+        require!(tolerance_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_a_id),
+            Argument::PlaintextBytes32(position_b_id),
+            Argument::PlaintextU64(tolerance_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProvePositionParityCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_position_parity")]
+    pub fn prove_position_parity_callback(
+        ctx: Context<ProvePositionParityCallback>,
+        output: ComputationOutputs<ProvePositionParityOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let parity = match output {
+            ComputationOutputs::Success(ProvePositionParityOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(PositionParityProved {
+            parity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Proves the intent's latest execution beat a direct swap at the
+    /// registered feeds' prices. The output is the one the execution
+    /// recorded, so an aggregator can't pick a flattering number.
+    pub fn prove_route_optimal(
+        ctx: Context<ProveRouteOptimal>,
+        computation_offset: u64,
+        intent_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(ctx.accounts.swap_intent.last_output > 0, ErrorCode::SwapNotExecuted);
+
+        require_oracle_fresh(&ctx.accounts.from_oracle, MAX_ORACLE_AGE_SECONDS)?;
+        require_oracle_fresh(&ctx.accounts.to_oracle, MAX_ORACLE_AGE_SECONDS)?;
+        let (from_price, _) = oracle_price_and_confidence(&ctx.accounts.from_oracle)?;
+        let (to_price, _) = oracle_price_and_confidence(&ctx.accounts.to_oracle)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(intent_id),
+            Argument::PlaintextU64(ctx.accounts.swap_intent.last_output),
+            Argument::PlaintextU64(from_price),
+            Argument::PlaintextU64(to_price),
+            Argument::PlaintextBytes32(ctx.accounts.from_oracle_feed.mint.to_bytes()),
+            Argument::PlaintextBytes32(ctx.accounts.to_oracle_feed.mint.to_bytes()),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveRouteOptimalCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_route_optimal")]
+    pub fn prove_route_optimal_callback(
+        ctx: Context<ProveRouteOptimalCallback>,
+        output: ComputationOutputs<ProveRouteOptimalOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (optimal, feeds_match) = match output {
+            ComputationOutputs::Success(ProveRouteOptimalOutput {
+                field_0: ProveRouteOptimalOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+        if !feeds_match {
+            return reject_callback(ErrorCode::OracleMintMismatch);
+        }
+
+        emit!(RouteOptimalityProved {
+            optimal,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn estimate_cascade_risk(
+        ctx: Context<EstimateCascadeRisk>,
+        computation_offset: u64,
+        owner_pubkey: [u8; 32],
+        owner_nonce: u128,
+        liquidation_price_impact_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(liquidation_price_impact_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let args = vec![
+            Argument::ArcisPubkey(owner_pubkey),
+            Argument::PlaintextU128(owner_nonce),
+            Argument::PlaintextU64(liquidation_price_impact_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![EstimateCascadeRiskCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "estimate_cascade_risk")]
+    pub fn estimate_cascade_risk_callback(
+        ctx: Context<EstimateCascadeRiskCallback>,
+        output: ComputationOutputs<EstimateCascadeRiskOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let additional_at_risk = match output {
+            ComputationOutputs::Success(EstimateCascadeRiskOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(CascadeRiskEstimated {
+            additional_at_risk,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn init_order_activity(
+        ctx: Context<InitOrderActivity>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let owner = ctx.accounts.payer.key();
+
+        let order_activity = &mut ctx.accounts.order_activity;
+        order_activity.owner = owner;
+        order_activity.pending_placements = 0;
+        order_activity.pending_cancels = 0;
+        order_activity.pending_fills = 0;
+        order_activity.bump = ctx.bumps.order_activity;
+
+        ctx.accounts
+            .activity_state
+            .bind(ctx.accounts.order_activity.key(), ctx.bumps.activity_state);
+
+        let args = vec![
+            Argument::PlaintextBytes32(owner.to_bytes()),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitOrderActivityCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.activity_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_order_activity")]
+    pub fn init_order_activity_callback(
+        ctx: Context<InitOrderActivityCallback>,
+        output: ComputationOutputs<InitOrderActivityOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let activity = match output {
+            ComputationOutputs::Success(InitOrderActivityOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts
+            .activity_state
+            .store(activity.nonce, &activity.ciphertexts)?;
+        Ok(())
+    }
+
+    /// Moves an owner's pending on-chain counts into their encrypted totals.
+    /// The callback stores the new totals and only then subtracts the counts
+    /// it folded, so an aborted computation drops nothing and activity
+    /// recorded while it runs stays pending.
+    pub fn record_order_activity(
+        ctx: Context<RecordOrderActivity>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        ctx.accounts
+            .activity_state
+            .bind(ctx.accounts.order_activity.key(), ctx.bumps.activity_state);
+
+        let order_activity = &ctx.accounts.order_activity;
+        let args = vec![
+            Argument::PlaintextBytes32(order_activity.owner.to_bytes()),
+            Argument::PlaintextU64(order_activity.pending_placements),
+            Argument::PlaintextU64(order_activity.pending_cancels),
+            Argument::PlaintextU64(order_activity.pending_fills),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RecordOrderActivityCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.order_activity.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.activity_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "record_order_activity")]
+    pub fn record_order_activity_callback(
+        ctx: Context<RecordOrderActivityCallback>,
+        output: ComputationOutputs<RecordOrderActivityOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (activity, placed, cancelled, filled) = match output {
+            ComputationOutputs::Success(RecordOrderActivityOutput {
+                field_0: RecordOrderActivityOutputStruct0 { field_0, field_1, field_2, field_3 },
+            }) => (field_0, field_1, field_2, field_3),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts
+            .activity_state
+            .store(activity.nonce, &activity.ciphertexts)?;
+
+        let order_activity = &mut ctx.accounts.order_activity;
+        order_activity.pending_placements = order_activity.pending_placements.saturating_sub(placed);
+        order_activity.pending_cancels = order_activity.pending_cancels.saturating_sub(cancelled);
+        order_activity.pending_fills = order_activity.pending_fills.saturating_sub(filled);
+        Ok(())
+    }
+
+    pub fn prove_not_spoofing(
+        ctx: Context<ProveNotSpoofing>,
+        computation_offset: u64,
+        max_cancel_to_fill_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let order_activity = &ctx.accounts.order_activity;
+        let args = vec![
+            Argument::PlaintextBytes32(order_activity.owner.to_bytes()),
+            Argument::PlaintextU64(order_activity.pending_cancels),
+            Argument::PlaintextU64(order_activity.pending_fills),
+            Argument::PlaintextU64(max_cancel_to_fill_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveNotSpoofingCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_not_spoofing")]
+    pub fn prove_not_spoofing_callback(
+        ctx: Context<ProveNotSpoofingCallback>,
+        output: ComputationOutputs<ProveNotSpoofingOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let clean = match output {
+            ComputationOutputs::Success(ProveNotSpoofingOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(SpoofingCheckPassed {
+            clean,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_floor_held(
+        ctx: Context<ProveFloorHeld>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        floor_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(floor_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveFloorHeldCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_floor_held")]
+    pub fn prove_floor_held_callback(
+        ctx: Context<ProveFloorHeldCallback>,
+        output: ComputationOutputs<ProveFloorHeldOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let held = match output {
+            ComputationOutputs::Success(ProveFloorHeldOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(FloorHeldProved {
+            held,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_swap_low_impact(
+        ctx: Context<ProveSwapLowImpact>,
+        computation_offset: u64,
+        intent_id: [u8; 32],
+        available_liquidity: u64,
+        max_impact_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(max_impact_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let args = vec![
+            Argument::PlaintextBytes32(intent_id),
+            Argument::PlaintextU64(available_liquidity),
+            Argument::PlaintextU64(max_impact_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveSwapLowImpactCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_swap_low_impact")]
+    pub fn prove_swap_low_impact_callback(
+        ctx: Context<ProveSwapLowImpactCallback>,
+        output: ComputationOutputs<ProveSwapLowImpactOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let low_impact = match output {
+            ComputationOutputs::Success(ProveSwapLowImpactOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(LowImpactProved {
+            low_impact,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Ranks a position against stored peer positions, passed in
+    /// `remaining_accounts` in the same order as `peer_position_ids`.
+    pub fn prove_top_tier<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProveTopTier<'info>>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        peer_position_ids: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require!(peer_position_ids.len() <= MAX_TOP_TIER_PEERS, ErrorCode::TooManyPositions);
+        require!(peer_position_ids.len() >= MIN_TOP_TIER_PEERS, ErrorCode::TooFewPeers);
+        require!(
+            ctx.remaining_accounts.len() == peer_position_ids.len(),
+            ErrorCode::InvalidPositionAccount
+        );
+
+        // Peers have to be distinct stored positions other than the user's,
+        // so the group can't be padded with copies or fabricated values.
+        let mut peer_ids = [[0u8; 32]; MAX_TOP_TIER_PEERS];
+        for (i, (peer_id, peer_info)) in peer_position_ids
+            .iter()
+            .zip(ctx.remaining_accounts.iter())
+            .enumerate()
+        {
+            require!(
+                *peer_id != position_id && !peer_position_ids[..i].contains(peer_id),
+                ErrorCode::InvalidPositionAccount
+            );
+            let (expected, _) =
+                Pubkey::find_program_address(&[POSITION_PDA_SEED, peer_id.as_ref()], ctx.program_id);
+            require_keys_eq!(peer_info.key(), expected, ErrorCode::InvalidPositionAccount);
+
+            let peer = PositionAccount::try_deserialize(&mut &peer_info.data.borrow()[..])?;
+            require_current_schema(&peer)?;
+            peer_ids[i] = *peer_id;
+        }
+
+        let mut args = vec![Argument::PlaintextBytes32(position_id)];
+        args.extend(peer_ids.iter().map(|id| Argument::PlaintextBytes32(*id)));
+        args.push(Argument::PlaintextU8(peer_position_ids.len() as u8));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveTopTierCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_top_tier")]
+    pub fn prove_top_tier_callback(
+        ctx: Context<ProveTopTierCallback>,
+        output: ComputationOutputs<ProveTopTierOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let in_top_tier = match output {
+            ComputationOutputs::Success(ProveTopTierOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(TopTierProved {
+            in_top_tier,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Keeper-only gate to call before re-queuing an aborted computation of
+    /// `kind` on `target`. Each retry doubles the wait before the next one.
+    /// The last allowed retry marks the computation as permanently failed.
+    pub fn retry_computation(
+        ctx: Context<RetryComputation>,
+        kind: ComputationKind,
+        target: [u8; 32],
+    ) -> Result<()> {
+        require!(kind.is_retryable(), ErrorCode::ComputationNotRetryable);
+        ctx.accounts
+            .keeper_registry
+            .require_assigned(&ctx.accounts.keeper.key(), Clock::get()?.slot)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let status = &mut ctx.accounts.computation_status;
+        require!(status.in_flight, ErrorCode::ComputationNotInFlight);
+        require!(!status.permanently_failed, ErrorCode::ComputationPermanentlyFailed);
+        require!(now >= status.next_retry_after, ErrorCode::RetryTooSoon);
+
+        status.retry_granted = true;
+        status.retry_count += 1;
+        if status.retry_count >= MAX_COMPUTATION_RETRIES {
+            status.permanently_failed = true;
+        } else {
+            status.next_retry_after = now + (RETRY_BASE_BACKOFF_SECONDS << status.retry_count);
+        }
+
+        emit!(ComputationRetryScheduled {
+            kind,
+            target,
+            retry_count: status.retry_count,
+            next_retry_after: status.next_retry_after,
+            permanently_failed: status.permanently_failed,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Sets the health at which keepers may repay debt on the owner's behalf,
+    /// and the health the repayment restores. `source` must delegate to the
+    /// config PDA; repayments are drawn from it into `repayment`, the
+    /// position's debt account.
+    pub fn configure_auto_deleverage(
+        ctx: Context<ConfigureAutoDeleverage>,
+        position_id: [u8; 32],
+        trigger_health_bps: u64,
+        target_health_bps: u64,
+        source: Pubkey,
+        repayment: Pubkey,
+    ) -> Result<()> {
+        require!(
+            trigger_health_bps < target_health_bps,
+            ErrorCode::InvalidDeleverageConfig
+        );
+
+        let config = &mut ctx.accounts.deleverage_config;
+        config.position_id = position_id;
+        config.trigger_health_bps = trigger_health_bps;
+        config.target_health_bps = target_health_bps;
+        config.source = source;
+        config.repayment = repayment;
+        config.bump = ctx.bumps.deleverage_config;
+        Ok(())
+    }
+
+    /// Keeper-callable. The circuit checks the configured trigger against the
+    /// stored encrypted health, so nothing is repaid unless health really is
+    /// below it; the callback then pays the repayment from the owner's
+    /// delegated source and writes the reduced position back.
+    pub fn execute_auto_deleverage(
+        ctx: Context<ExecuteAutoDeleverage>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        ctx.accounts
+            .keeper_registry
+            .require_assigned(&ctx.accounts.payer.key(), Clock::get()?.slot)?;
+
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+
+        let config = &ctx.accounts.deleverage_config;
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(config.trigger_health_bps),
+            Argument::PlaintextU64(config.target_health_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ExecuteAutoDeleverageCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.deleverage_config.key(),
+                    is_writable: false,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.source.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.repayment.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.token_program.key(),
+                    is_writable: false,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "apply_auto_deleverage")]
+    pub fn apply_auto_deleverage_callback(
+        ctx: Context<ExecuteAutoDeleverageCallback>,
+        output: ComputationOutputs<ApplyAutoDeleverageOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (position, debt_reduced) = match output {
+            ComputationOutputs::Success(ApplyAutoDeleverageOutput {
+                field_0: ApplyAutoDeleverageOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        // The reduced debt only sticks if the repayment goes through.
+        let config = &ctx.accounts.deleverage_config;
+        transfer_delegated_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.source,
+            &ctx.accounts.repayment,
+            &config.to_account_info(),
+            &[AUTO_DELEVERAGE_SEED, config.position_id.as_ref(), &[config.bump]],
+            debt_reduced,
+        )?;
+        ctx.accounts
+            .position_state
+            .store(position.nonce, &position.ciphertexts)?;
+
+        emit!(AutoDeleverageExecuted {
+            debt_reduced,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_terms_match(
+        ctx: Context<ProveTermsMatch>,
+        computation_offset: u64,
+        order_a_id: [u8; 32],
+        order_b_id: [u8; 32],
+        price_tolerance_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(price_tolerance_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let args = vec![
+            Argument::PlaintextBytes32(order_a_id),
+            Argument::PlaintextBytes32(order_b_id),
+            Argument::PlaintextU64(price_tolerance_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveTermsMatchCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_terms_match")]
+    pub fn prove_terms_match_callback(
+        ctx: Context<ProveTermsMatchCallback>,
+        output: ComputationOutputs<ProveTermsMatchOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let terms_match = match output {
+            ComputationOutputs::Success(ProveTermsMatchOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(TermsMatchProved {
+            terms_match,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_liquidation_cooldown_passed(
+        ctx: Context<ProveLiquidationCooldownPassed>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        cooldown_seconds: i64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require!(cooldown_seconds >= 0, ErrorCode::InvalidLiquidationCooldown);
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextI64(Clock::get()?.unix_timestamp),
+            Argument::PlaintextI64(cooldown_seconds),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveLiquidationCooldownPassedCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_liquidation_cooldown_passed")]
+    pub fn prove_liquidation_cooldown_passed_callback(
+        ctx: Context<ProveLiquidationCooldownPassedCallback>,
+        output: ComputationOutputs<ProveLiquidationCooldownPassedOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let passed = match output {
+            ComputationOutputs::Success(ProveLiquidationCooldownPassedOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(LiquidationCooldownProved {
+            passed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_health_with_funding(
+        ctx: Context<ProveHealthWithFunding>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        pending_funding_usd: i64,
+        threshold_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextI64(pending_funding_usd),
+            Argument::PlaintextU64(threshold_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveHealthWithFundingCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_health_with_funding")]
+    pub fn prove_health_with_funding_callback(
+        ctx: Context<ProveHealthWithFundingCallback>,
+        output: ComputationOutputs<ProveHealthWithFundingOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let is_healthy = match output {
+            ComputationOutputs::Success(ProveHealthWithFundingOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(FundingAdjustedHealthProved {
+            is_healthy,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn compute_book_imbalance(
+        ctx: Context<ComputeBookImbalance>,
+        computation_offset: u64,
+        order_ids: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        require!(order_ids.len() <= MAX_IMBALANCE_ORDERS, ErrorCode::TooManyOrders);
+
+        let mut ids = [[0u8; 32]; MAX_IMBALANCE_ORDERS];
+        ids[..order_ids.len()].copy_from_slice(&order_ids);
+
+        let mut args: Vec<Argument> = ids.iter().map(|id| Argument::PlaintextBytes32(*id)).collect();
+        args.push(Argument::PlaintextU8(order_ids.len() as u8));
+        args.push(Argument::PlaintextI64(Clock::get()?.unix_timestamp));
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ComputeBookImbalanceCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "compute_book_imbalance")]
+    pub fn compute_book_imbalance_callback(
+        ctx: Context<ComputeBookImbalanceCallback>,
+        output: ComputationOutputs<ComputeBookImbalanceOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let imbalance_bucket = match output {
+            ComputationOutputs::Success(ComputeBookImbalanceOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(BookImbalanceComputed {
+            imbalance_bucket,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Previews the slippage `execute_private_swap` would allow against the
+    /// given feed's current confidence interval.
+    pub fn compute_slippage_from_confidence(
+        ctx: Context<ComputeSlippageFromConfidence>,
+        computation_offset: u64,
+        base_slippage_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_oracle_fresh(&ctx.accounts.oracle, MAX_ORACLE_AGE_SECONDS)?;
+        let (oracle_price, confidence) = oracle_price_and_confidence(&ctx.accounts.oracle)?;
+
+        let args = vec![
+            Argument::PlaintextU64(oracle_price),
+            Argument::PlaintextU64(confidence),
+            Argument::PlaintextU64(base_slippage_bps),
+            Argument::PlaintextU64(MAX_CONFIDENCE_SLIPPAGE_BPS),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ComputeSlippageFromConfidenceCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "compute_slippage_from_confidence")]
+    pub fn compute_slippage_from_confidence_callback(
+        ctx: Context<ComputeSlippageFromConfidenceCallback>,
+        output: ComputationOutputs<ComputeSlippageFromConfidenceOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let slippage_bps = match output {
+            ComputationOutputs::Success(ComputeSlippageFromConfidenceOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(ConfidenceSlippageComputed {
+            slippage_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_under_credit_line(
+        ctx: Context<ProveUnderCreditLine>,
+        computation_offset: u64,
+        credit_limit_usd: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextU64(credit_limit_usd),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveUnderCreditLineCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_under_credit_line")]
+    pub fn prove_under_credit_line_callback(
+        ctx: Context<ProveUnderCreditLineCallback>,
+        output: ComputationOutputs<ProveUnderCreditLineOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let under_limit = match output {
+            ComputationOutputs::Success(ProveUnderCreditLineOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(CreditLineProved {
+            under_limit,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_realized_volume_tier(
+        ctx: Context<ProveRealizedVolumeTier>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let maker_volume = &ctx.accounts.maker_volume;
+        let window_expired = Clock::get()?.unix_timestamp
+            >= maker_volume.window_started_at + REALIZED_VOLUME_WINDOW_SECONDS;
+
+        let args = vec![
+            Argument::PlaintextBytes32(maker_volume.maker.to_bytes()),
+            Argument::PlaintextBool(window_expired),
+            Argument::PlaintextU64(VOLUME_TIER_THRESHOLDS[0]),
+            Argument::PlaintextU64(VOLUME_TIER_THRESHOLDS[1]),
+            Argument::PlaintextU64(VOLUME_TIER_THRESHOLDS[2]),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveRealizedVolumeTierCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_realized_volume_tier")]
+    pub fn prove_realized_volume_tier_callback(
+        ctx: Context<ProveRealizedVolumeTierCallback>,
+        output: ComputationOutputs<ProveRealizedVolumeTierOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let tier = match output {
+            ComputationOutputs::Success(ProveRealizedVolumeTierOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(RealizedVolumeTierProved {
+            tier,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_clean_source(
+        ctx: Context<ProveCleanSource>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveCleanSourceCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_clean_source")]
+    pub fn prove_clean_source_callback(
+        ctx: Context<ProveCleanSourceCallback>,
+        output: ComputationOutputs<ProveCleanSourceOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let clean = match output {
+            ComputationOutputs::Success(ProveCleanSourceOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(SourceProvenanceProved {
+            clean,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Reports a risk level escalated by the bound feed's confidence interval.
+    /// Informational only: it doesn't touch the position's escalation state.
+    pub fn calculate_liquidation_risk_weighted(
+        ctx: Context<CalculateLiquidationRiskWeighted>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        price_impact_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.protocol_config.max_oracle_age_seconds,
+        )?;
+        let (oracle_price, confidence) = oracle_price_and_confidence(&ctx.accounts.oracle)?;
+        let confidence_bps = (confidence as u128 * 10000 / oracle_price as u128)
+            .min(u64::MAX as u128) as u64;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(price_impact_bps),
+            Argument::PlaintextU64(ctx.accounts.protocol_config.band_width_bps),
+            Argument::PlaintextU64(confidence_bps),
+            Argument::PlaintextU8(ctx.accounts.protocol_config.reveal_precision),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CalculateLiquidationRiskWeightedCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "calculate_liquidation_risk_weighted")]
+    pub fn calculate_liquidation_risk_weighted_callback(
+        ctx: Context<CalculateLiquidationRiskWeightedCallback>,
+        output: ComputationOutputs<CalculateLiquidationRiskWeightedOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let risk_level = match output {
+            ComputationOutputs::Success(CalculateLiquidationRiskWeightedOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(WeightedRiskCalculated {
+            risk_level,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_portfolio_neutral(
+        ctx: Context<ProvePortfolioNeutral>,
+        computation_offset: u64,
+        max_bias_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(max_bias_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let args = vec![
+            Argument::PlaintextU64(max_bias_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProvePortfolioNeutralCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_portfolio_neutral")]
+    pub fn prove_portfolio_neutral_callback(
+        ctx: Context<ProvePortfolioNeutralCallback>,
+        output: ComputationOutputs<ProvePortfolioNeutralOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let neutral = match output {
+            ComputationOutputs::Success(ProvePortfolioNeutralOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(PortfolioNeutralityProved {
+            neutral,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_debt_diversified(
+        ctx: Context<ProveDebtDiversified>,
+        computation_offset: u64,
+        max_debt_concentration_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require!(max_debt_concentration_bps <= 10000, ErrorCode::InvalidBasisPoints);
+
+        let args = vec![
+            Argument::PlaintextU64(max_debt_concentration_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveDebtDiversifiedCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_debt_diversified")]
+    pub fn prove_debt_diversified_callback(
+        ctx: Context<ProveDebtDiversifiedCallback>,
+        output: ComputationOutputs<ProveDebtDiversifiedOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let diversified = match output {
+            ComputationOutputs::Success(ProveDebtDiversifiedOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(DebtDiversificationProved {
+            diversified,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Prices the position's collateral at the bound feed's current price and
+    /// at `projected_price`; the resulting buffer is only readable by the owner.
+    pub fn compute_margin_call_buffer(
+        ctx: Context<ComputeMarginCallBuffer>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        owner_pubkey: [u8; 32],
+        owner_nonce: u128,
+        projected_price: u64,
+        margin_call_health_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.protocol_config.max_oracle_age_seconds,
+        )?;
+        require!(projected_price > 0, ErrorCode::InvalidProjectedPrice);
+        let (current_price, _confidence) = oracle_price_and_confidence(&ctx.accounts.oracle)?;
+
+        let args = vec![
+            Argument::ArcisPubkey(owner_pubkey),
+            Argument::PlaintextU128(owner_nonce),
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(current_price),
+            Argument::PlaintextU64(projected_price),
+            Argument::PlaintextU64(margin_call_health_bps),
+            Argument::PlaintextU8(ctx.accounts.protocol_config.reveal_precision),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ComputeMarginCallBufferCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "compute_margin_call_buffer")]
+    pub fn compute_margin_call_buffer_callback(
+        ctx: Context<ComputeMarginCallBufferCallback>,
+        output: ComputationOutputs<ComputeMarginCallBufferOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let buffer = match output {
+            ComputationOutputs::Success(ComputeMarginCallBufferOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(MarginCallBufferComputed {
+            buffer_bucket: buffer.ciphertexts[0],
+            nonce: buffer.nonce,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_not_round_trip(
+        ctx: Context<ProveNotRoundTrip>,
+        computation_offset: u64,
+        intent_a_id: [u8; 32],
+        intent_b_id: [u8; 32],
+        wash_window_seconds: i64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        // A zero window would pass every pair, proving nothing.
+        require!(
+            (1..=MAX_WASH_WINDOW_SECONDS).contains(&wash_window_seconds),
+            ErrorCode::InvalidWashWindow
+        );
+        // Round trips are judged on when the swaps ran, so both must have.
+        let executed_at_a = ctx.accounts.swap_intent_a.last_executed_at;
+        let executed_at_b = ctx.accounts.swap_intent_b.last_executed_at;
+        require!(executed_at_a > 0 && executed_at_b > 0, ErrorCode::SwapNotExecuted);
+
+        let args = vec![
+            Argument::PlaintextBytes32(intent_a_id),
+            Argument::PlaintextBytes32(intent_b_id),
+            Argument::PlaintextI64(executed_at_a),
+            Argument::PlaintextI64(executed_at_b),
+            Argument::PlaintextI64(wash_window_seconds),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveNotRoundTripCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_not_round_trip")]
+    pub fn prove_not_round_trip_callback(
+        ctx: Context<ProveNotRoundTripCallback>,
+        output: ComputationOutputs<ProveNotRoundTripOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let clean = match output {
+            ComputationOutputs::Success(ProveNotRoundTripOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(RoundTripCheckPassed {
+            clean,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn prove_smoothed_health_threshold(
+        ctx: Context<ProveSmoothedHealthThreshold>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        threshold_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require_oracle_fresh(
+            &ctx.accounts.oracle,
+            ctx.accounts.protocol_config.max_oracle_age_seconds,
+        )?;
+
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(threshold_bps),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProveSmoothedHealthThresholdCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_smoothed_health_threshold")]
+    pub fn prove_smoothed_health_threshold_callback(
+        ctx: Context<ProveSmoothedHealthThresholdCallback>,
+        output: ComputationOutputs<ProveSmoothedHealthThresholdOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let is_healthy = match output {
+            ComputationOutputs::Success(ProveSmoothedHealthThresholdOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(SmoothedHealthProved {
+            is_healthy,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Proves the position has been idle for `POSITION_STALE_TTL_SECONDS`, so
+    /// reaping rests on the encrypted `last_updated` rather than the caller.
+    pub fn prove_position_stale(
+        ctx: Context<ProvePositionStale>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+
+        let stale_before = Clock::get()?.unix_timestamp - POSITION_STALE_TTL_SECONDS;
+        let args = vec![
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextI64(stale_before),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ProvePositionStaleCallback::callback_ix(&[CallbackAccount {
+                pubkey: ctx.accounts.pending_computations.key(),
+                is_writable: true,
+            }])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "prove_position_stale")]
+    pub fn prove_position_stale_callback(
+        ctx: Context<ProvePositionStaleCallback>,
+        output: ComputationOutputs<ProvePositionStaleOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let stale = match output {
+            ComputationOutputs::Success(ProvePositionStaleOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        emit!(PositionStaleProved {
+            stale,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Activates a conditional order once its trigger position's health drops
+    /// below the order's encrypted trigger level. The trigger position must
+    /// belong to the order owner.
+    pub fn check_conditional_trigger(
+        ctx: Context<CheckConditionalTrigger>,
+        computation_offset: u64,
+        order_id: [u8; 32],
+        position_id: [u8; 32],
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        let order_account = &ctx.accounts.order_account;
+        require!(order_account.conditional, ErrorCode::NotConditionalOrder);
+        require!(!order_account.trigger_fired, ErrorCode::InvalidOrderAccount);
+
+        let args = vec![
+            Argument::PlaintextBytes32(order_id),
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextBytes32(position_id),
+        ];
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CheckConditionalTriggerCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.order_account.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "check_conditional_trigger")]
+    pub fn check_conditional_trigger_callback(
+        ctx: Context<CheckConditionalTriggerCallback>,
+        output: ComputationOutputs<CheckConditionalTriggerOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let triggered = match output {
+            ComputationOutputs::Success(CheckConditionalTriggerOutput { field_0 }) => field_0,
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        if triggered {
+            let order_account = &mut ctx.accounts.order_account;
+            order_account.trigger_fired = true;
+
+            emit!(ConditionalOrderTriggered {
+                order_id: order_account.order_id,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+        Ok(())
+    }
+
+    /// Owner-only. Closing the whole position goes through `close_position`,
+    /// so the fraction must leave something behind.
+    pub fn partial_close_position(
+        ctx: Context<PartialClosePosition>,
+        computation_offset: u64,
+        position_id: [u8; 32],
+        owner_pubkey: [u8; 32],
+        owner_nonce: u128,
+        close_fraction_bps: u64,
+    ) -> Result<()> {
+        require_comp_def_ready(&ctx.accounts.comp_def_account)?;
+        require_current_schema(&ctx.accounts.position_account)?;
+        require!(
+            (1..=9999).contains(&close_fraction_bps),
+            ErrorCode::InvalidCloseFraction
+        );
+
+        let args = vec![
+            Argument::ArcisPubkey(owner_pubkey),
+            Argument::PlaintextU128(owner_nonce),
+            Argument::PlaintextBytes32(position_id),
+            Argument::PlaintextU64(close_fraction_bps),
+        ];
+
+        ctx.accounts
+            .position_state
+            .bind(ctx.accounts.position_account.key(), ctx.bumps.position_state);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.pending_computations.start(ctx.bumps.pending_computations);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![PartialClosePositionCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.pending_computations.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.position_state.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "partial_close_position")]
+    pub fn partial_close_position_callback(
+        ctx: Context<PartialClosePositionCallback>,
+        output: ComputationOutputs<PartialClosePositionOutput>,
+    ) -> Result<()> {
+        verify_callback_context(&ctx.accounts.instructions_sysvar)?;
+        ctx.accounts.pending_computations.finish();
+
+        let (position, debt_repaid) = match output {
+            ComputationOutputs::Success(PartialClosePositionOutput {
+                field_0: PartialClosePositionOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            _ => return reject_callback(ErrorCode::AbortedComputation),
+        };
+
+        ctx.accounts
+            .position_state
+            .store(position.nonce, &position.ciphertexts)?;
+
+        emit!(PositionPartiallyClosed {
+            debt_repaid: debt_repaid.ciphertexts[0],
+            nonce: debt_repaid.nonce,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+}
+
+
+#[event]
+pub struct PositionInitialized {
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HealthFactorUpdated {
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HealthRefreshRequested {
+    pub position_id: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MinPositionCountProved {
+    pub satisfied: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HealthThresholdProved {
+    pub is_healthy: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DarkPoolOrderCreated {
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DarkPoolOrdersMatched {
+    pub is_matched: bool,
+    pub failure_reason: u8,
+    pub match_sequence: u64,
+    pub buy_remainder_cancelled: bool,
+    pub sell_remainder_cancelled: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct IcebergReplenished {
+    pub order_id: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MatchSettled {
+    pub buy_order_id: [u8; 32],
+    pub sell_order_id: [u8; 32],
+    pub fill_amount: u64,
+    pub execution_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PrivateSwapExecuted {
+    pub success: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PartialSwapExecuted {
+    pub remaining_bucket: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchHealthChecked {
+    pub at_risk_count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidationRiskCalculated {
+    pub risk_level: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderExpiryExtended {
+    pub order_id: [u8; 32],
+    pub new_expiry: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VolumeTierProved {
+    pub tier: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardiansUpdated {
+    pub position_id: [u8; 32],
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SignPdaClosed {
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionClosed {
+    pub position_id: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionOwnerRecovered {
+    pub position_id: [u8; 32],
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriceInBandProved {
+    pub in_band: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StopConverted {
+    pub order_id: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolConfigUpdated {
+    pub protocol_id: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SolvencyRatioPublished {
+    pub ratio_bucket: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WeightedHealthProved {
+    pub is_healthy: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct KeeperAssigned {
+    pub keeper_index: u8,
+    pub keeper: Pubkey,
+    pub seed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrdersCancelled {
+    pub count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AggregateLeverageProved {
+    pub bounded: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultiMarketPreviewed {
+    /// Bit `i` is set when pair `i` would cross.
+    pub crossing_mask: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CompositionProved {
+    pub satisfied: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AutoTopupExecuted {
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AutoTopupAborted {
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// A callback whose computation aborted or whose output failed a check.
+/// `error_code` is the `ErrorCode` the callback would otherwise have failed
+/// with.
+#[event]
+pub struct CallbackRejected {
+    pub error_code: u32,
+}
+
+#[event]
+pub struct LiquidationRecorded {
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CleanHistoryProved {
+    pub clean: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MatchPreviewed {
+    pub would_match: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultiThresholdProved {
+    pub results_mask: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriceImprovementProved {
+    pub improved: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionSchemaMigrated {
+    pub position_id: [u8; 32],
+    pub schema_version: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StressTestCompleted {
+    pub liquidations_under_shock: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DelayedRevealCommitted {
+    pub reveal_id: [u8; 32],
+    pub reveal_after: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DelayedRevealFinalized {
+    pub reveal_id: [u8; 32],
+    pub result: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BookMatchSelected {
+    pub selected_slot: u8,
+    pub match_sequence: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriorityFeeCollected {
+    pub order_id: [u8; 32],
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WeightedRiskScored {
+    pub score_bucket: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MaxLiquidatableComputed {
+    pub amount_bucket: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolExposureCompared {
+    pub larger: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleRebound {
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleFeedRegistered {
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeSurchargeCharged {
+    pub protocol_id: u8,
+    pub kind: ComputationKind,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleFreshnessProved {
+    pub publish_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BundledHealthProved {
+    pub is_healthy: bool,
+    pub discount_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgeDistributionComputed {
+    pub histogram: [u8; 4],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExpiryConversionTriggered {
+    pub order_id: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnleveragedProved {
+    pub unleveraged: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionsBatchInitialized {
+    pub count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MinCollateralProved {
+    pub satisfied: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolPauseChanged {
+    pub protocol_id: u8,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NoFrontRunningProved {
+    pub compliant: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MakerRebateClaimed {
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CorrelationChecked {
+    pub uncorrelated: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ArmsLengthProved {
+    pub legitimate: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeTierComputed {
+    pub position_id: [u8; 32],
+    pub fee_tier: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RiskCapProved {
+    pub respected: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PortfolioRiskAggregated {
+    pub tracker: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsEligibilityProved {
+    pub eligible: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollateralDiversityProved {
+    pub satisfied: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MinHealthyCountProved {
+    pub satisfied: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionParityProved {
+    pub parity: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RouteOptimalityProved {
+    pub optimal: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CascadeRiskEstimated {
+    pub additional_at_risk: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SpoofingCheckPassed {
+    pub clean: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FloorHeldProved {
+    pub held: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LowImpactProved {
+    pub low_impact: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TopTierProved {
+    pub in_top_tier: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AutoDeleverageExecuted {
+    pub debt_reduced: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ComputationRetryScheduled {
+    pub kind: ComputationKind,
+    pub target: [u8; 32],
+    pub retry_count: u8,
+    pub next_retry_after: i64,
+    pub permanently_failed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TermsMatchProved {
+    pub terms_match: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidationCooldownProved {
+    pub passed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FundingAdjustedHealthProved {
+    pub is_healthy: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BookImbalanceComputed {
+    pub imbalance_bucket: i8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfidenceSlippageComputed {
+    pub slippage_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CreditLineProved {
+    pub under_limit: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RealizedVolumeTierProved {
+    pub tier: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SourceProvenanceProved {
+    pub clean: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WeightedRiskCalculated {
+    pub risk_level: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PortfolioNeutralityProved {
+    pub neutral: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DebtDiversificationProved {
+    pub diversified: bool,
+    pub timestamp: i64,
+}
+
+/// `buffer_bucket` is encrypted to the owner under `nonce`.
+#[event]
+pub struct MarginCallBufferComputed {
+    pub buffer_bucket: [u8; 32],
+    pub nonce: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundTripCheckPassed {
+    pub clean: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SmoothedHealthProved {
+    pub is_healthy: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionStaleProved {
+    pub stale: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConditionalOrderTriggered {
+    pub order_id: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// `debt_repaid` is encrypted to the owner under `nonce`.
+#[event]
+pub struct PositionPartiallyClosed {
+    pub debt_repaid: [u8; 32],
+    pub nonce: u128,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Computation was aborted")]
+    AbortedComputation,
+    #[msg("Cluster not configured")]
+    ClusterNotSet,
+    #[msg("Invalid position state")]
+    InvalidPositionState,
+    #[msg("Order expired")]
+    OrderExpired,
+    #[msg("Insufficient liquidity")]
+    InsufficientLiquidity,
+    #[msg("Slippage exceeded")]
+    SlippageExceeded,
+    #[msg("Signer is not authorized for this account")]
+    Unauthorized,
+    #[msg("New expiry must be later than the current expiry")]
+    InvalidExpiryExtension,
+    #[msg("Computation definition is not initialized or finalized")]
+    CompDefNotReady,
+    #[msg("Guardian keys must be unique")]
+    DuplicateGuardian,
+    #[msg("Guardian threshold must be between 1 and the number of guardians")]
+    InvalidGuardianThreshold,
+    #[msg("Not enough guardian signatures to recover the position")]
+    InsufficientGuardianSignatures,
+    #[msg("Price band low must not exceed band high")]
+    InvalidPriceBand,
+    #[msg("Stop price has not been reached")]
+    StopNotTriggered,
+    #[msg("Slippage bound must not exceed 10000 bps")]
+    InvalidSlippage,
+    #[msg("Risk band width must be between 1 and 10000 bps")]
+    InvalidBandWidth,
+    #[msg("Too many collateral legs for a multi-collateral position")]
+    TooManyLegs,
+    #[msg("Collateral legs must use distinct mints")]
+    DuplicateCollateralMint,
+    #[msg("Keeper registry is full")]
+    KeeperRegistryFull,
+    #[msg("Keeper is already registered")]
+    KeeperAlreadyRegistered,
+    #[msg("Keeper is not registered")]
+    KeeperNotRegistered,
+    #[msg("No keepers are registered")]
+    NoKeepersRegistered,
+    #[msg("Too many orders in a single batch")]
+    TooManyOrders,
+    #[msg("Order account does not match the order id")]
+    InvalidOrderAccount,
+    #[msg("Callback was not invoked from an expected transaction layout")]
+    InvalidCallbackContext,
+    #[msg("Too many markets in a multi-market preview")]
+    TooManyMarkets,
+    #[msg("Basis point value must not exceed 10000")]
+    InvalidBasisPoints,
+    #[msg("Position has not been flagged at the maximum risk level")]
+    TopupNotAllowed,
+    #[msg("Auto-topup would exceed the authorized cap")]
+    TopupCapExceeded,
+    #[msg("Position is not at the liquidatable risk level")]
+    PositionNotLiquidatable,
+    #[msg("Thresholds must be provided in descending order")]
+    InvalidThresholdOrder,
+    #[msg("Position must be migrated to the current schema version")]
+    PositionSchemaOutdated,
+    #[msg("Price tolerance exceeds the maximum allowed")]
+    InvalidPriceTolerance,
+    #[msg("Reveal deadline has not passed yet")]
+    RevealTooEarly,
+    #[msg("Delayed reveal has already been finalized")]
+    RevealAlreadyFinalized,
+    #[msg("Payer balance does not cover the estimated batch cost")]
+    InsufficientBalanceForBatch,
+    #[msg("Close factor must be between 1 and 10000 bps")]
+    InvalidCloseFactor,
+    #[msg("Oracle account is not owned by a recognized oracle program")]
+    UnrecognizedOracle,
+    #[msg("Oracle account does not match the feed bound to the position")]
+    OracleNotBound,
+    #[msg("An identical order from this maker is already live")]
+    DuplicateOrder,
+    #[msg("Order is not within the expiry grace window")]
+    OrderNotExpiring,
+    #[msg("Order did not opt into conversion on expiry")]
+    ExpiryConversionNotEnabled,
+    #[msg("Too many positions in one batch")]
+    TooManyPositions,
+    #[msg("Protocol has no registered ProtocolConfig")]
+    UnregisteredProtocol,
+    #[msg("Position account does not match the expected PDA")]
+    InvalidPositionAccount,
+    #[msg("Match cooldown exceeds the maximum allowed")]
+    InvalidMatchCooldown,
+    #[msg("Order was tried for a match too recently")]
+    MatchCooldownActive,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Escalation delay exceeds the maximum")]
+    InvalidEscalationDelay,
+    #[msg("Too many correlated mint pairs")]
+    TooManyCorrelatedPairs,
+    #[msg("Reveal precision must be between 1 and the maximum")]
+    InvalidRevealPrecision,
+    #[msg("Wash-trading window is out of range")]
+    InvalidWashWindow,
+    #[msg("Tier fees must not decrease as health worsens")]
+    InvalidFeeSchedule,
+    #[msg("Settlement is enabled without a settlement program")]
+    InvalidSettlementProgram,
+    #[msg("Buy order has a match awaiting settlement")]
+    SettlementPending,
+    #[msg("Order has no unsettled match")]
+    NothingToSettle,
+    #[msg("Oracle price is older than the protocol's maximum oracle age")]
+    OracleStale,
+    #[msg("Max oracle age must be between 1 second and 1 hour")]
+    InvalidOracleAge,
+    #[msg("Oracle feed's publish time cannot be read")]
+    UnsupportedOracleFeed,
+    #[msg("Owner already holds the maximum number of positions")]
+    PositionLimitReached,
+    #[msg("Max positions per owner must be at least 1")]
+    InvalidPositionLimit,
+    #[msg("Fee surcharges must be at most 100%, on a surchargeable kind, and list each kind once")]
+    InvalidFeeSurcharge,
+    #[msg("Deleverage trigger health must be below the target health")]
+    InvalidDeleverageConfig,
+    #[msg("No health proof has flagged this position for deleveraging")]
+    DeleverageNotTriggered,
+    #[msg("Oracle price must be positive")]
+    InvalidOraclePrice,
+    #[msg("Time-in-force must be GTC (0), IOC (1) or FOK (2)")]
+    InvalidTimeInForce,
+    #[msg("Computation retry backoff has not elapsed")]
+    RetryTooSoon,
+    #[msg("Computation exhausted its retries")]
+    ComputationPermanentlyFailed,
+    #[msg("Projected price must be positive")]
+    InvalidProjectedPrice,
+    #[msg("EMA smoothing must be between 1 and 10000 bps")]
+    InvalidEmaSmoothing,
+    #[msg("Conditional order has not been triggered")]
+    ConditionalOrderNotTriggered,
+    #[msg("Order is not conditional")]
+    NotConditionalOrder,
+    #[msg("Too many protocol configs in one batch")]
+    TooManyProtocolConfigs,
+    #[msg("Protocol appears more than once in the batch")]
+    DuplicateProtocolConfig,
+    #[msg("Close fraction must be between 1 and 9999 bps")]
+    InvalidCloseFraction,
+    #[msg("Encrypted output is larger than an EncryptedState can hold")]
+    EncryptedStateTooLarge,
+    #[msg("A topup for this position is still in flight")]
+    TopupPending,
+    #[msg("Token program must be SPL Token")]
+    InvalidTokenProgram,
+    #[msg("Position is already on the current schema")]
+    PositionSchemaCurrent,
+    #[msg("Reveal time must be in the future")]
+    InvalidRevealTime,
+    #[msg("Delayed reveal has not been committed yet")]
+    RevealNotCommitted,
+    #[msg("Bound oracle does not price the position's asset")]
+    OracleMintMismatch,
+    #[msg("Swap intent has not executed yet")]
+    SwapNotExecuted,
+    #[msg("Computations are still pending")]
+    ComputationsPending,
+    #[msg("Too few peer positions to rank against")]
+    TooFewPeers,
+    #[msg("Position has not opted into health refresh on settlement")]
+    RefreshNotEnabled,
+    #[msg("Computation is still in flight; schedule a retry before re-queuing it")]
+    RetryNotGranted,
+    #[msg("Computation has no attempt in flight to retry")]
+    ComputationNotInFlight,
+    #[msg("Conditional orders need a trigger position and level; other orders take neither")]
+    InvalidConditionalTrigger,
+    #[msg("Position schema doesn't match the layout this migration reads")]
+    PositionSchemaMismatch,
+    #[msg("Liquidation cooldown must not be negative")]
+    InvalidLiquidationCooldown,
+    #[msg("Treasury must be set and match the protocol config")]
+    InvalidTreasury,
+    #[msg("Orders aren't each other's latest match")]
+    OrdersNotMatched,
+    #[msg("Midpoint rounding must be buyer (0), seller (1) or nearest (2)")]
+    InvalidRoundingMode,
+    #[msg("A keeper was already selected for this assignment window")]
+    KeeperAlreadySelected,
+    #[msg("Only the assigned keeper can act while its assignment is current")]
+    KeeperNotAssigned,
+    #[msg("This computation kind doesn't track retries")]
+    ComputationNotRetryable,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DarkPoolState {
+    pub next_sequence: u64,
+    pub bump: u8,
+}
+
+/// Orders every match-related event across transactions so indexers can
+/// detect gaps.
+#[account]
+#[derive(InitSpace)]
+pub struct MatchSequenceCounter {
+    pub next_sequence: u64,
+    pub bump: u8,
+}
+
+impl MatchSequenceCounter {
+    pub fn advance(&mut self) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence = sequence + 1;
+        sequence
+    }
+}
+
+/// Computations queued but not yet called back. Every queue increments it and
+/// every callback decrements it, so `close_sign_pda` can tell when the shared
+/// sign PDA is idle.
+#[account]
+#[derive(InitSpace)]
+pub struct PendingComputations {
+    pub count: u64,
+    pub bump: u8,
+}
+
+impl PendingComputations {
+    pub fn start(&mut self, bump: u8) {
+        self.bump = bump;
+        self.count += 1;
+    }
+
+    pub fn finish(&mut self) {
+        self.count = self.count.saturating_sub(1);
+    }
+
+    pub fn require_idle(&self) -> Result<()> {
+        require!(self.count == 0, ErrorCode::ComputationsPending);
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DarkPoolOrderAccount {
+    pub owner: Pubkey,
+    pub order_id: [u8; 32],
+    pub sequence: u64,
+    pub expires_at: i64,
+    pub order_hash: [u8; 32],
+    pub last_match_attempt: i64,
+    /// Revealed fill of the last match when this is the buy side and it
+    /// hasn't been settled yet; 0 otherwise.
+    pub unsettled_fill_amount: u64,
+    pub unsettled_execution_price: u64,
+    pub unsettled_counterparty: [u8; 32],
+    /// Order this one last matched against; zero until it first matches.
+    pub last_counterparty: [u8; 32],
+    /// Time-in-force set at init; see `TIF_GOOD_TIL_CANCELLED` and siblings.
+    pub tif: u8,
+    /// Set at init; conditional orders can't match until `trigger_fired`.
+    pub conditional: bool,
+    /// Set once `check_conditional_trigger` sees the trigger position cross.
+    pub trigger_fired: bool,
+    /// Lamports escrowed on this account at init to rank ahead of same-price
+    /// takers in `match_book`; paid to the treasury when the order fills as
+    /// the taker.
+    pub priority_fee: u64,
+    /// Opted-in position settling this order flags for a health refresh;
+    /// zero when none is linked.
+    pub refresh_position_id: [u8; 32],
+    pub bump: u8,
+}
+
+impl DarkPoolOrderAccount {
+    pub fn is_matchable(&self) -> bool {
+        !self.conditional || self.trigger_fired
+    }
+
+    /// Enforces the match cooldown on orders `caller` owns and stamps the
+    /// attempt. Other orders are left alone, so failed attempts against
+    /// someone else's order can't lock it out of matching.
+    pub fn stamp_match_attempt(&mut self, caller: &Pubkey, now: i64, cooldown: i64) -> Result<()> {
+        if self.owner != *caller {
+            return Ok(());
+        }
+        require!(
+            self.last_match_attempt == 0 || now >= self.last_match_attempt + cooldown,
+            ErrorCode::MatchCooldownActive
+        );
+        self.last_match_attempt = now;
+        Ok(())
+    }
+}
+
+/// Marks an order commitment as live for its maker, so the same order can't be
+/// submitted twice. Closed when the order is cancelled or a match leaves it
+/// with nothing to fill.
+#[account]
+#[derive(InitSpace)]
+pub struct SubmittedOrders {
+    pub order_hash: [u8; 32],
+    pub order_id: [u8; 32],
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MakerVolumeAccount {
+    pub maker: Pubkey,
+    pub match_count: u64,
+    pub claimable_rebate: u64,
+    /// Rebate released by a match that the vault couldn't back at the time.
+    /// Credited ahead of later rebates as fees come in.
+    pub owed_rebate: u64,
+    /// Start of the current realized-volume window.
+    pub window_started_at: i64,
+    /// Start of the next window, recorded by the match callback that resets
+    /// the encrypted realized volume; 0 when no reset is in flight.
+    pub pending_window_start: i64,
+    pub bump: u8,
+}
+
+impl MakerVolumeAccount {
+    /// Credits what's owed plus `rebate` as far as uncommitted collected
+    /// fees can back it, carrying the rest in `owed_rebate`.
+    pub fn credit_rebate(&mut self, vault: &mut RebateVault, rebate: u64) {
+        let owed = self.owed_rebate.saturating_add(rebate);
+        let available = vault.collected_fees.saturating_sub(vault.committed_rebates);
+        let credited = owed.min(available);
+        vault.committed_rebates += credited;
+        self.claimable_rebate += credited;
+        self.owed_rebate = owed - credited;
+    }
+}
+
+/// Placements, cancels and fills counted since the owner's last
+/// `record_order_activity`. These mirror public instructions and aren't
+/// private; they're only staged here until the callback folds them into the
+/// encrypted totals in the owner's `EncryptedState`.
+#[account]
+#[derive(InitSpace)]
+pub struct OrderActivityAccount {
+    pub owner: Pubkey,
+    pub pending_placements: u64,
+    pub pending_cancels: u64,
+    pub pending_fills: u64,
+    pub bump: u8,
+}
+
+/// Owner of an encrypted `RiskCapTracker`. The running max lives in the
+/// tracker's `EncryptedState` and only `aggregate_portfolio_risk` updates it.
+#[account]
+#[derive(InitSpace)]
+pub struct RiskCapTrackerAccount {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+/// On-chain handle for a swap intent. The intent's terms stay encrypted in
+/// the `EncryptedState` keyed by this account.
+#[account]
+#[derive(InitSpace)]
+pub struct SwapIntentAccount {
+    pub intent_id: [u8; 32],
+    /// Output of the latest execution; 0 until the intent first executes.
+    pub last_output: u64,
+    pub last_executed_at: i64,
+    pub bump: u8,
+}
+
+/// On-chain handle for a multi-collateral position. Its legs and debt stay
+/// encrypted in the `EncryptedState` keyed by this account.
+#[account]
+#[derive(InitSpace)]
+pub struct MultiCollateralPositionAccount {
+    pub position_id: [u8; 32],
+    pub bump: u8,
+}
+
+/// Holds collected fees that back maker rebates. Rebates are only credited
+/// against fees that haven't already been committed to other makers.
+#[account]
+#[derive(InitSpace)]
+pub struct RebateVault {
+    pub collected_fees: u64,
+    pub committed_rebates: u64,
+    pub bump: u8,
+}
+
+/// Fails fast with `CompDefNotReady` when a computation definition is missing or its
+/// circuit upload was never finalized, instead of surfacing an opaque Arcium error
+/// from inside `queue_computation`. Queue instructions take the account unchecked so
+/// a missing definition reaches this check instead of failing deserialization.
+fn require_comp_def_ready(comp_def_account: &UncheckedAccount) -> Result<()> {
+    require!(
+        comp_def_account.owner == &ARCIUM_PROG_ID && !comp_def_account.data_is_empty(),
+        ErrorCode::CompDefNotReady
+    );
+
+    let data = comp_def_account.try_borrow_data()?;
+    let comp_def = ComputationDefinitionAccount::try_deserialize(&mut &data[..])
+        .map_err(|_| error!(ErrorCode::CompDefNotReady))?;
+    let is_finalized = match &comp_def.circuit_source {
+        CircuitSource::OnChain(source) => source.is_completed,
+        _ => true,
+    };
+    require!(is_finalized, ErrorCode::CompDefNotReady);
+    Ok(())
+}
+
+/// Index of the first protocol in a batch whose `ProtocolConfig`, passed
+/// alongside it, isn't an initialized account of this program or is paused,
+/// with the error it fails on.
+fn unusable_protocol_index<'a, 'info: 'a>(
+    program_id: &Pubkey,
+    protocols: &[u8],
+    config_infos: impl Iterator<Item = &'a AccountInfo<'info>>,
+) -> Option<(usize, ErrorCode)> {
+    protocols
+        .iter()
+        .zip(config_infos)
+        .enumerate()
+        .find_map(|(i, (protocol, config_info))| {
+            let (expected, _) =
+                Pubkey::find_program_address(&[PROTOCOL_CONFIG_SEED, &[*protocol]], program_id);
+            if config_info.key() != expected || config_info.owner != program_id {
+                return Some((i, ErrorCode::UnregisteredProtocol));
+            }
+            match ProtocolConfig::try_deserialize(&mut &config_info.data.borrow()[..]) {
+                Ok(config) if config.protocol_paused => Some((i, ErrorCode::ProtocolPaused)),
+                Ok(_) => None,
+                Err(_) => Some((i, ErrorCode::UnregisteredProtocol)),
+            }
+        })
+}
+
+/// Positions written under an older `EncryptedPosition` layout would be
+/// misread by the current circuits, so they're locked until migrated.
+fn require_current_schema(position_account: &PositionAccount) -> Result<()> {
+    require!(
+        position_account.schema_version >= CURRENT_POSITION_SCHEMA_VERSION,
+        ErrorCode::PositionSchemaOutdated
+    );
+    Ok(())
+}
+
+/// Each migration circuit reads exactly one legacy layout.
+fn require_migratable_schema(position_account: &PositionAccount, from_version: u8) -> Result<()> {
+    require!(
+        position_account.schema_version < CURRENT_POSITION_SCHEMA_VERSION,
+        ErrorCode::PositionSchemaCurrent
+    );
+    require!(
+        position_account.schema_version == from_version,
+        ErrorCode::PositionSchemaMismatch
+    );
+    Ok(())
+}
+
+/// Reads the 8-byte field at `legacy_offset` in a Pyth legacy price account,
+/// or at `message_offset` within a receiver account's `PriceFeedMessage`.
+fn pyth_field(oracle: &AccountInfo, legacy_offset: usize, message_offset: usize) -> Result<[u8; 8]> {
+    let data = oracle.try_borrow_data()?;
+    let offset = if oracle.owner == &RECOGNIZED_ORACLE_PROGRAMS[0] {
+        legacy_offset
+    } else if oracle.owner == &RECOGNIZED_ORACLE_PROGRAMS[1] {
+        // `verification_level` is Partial { num_signatures: u8 } (tag 0) or Full (tag 1).
+        let message_start = match data.get(PYTH_RECEIVER_HEADER_LEN) {
+            Some(0) => PYTH_RECEIVER_HEADER_LEN + 2,
+            Some(1) => PYTH_RECEIVER_HEADER_LEN + 1,
+            _ => return err!(ErrorCode::UnsupportedOracleFeed),
+        };
+        message_start + message_offset
+    } else {
+        return err!(ErrorCode::UnsupportedOracleFeed);
+    };
+
+    let bytes = data
+        .get(offset..offset + 8)
+        .ok_or(ErrorCode::UnsupportedOracleFeed)?;
+    Ok(bytes.try_into().unwrap())
+}
+
+/// Reads the publish time from a Pyth price account.
+fn oracle_publish_time(oracle: &AccountInfo) -> Result<i64> {
+    let bytes = pyth_field(oracle, PYTH_LEGACY_TIMESTAMP_OFFSET, PYTH_MESSAGE_PUBLISH_TIME_OFFSET)?;
+    Ok(i64::from_le_bytes(bytes))
+}
+
+/// Reads a Pyth feed's price and confidence interval, in the feed's own
+/// exponent. Non-positive prices are rejected.
+fn oracle_price_and_confidence(oracle: &AccountInfo) -> Result<(u64, u64)> {
+    let price = i64::from_le_bytes(pyth_field(oracle, PYTH_LEGACY_PRICE_OFFSET, PYTH_MESSAGE_PRICE_OFFSET)?);
+    let confidence = u64::from_le_bytes(pyth_field(oracle, PYTH_LEGACY_CONF_OFFSET, PYTH_MESSAGE_CONF_OFFSET)?);
+    require!(price > 0, ErrorCode::InvalidOraclePrice);
+    Ok((price as u64, confidence))
+}
+
+/// Fails with `OracleStale` unless the feed published within `max_age_seconds`.
+/// Returns the publish time for callers that report it.
+fn require_oracle_fresh(oracle: &AccountInfo, max_age_seconds: i64) -> Result<i64> {
+    let publish_time = oracle_publish_time(oracle)?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now.saturating_sub(publish_time) <= max_age_seconds,
+        ErrorCode::OracleStale
+    );
+    Ok(publish_time)
+}
+
+/// Moves `amount` from the payer into the rebate vault as collected fees.
+fn collect_fee<'info>(
+    payer: &Signer<'info>,
+    rebate_vault: &mut Account<'info, RebateVault>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.to_account_info(),
+                to: rebate_vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    rebate_vault.collected_fees += amount;
+    Ok(())
+}
+
+/// Moves `amount` out of a token account that delegated to `authority`, a
+/// program PDA signing with `authority_seeds`.
+fn transfer_delegated_tokens<'info>(
+    token_program: &AccountInfo<'info>,
+    source: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    authority_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(token_program.key(), SPL_TOKEN_PROGRAM_ID, ErrorCode::InvalidTokenProgram);
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let mut data = vec![SPL_TOKEN_TRANSFER_TAG];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    anchor_lang::solana_program::program::invoke_signed(
+        &anchor_lang::solana_program::instruction::Instruction {
+            program_id: SPL_TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(source.key(), false),
+                AccountMeta::new(destination.key(), false),
+                AccountMeta::new_readonly(authority.key(), true),
+            ],
+            data,
+        },
+        &[
+            source.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        &[authority_seeds],
+    )?;
+    Ok(())
+}
+
+/// Collects the protocol's surcharge for `kind`, if any, into its treasury
+/// at queue time.
+fn charge_fee_surcharge<'info>(
+    protocol_config: &ProtocolConfig,
+    kind: ComputationKind,
+    payer: &Signer<'info>,
+    treasury: &UncheckedAccount<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let amount = protocol_config.surcharge_lamports(kind);
+    if amount == 0 {
+        return Ok(());
+    }
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.to_account_info(),
+                to: treasury.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    emit!(FeeSurchargeCharged {
+        protocol_id: protocol_config.protocol_id,
+        kind,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PositionAccount {
+    pub owner: Pubkey,
+    pub position_id: [u8; 32],
+    pub protocol_id: u8,
+    pub guardians: [Pubkey; MAX_GUARDIANS],
+    pub guardian_threshold: u8,
+    /// Latest risk reading. From `calculate_liquidation_risk` it's coarsened
+    /// by `reveal_precision`; `risk_flagged_at` tracks the max level.
+    pub last_risk_level: u8,
+    pub risk_updated_at: i64,
+    pub schema_version: u8,
+    pub oracle: Pubkey,
+    /// When the position was first seen at the max risk level; 0 if not flagged.
+    pub risk_flagged_at: i64,
+    /// Set once the max risk level is confirmed after the escalation delay.
+    pub liquidatable_after: i64,
+    /// Last tier revealed by `compute_fee_tier`; 0 until one is computed.
+    pub fee_tier: u8,
+    /// Opt-in: settling a match for this owner flags the position for a
+    /// follow-up `update_health_factor`.
+    pub refresh_health_on_settle: bool,
+    /// Set by `settle_match` when the above is enabled; cleared once the
+    /// follow-up health computation succeeds.
+    pub health_refresh_pending: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolState {
+    pub admin: Pubkey,
+    pub max_positions_per_owner: u32,
+    pub bump: u8,
+}
+
+/// Admin-registered price feed for a mint. Positions can only be bound to
+/// feeds registered here.
+#[account]
+#[derive(InitSpace)]
+pub struct OracleFeed {
+    pub mint: Pubkey,
+    pub oracle: Pubkey,
+    pub bump: u8,
+}
+
+/// Counts the positions an owner holds, to enforce `max_positions_per_owner`.
+#[account]
+#[derive(InitSpace)]
+pub struct OwnerRegistry {
+    pub owner: Pubkey,
+    pub position_count: u32,
+    pub bump: u8,
+}
+
+impl OwnerRegistry {
+    pub fn reserve(&mut self, count: u32, max_positions: u32) -> Result<()> {
+        let position_count = self.position_count.saturating_add(count);
+        require!(position_count <= max_positions, ErrorCode::PositionLimitReached);
+        self.position_count = position_count;
+        Ok(())
+    }
+
+    pub fn release(&mut self) {
+        self.position_count = self.position_count.saturating_sub(1);
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolConfig {
+    pub protocol_id: u8,
+    pub band_width_bps: u64,
+    pub price_tolerance_bps: u64,
+    pub size_priority: bool,
+    pub close_factor_bps: u64,
+    pub bundle_discount_bps: u64,
+    pub match_cooldown_seconds: i64,
+    pub protocol_paused: bool,
+    pub maker_rebate_bps: u64,
+    pub escalation_delay_seconds: i64,
+    /// Native buckets merged per revealed bucket; 1 is full precision.
+    pub reveal_precision: u8,
+    /// Orders created closer together than this fail `prove_arms_length`.
+    pub wash_window_seconds: i64,
+    pub fee_schedule: FeeSchedule,
+    /// When set, matches reveal their fill terms for `settle_match`.
+    pub settlement_enabled: bool,
+    pub settlement_program: Pubkey,
+    /// Oldest oracle publish time, in seconds, health and proofs accept.
+    pub max_oracle_age_seconds: i64,
+    /// Charged on top of a kind's estimated Arcium fee; 0 bps entries are unused.
+    pub fee_surcharges: [FeeSurcharge; MAX_FEE_SURCHARGES],
+    /// Receives fee surcharges.
+    pub treasury: Pubkey,
+    /// Weight of each new health reading in the position's health EMA.
+    pub ema_smoothing_bps: u64,
+    /// Adverse price move `calculate_liquidation_risk` grades positions against.
+    pub liquidation_price_impact_bps: u64,
+    /// Rounding of odd-sum match midpoints; see `ROUND_TOWARD_BUYER` and siblings.
+    pub midpoint_round_toward: u8,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ProtocolConfigParams {
+    pub band_width_bps: u64,
+    pub price_tolerance_bps: u64,
+    pub size_priority: bool,
+    pub close_factor_bps: u64,
+    pub bundle_discount_bps: u64,
+    pub match_cooldown_seconds: i64,
+    pub maker_rebate_bps: u64,
+    pub escalation_delay_seconds: i64,
+    pub reveal_precision: u8,
+    pub wash_window_seconds: i64,
+    pub fee_schedule: FeeSchedule,
+    pub settlement_enabled: bool,
+    pub settlement_program: Pubkey,
+    pub max_oracle_age_seconds: i64,
+    pub fee_surcharges: [FeeSurcharge; MAX_FEE_SURCHARGES],
+    pub treasury: Pubkey,
+    pub ema_smoothing_bps: u64,
+    pub liquidation_price_impact_bps: u64,
+    pub midpoint_round_toward: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ProtocolConfigEntry {
+    pub protocol_id: u8,
+    pub params: ProtocolConfigParams,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct FeeSurcharge {
+    pub kind: ComputationKind,
+    pub surcharge_bps: u64,
+}
+
+/// Per-operation fees by health tier. Tier `n` applies below
+/// `health_thresholds_bps[n - 1]`, so healthier positions pay less.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct FeeSchedule {
+    pub health_thresholds_bps: [u64; 3],
+    pub tier_fee_lamports: [u64; 4],
+}
+
+impl FeeSchedule {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.health_thresholds_bps.windows(2).all(|w| w[0] >= w[1]),
+            ErrorCode::InvalidThresholdOrder
+        );
+        require!(
+            self.tier_fee_lamports.windows(2).all(|w| w[0] <= w[1]),
+            ErrorCode::InvalidFeeSchedule
+        );
+        Ok(())
+    }
+
+    pub fn fee_for_tier(&self, tier: u8) -> u64 {
+        self.tier_fee_lamports[(tier as usize).min(self.tier_fee_lamports.len() - 1)]
+    }
+}
+
+impl ProtocolConfigParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.band_width_bps > 0 && self.band_width_bps <= MAX_BAND_WIDTH_BPS,
+            ErrorCode::InvalidBandWidth
+        );
+        require!(
+            self.price_tolerance_bps <= MAX_PRICE_TOLERANCE_BPS,
+            ErrorCode::InvalidPriceTolerance
+        );
+        require!(
+            self.close_factor_bps > 0 && self.close_factor_bps <= 10000,
+            ErrorCode::InvalidCloseFactor
+        );
+        require!(self.bundle_discount_bps <= 10000, ErrorCode::InvalidBasisPoints);
+        require!(
+            (0..=MAX_MATCH_COOLDOWN_SECONDS).contains(&self.match_cooldown_seconds),
+            ErrorCode::InvalidMatchCooldown
+        );
+        require!(self.maker_rebate_bps <= 10000, ErrorCode::InvalidBasisPoints);
+        require!(
+            (0..=MAX_ESCALATION_DELAY_SECONDS).contains(&self.escalation_delay_seconds),
+            ErrorCode::InvalidEscalationDelay
+        );
+        require!(
+            (1..=MAX_REVEAL_PRECISION).contains(&self.reveal_precision),
+            ErrorCode::InvalidRevealPrecision
+        );
+        require!(
+            (0..=MAX_WASH_WINDOW_SECONDS).contains(&self.wash_window_seconds),
+            ErrorCode::InvalidWashWindow
+        );
+        self.fee_schedule.validate()?;
+        require!(
+            !self.settlement_enabled || self.settlement_program != Pubkey::default(),
+            ErrorCode::InvalidSettlementProgram
+        );
+        require!(
+            (1..=MAX_ORACLE_AGE_SECONDS).contains(&self.max_oracle_age_seconds),
+            ErrorCode::InvalidOracleAge
+        );
+        for (i, surcharge) in self.fee_surcharges.iter().enumerate() {
+            if surcharge.surcharge_bps == 0 {
+                continue;
+            }
+            require!(
+                surcharge.surcharge_bps <= MAX_FEE_SURCHARGE_BPS
+                    && surcharge.kind.accepts_surcharge(),
+                ErrorCode::InvalidFeeSurcharge
+            );
+            require!(
+                !self.fee_surcharges[..i]
+                    .iter()
+                    .any(|other| other.surcharge_bps > 0 && other.kind == surcharge.kind),
+                ErrorCode::InvalidFeeSurcharge
+            );
+        }
+        require!(self.treasury != Pubkey::default(), ErrorCode::InvalidTreasury);
+        require!(
+            self.ema_smoothing_bps > 0 && self.ema_smoothing_bps <= 10000,
+            ErrorCode::InvalidEmaSmoothing
+        );
+        require!(
+            self.liquidation_price_impact_bps <= 10000,
+            ErrorCode::InvalidBasisPoints
+        );
+        require!(
+            self.midpoint_round_toward <= ROUND_TOWARD_NEAREST,
+            ErrorCode::InvalidRoundingMode
+        );
+        Ok(())
+    }
+}
+
+impl ProtocolConfig {
+    pub fn apply(&mut self, params: &ProtocolConfigParams) {
+        self.band_width_bps = params.band_width_bps;
+        self.price_tolerance_bps = params.price_tolerance_bps;
+        self.size_priority = params.size_priority;
+        self.close_factor_bps = params.close_factor_bps;
+        self.bundle_discount_bps = params.bundle_discount_bps;
+        self.match_cooldown_seconds = params.match_cooldown_seconds;
+        self.maker_rebate_bps = params.maker_rebate_bps;
+        self.escalation_delay_seconds = params.escalation_delay_seconds;
+        self.reveal_precision = params.reveal_precision;
+        self.wash_window_seconds = params.wash_window_seconds;
+        self.fee_schedule = params.fee_schedule;
+        self.settlement_enabled = params.settlement_enabled;
+        self.settlement_program = params.settlement_program;
+        self.max_oracle_age_seconds = params.max_oracle_age_seconds;
+        self.fee_surcharges = params.fee_surcharges;
+        self.treasury = params.treasury;
+        self.ema_smoothing_bps = params.ema_smoothing_bps;
+        self.liquidation_price_impact_bps = params.liquidation_price_impact_bps;
+        self.midpoint_round_toward = params.midpoint_round_toward;
+    }
+
+    pub fn surcharge_lamports(&self, kind: ComputationKind) -> u64 {
+        self.fee_surcharges
+            .iter()
+            .find(|surcharge| surcharge.surcharge_bps > 0 && surcharge.kind == kind)
+            .map_or(0, |surcharge| kind.estimated_cost() * surcharge.surcharge_bps / 10000)
+    }
+}
+
+/// Validates the supplied collateral mints and pads them to the fixed leg
+/// capacity. Unused legs are left as the zero mint.
+fn collateral_leg_mints(mints: &[[u8; 32]]) -> Result<[[u8; 32]; MAX_COLLATERAL_LEGS]> {
+    require!(mints.len() <= MAX_COLLATERAL_LEGS, ErrorCode::TooManyLegs);
+
+    let mut legs = [[0u8; 32]; MAX_COLLATERAL_LEGS];
+    for (i, mint) in mints.iter().enumerate() {
+        require!(!mints[..i].contains(mint), ErrorCode::DuplicateCollateralMint);
+        legs[i] = *mint;
+    }
+    Ok(legs)
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct KeeperRegistry {
+    pub keepers: [Pubkey; MAX_KEEPERS],
+    pub keeper_count: u8,
+    /// Keeper `select_keeper` last drew; default when none is assigned.
+    pub assigned_keeper: Pubkey,
+    /// Slot the assignment was recorded at.
+    pub assigned_slot: u64,
+    /// Slot the last selection was queued at; one selection per window.
+    pub selection_slot: u64,
+    pub bump: u8,
+}
+
+impl KeeperRegistry {
+    pub fn is_keeper(&self, key: &Pubkey) -> bool {
+        self.keepers[..self.keeper_count as usize].contains(key)
+    }
+
+    /// Fails unless `key` may run a keeper-gated instruction at `slot`: only
+    /// the assigned keeper while its assignment is current, any registered
+    /// keeper once it lapses.
+    pub fn require_assigned(&self, key: &Pubkey, slot: u64) -> Result<()> {
+        require!(self.is_keeper(key), ErrorCode::KeeperNotRegistered);
+        let assignment_current = self.assigned_keeper != Pubkey::default()
+            && slot < self.assigned_slot.saturating_add(KEEPER_ASSIGNMENT_SLOTS);
+        require!(
+            !assignment_current || *key == self.assigned_keeper,
+            ErrorCode::KeeperNotAssigned
+        );
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AlertConfig {
+    pub position_id: [u8; 32],
+    pub hysteresis_bps: u64,
+    pub triggered: bool,
+    pub bump: u8,
+}
+
+/// Creates and writes a `PositionAccount` PDA for batch initialization, where
+/// the accounts can't be declared up front in the context.
+fn create_position_account<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    program_id: &Pubkey,
+    position: PositionAccount,
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[POSITION_PDA_SEED, position.position_id.as_ref(), &[position.bump]];
+    let space = 8 + PositionAccount::INIT_SPACE;
+
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::CreateAccount {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+            &[seeds],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        program_id,
+    )?;
+
+    position.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// Flags the position `order` linked for a health refresh, if it's still
+/// opted in. A linked position has to be passed, so a settler can't skip the
+/// refresh by leaving it out.
+fn request_health_refresh(
+    order: &DarkPoolOrderAccount,
+    position: Option<&mut PositionAccount>,
+) -> Result<()> {
+    if order.refresh_position_id == [0u8; 32] {
+        return Ok(());
+    }
+    let position = position.ok_or(ErrorCode::InvalidPositionAccount)?;
+    require!(
+        position.position_id == order.refresh_position_id && position.owner == order.owner,
+        ErrorCode::InvalidPositionAccount
+    );
+    if position.refresh_health_on_settle {
+        position.health_refresh_pending = true;
+        emit!(HealthRefreshRequested {
+            position_id: position.position_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+    Ok(())
+}
+
+/// Plaintext a match circuit reveals alongside the orders and maker volume
+/// it writes back.
+struct MatchOutcome {
+    is_matched: bool,
+    failure_reason: u8,
+    rebate: u64,
+    buy_replenished: bool,
+    sell_replenished: bool,
+    fill_amount: u64,
+    execution_price: u64,
+}
+
+/// Plaintext accounts a match callback updates.
+struct MatchParties<'a> {
+    buy_order: &'a mut DarkPoolOrderAccount,
+    sell_order: &'a mut DarkPoolOrderAccount,
+    buy_activity: &'a mut OrderActivityAccount,
+    sell_activity: &'a mut OrderActivityAccount,
+    maker_volume: &'a mut MakerVolumeAccount,
+    rebate_vault: &'a mut RebateVault,
+    match_sequence_counter: &'a mut MatchSequenceCounter,
+}
+
+/// Bookkeeping shared by every callback that fills orders, run once the
+/// encrypted orders and maker volume are stored. Rolls the maker's realized
+/// window, credits the released rebate, records a match's counterparties,
+/// pending fills and unsettled terms, and emits the match events.
+fn record_match(parties: MatchParties, outcome: &MatchOutcome, timestamp: i64) {
+    let MatchParties {
+        buy_order,
+        sell_order,
+        buy_activity,
+        sell_activity,
+        maker_volume,
+        rebate_vault,
+        match_sequence_counter,
+    } = parties;
+
+    if maker_volume.pending_window_start > 0 {
+        maker_volume.window_started_at = maker_volume.pending_window_start;
+        maker_volume.pending_window_start = 0;
+    }
+
+    // The circuit has already released the rebate from the encrypted
+    // accrual, so whatever the vault can't back yet stays owed.
+    maker_volume.credit_rebate(rebate_vault, outcome.rebate);
+
+    if outcome.is_matched {
+        maker_volume.match_count += 1;
+        buy_order.last_match_attempt = 0;
+        sell_order.last_match_attempt = 0;
+        buy_order.last_counterparty = sell_order.order_id;
+        sell_order.last_counterparty = buy_order.order_id;
+        buy_activity.pending_fills += 1;
+        sell_activity.pending_fills += 1;
+
+        // Fill terms are only revealed when settlement is enabled. Tokens
+        // move in `settle_match`, so a failing settlement program can't
+        // abort the callback and lose the match.
+        if outcome.fill_amount > 0 {
+            buy_order.unsettled_fill_amount = outcome.fill_amount;
+            buy_order.unsettled_execution_price = outcome.execution_price;
+            buy_order.unsettled_counterparty = sell_order.order_id;
+        }
+    }
+
+    // The circuit has already zeroed any immediate-or-cancel remainder.
+    emit!(DarkPoolOrdersMatched {
+        is_matched: outcome.is_matched,
+        failure_reason: outcome.failure_reason,
+        match_sequence: match_sequence_counter.advance(),
+        buy_remainder_cancelled: buy_order.tif == TIF_IMMEDIATE_OR_CANCEL,
+        sell_remainder_cancelled: sell_order.tif == TIF_IMMEDIATE_OR_CANCEL,
+        timestamp,
+    });
+
+    for (order, replenished) in [
+        (buy_order, outcome.buy_replenished),
+        (sell_order, outcome.sell_replenished),
+    ] {
+        if replenished {
+            emit!(IcebergReplenished {
+                order_id: order.order_id,
+                timestamp,
+            });
+        }
+    }
+}
+
+/// The taker (the later order) pays its priority fee when the match fills,
+/// so a match that would spend it needs the taker's owner to sign.
+fn require_priority_fee_consent(
+    buy_order: &DarkPoolOrderAccount,
+    sell_order: &DarkPoolOrderAccount,
+    caller: &Pubkey,
+) -> Result<()> {
+    let taker = if buy_order.sequence > sell_order.sequence { buy_order } else { sell_order };
+    if taker.priority_fee > 0 {
+        require_keys_eq!(*caller, taker.owner, ErrorCode::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Pays the taker's escrowed priority fee to the treasury once its match has
+/// filled. The fee is only paid once.
+fn collect_priority_fee<'info>(
+    buy_order: &mut Account<'info, DarkPoolOrderAccount>,
+    sell_order: &mut Account<'info, DarkPoolOrderAccount>,
+    treasury: &AccountInfo<'info>,
+) -> Result<()> {
+    let taker = if buy_order.sequence > sell_order.sequence { buy_order } else { sell_order };
+    let amount = taker.priority_fee;
+    if amount == 0 {
+        return Ok(());
+    }
+    taker.priority_fee = 0;
+    **taker.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **treasury.try_borrow_mut_lamports()? += amount;
+
+    emit!(PriorityFeeCollected {
+        order_id: taker.order_id,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Closes the duplicate-submission marker of each order a match left with
+/// nothing to fill, so the same parameters can be submitted again. The rent
+/// moves onto the order and goes back to its owner when the order is closed.
+/// A marker already reused by a newer order is left alone.
+fn release_exhausted_markers<'info>(
+    program_id: &Pubkey,
+    orders: [(&Account<'info, DarkPoolOrderAccount>, &UncheckedAccount<'info>, bool); 2],
+) -> Result<()> {
+    for (order, marker, exhausted) in orders {
+        if !exhausted || marker.owner != program_id || marker.data_is_empty() {
+            continue;
+        }
+        let submitted = SubmittedOrders::try_deserialize(&mut &marker.data.borrow()[..])?;
+        if submitted.order_id == order.order_id {
+            close_program_account(marker, &order.to_account_info())?;
+        }
+    }
+    Ok(())
+}
+
+/// Closes a program-owned account, moving its lamports to `destination`.
+fn close_program_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    **destination.try_borrow_mut_lamports()? += account.lamports();
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&anchor_lang::system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+/// Ends a callback whose computation aborted or whose output failed a check.
+/// The callback still succeeds so its `PendingComputations::finish` sticks;
+/// failing it would revert the decrement and keep `close_sign_pda` blocked.
+fn reject_callback(error: ErrorCode) -> Result<()> {
+    msg!("Callback rejected: {}", error);
+    emit!(CallbackRejected {
+        error_code: error.into(),
+    });
+    Ok(())
+}
+
+/// Callbacks already pin the instructions sysvar by address. With the
+/// `strict-callbacks` feature they also require the top-level instruction to
+/// be the Arcium program, preceded by nothing but compute-budget instructions.
+///
+/// Strictness is chosen when the program is built and deployed; no
+/// `ProtocolConfig` field toggles it. Callbacks are shared by every protocol
+/// and receive no config account, so a deployment that wants strict checks
+/// builds with the feature and redeploys to change it.
+#[cfg(feature = "strict-callbacks")]
+fn verify_callback_context(instructions_sysvar: &AccountInfo) -> Result<()> {
+    use anchor_lang::solana_program::sysvar::instructions::{
+        load_current_index_checked, load_instruction_at_checked,
+    };
+
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    let current_ix = load_instruction_at_checked(current_index, instructions_sysvar)?;
+    require_keys_eq!(
+        current_ix.program_id,
+        ARCIUM_PROG_ID,
+        ErrorCode::InvalidCallbackContext
+    );
+
+    for index in 0..current_index {
+        let ix = load_instruction_at_checked(index, instructions_sysvar)?;
+        require_keys_eq!(
+            ix.program_id,
+            anchor_lang::solana_program::compute_budget::ID,
+            ErrorCode::InvalidCallbackContext
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "strict-callbacks"))]
+fn verify_callback_context(_instructions_sysvar: &AccountInfo) -> Result<()> {
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ComputationKind {
+    InitPosition,
+    UpdateHealth,
+    ProveHealth,
+    InitDarkPoolOrder,
+    MatchOrders,
+    PrivateSwap,
+    BatchHealth,
+    LiquidationRisk,
+    ExtendOrderExpiry,
+    InitMakerVolume,
+    VolumeTier,
+    PriceInBand,
+    ConvertStop,
+    SolvencyRatio,
+    InitMultiCollateral,
+    WeightedHealth,
+    SelectKeeper,
+    AggregateLeverage,
+    PreviewMultiMarket,
+    CollateralComposition,
+    CollateralTopup,
+    RecordLiquidation,
+    ProveCleanHistory,
+    ProveHealthThresholds,
+    PreviewMatch,
+    ProvePriceImprovement,
+    MigratePositionSchema,
+    StressTestPortfolio,
+    CommitDelayedReveal,
+    FinalizeDelayedReveal,
+    MatchBook,
+    BatchWeightedRiskScore,
+    ComputeMaxLiquidatable,
+    CompareProtocolExposure,
+    UpdateAndProveHealth,
+    ComputeAgeDistribution,
+    HandleExpiringOrder,
+    ProveUnleveraged,
+    BatchInitPositions,
+    ProveMinCollateral,
+    ProveNoFrontRunning,
+    ComputeRiskAdjustedScore,
+    ProveUncorrelated,
+    ProveArmsLength,
+    ComputeFeeTier,
+    ProveRiskCapRespected,
+    InitRiskCapTracker,
+    AggregatePortfolioRisk,
+    MatchAndTopup,
+    ProveRewardsEligible,
+    ProveMinCollateralDiversity,
+    ProveMinHealthyCount,
+    ProvePositionParity,
+    ProveRouteOptimal,
+    EstimateCascadeRisk,
+    InitOrderActivity,
+    RecordOrderActivity,
+    ProveNotSpoofing,
+    ProveFloorHeld,
+    ProveSwapLowImpact,
+    ProveTopTier,
+    AutoDeleverage,
+    ProveTermsMatch,
+    ProveLiquidationCooldown,
+    ProveHealthWithFunding,
+    BookImbalance,
+    ConfidenceSlippage,
+    ProveUnderCreditLine,
+    RealizedVolumeTier,
+    ProveCleanSource,
+    LiquidationRiskWeighted,
+    ProvePortfolioNeutral,
+    ProveDebtDiversified,
+    ComputeMarginCallBuffer,
+    ProveNotRoundTrip,
+    ProveSmoothedHealthThreshold,
+    ProvePositionStale,
+    CheckConditionalTrigger,
+    PartialClosePosition,
+    MigratePositionSchemaV1,
+    UpdateMultiCollateral,
+    MigratePositionSchemaV2,
+}
+
+impl ComputationKind {
+    /// Kinds whose queue and callback keep a `ComputationStatus`, which is
+    /// what `retry_computation` gates. Others have no status to retry.
+    pub const fn is_retryable(self) -> bool {
+        matches!(self, ComputationKind::UpdateHealth)
+    }
+
+    /// Kinds queued against a protocol config, which is where their surcharge
+    /// is read from and paid to. Others can't carry one.
+    pub const fn accepts_surcharge(self) -> bool {
+        matches!(
+            self,
+            ComputationKind::SolvencyRatio
+                | ComputationKind::BatchWeightedRiskScore
+                | ComputationKind::StressTestPortfolio
+        )
+    }
+
+    pub const fn estimated_cost(self) -> u64 {
+        match self {
+            ComputationKind::InitPosition
+            | ComputationKind::UpdateHealth
+            | ComputationKind::ProveHealth
+            | ComputationKind::InitDarkPoolOrder
+            | ComputationKind::PrivateSwap
+            | ComputationKind::LiquidationRisk
+            | ComputationKind::ExtendOrderExpiry
+            | ComputationKind::InitMakerVolume
+            | ComputationKind::VolumeTier
+            | ComputationKind::ConvertStop
+            | ComputationKind::SelectKeeper
+            | ComputationKind::CollateralTopup
+            | ComputationKind::RecordLiquidation
+            | ComputationKind::ProveCleanHistory
+            | ComputationKind::ProveHealthThresholds
+            | ComputationKind::ProvePriceImprovement
+            | ComputationKind::MigratePositionSchema
+            | ComputationKind::MigratePositionSchemaV1
+            | ComputationKind::MigratePositionSchemaV2
+            | ComputationKind::CommitDelayedReveal
+            | ComputationKind::FinalizeDelayedReveal
+            | ComputationKind::ComputeMaxLiquidatable
+            | ComputationKind::HandleExpiringOrder
+            | ComputationKind::ProveUnleveraged
+            | ComputationKind::ProveMinCollateral
+            | ComputationKind::ProveNoFrontRunning
+            | ComputationKind::ProveUncorrelated
+            | ComputationKind::ComputeFeeTier
+            | ComputationKind::ProveRiskCapRespected
+            | ComputationKind::InitRiskCapTracker
+            | ComputationKind::ProveRewardsEligible
+            | ComputationKind::ProveRouteOptimal
+            | ComputationKind::InitOrderActivity
+            | ComputationKind::RecordOrderActivity
+            | ComputationKind::ProveNotSpoofing
+            | ComputationKind::ProveFloorHeld
+            | ComputationKind::ProveSwapLowImpact
+            | ComputationKind::AutoDeleverage
+            | ComputationKind::ProveLiquidationCooldown
+            | ComputationKind::ProveHealthWithFunding
+            | ComputationKind::ConfidenceSlippage
+            | ComputationKind::RealizedVolumeTier
+            | ComputationKind::ProveCleanSource
+            | ComputationKind::LiquidationRiskWeighted
+            | ComputationKind::ComputeMarginCallBuffer
+            | ComputationKind::ProveSmoothedHealthThreshold
+            | ComputationKind::ProvePositionStale
+            | ComputationKind::PartialClosePosition => LIGHT_COMPUTATION_COST_LAMPORTS,
+            ComputationKind::MatchOrders
+            | ComputationKind::PriceInBand
+            | ComputationKind::InitMultiCollateral
+            | ComputationKind::UpdateMultiCollateral
+            | ComputationKind::WeightedHealth
+            | ComputationKind::CollateralComposition
+            | ComputationKind::PreviewMatch
+            | ComputationKind::StressTestPortfolio
+            | ComputationKind::BatchWeightedRiskScore
+            | ComputationKind::CompareProtocolExposure
+            | ComputationKind::UpdateAndProveHealth
+            | ComputationKind::ComputeAgeDistribution
+            | ComputationKind::BatchInitPositions
+            | ComputationKind::ProveArmsLength
+            | ComputationKind::MatchAndTopup
+            | ComputationKind::ProveMinCollateralDiversity
+            | ComputationKind::ProveMinHealthyCount
+            | ComputationKind::ProvePositionParity
+            | ComputationKind::ProveTermsMatch
+            | ComputationKind::ProveNotRoundTrip
+            | ComputationKind::CheckConditionalTrigger => MEDIUM_COMPUTATION_COST_LAMPORTS,
+            ComputationKind::BatchHealth
+            | ComputationKind::SolvencyRatio
+            | ComputationKind::AggregateLeverage
+            | ComputationKind::PreviewMultiMarket
+            | ComputationKind::MatchBook
+            | ComputationKind::ComputeRiskAdjustedScore
+            | ComputationKind::EstimateCascadeRisk
+            | ComputationKind::ProveTopTier
+            | ComputationKind::BookImbalance
+            | ComputationKind::ProveUnderCreditLine
+            | ComputationKind::ProvePortfolioNeutral
+            | ComputationKind::ProveDebtDiversified
+            | ComputationKind::AggregatePortfolioRisk => HEAVY_COMPUTATION_COST_LAMPORTS,
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TopupAuthorization {
+    pub position_id: [u8; 32],
+    /// Token account that delegated to this PDA; topups are drawn from it.
+    pub source: Pubkey,
+    /// Collateral account topups are paid into.
+    pub destination: Pubkey,
+    pub max_topup_usd: u64,
+    /// Reserved against the cap, including any topup still in flight.
+    pub used_topup_usd: u64,
+    /// Amount of the in-flight topup; 0 when none is queued.
+    pub pending_topup_usd: u64,
+    pub bump: u8,
+}
+
+impl TopupAuthorization {
+    /// Reserves `amount` against the cap for a topup about to be queued.
+    pub fn reserve(&mut self, amount: u64) -> Result<()> {
+        require!(self.pending_topup_usd == 0, ErrorCode::TopupPending);
+        let used = self
+            .used_topup_usd
+            .checked_add(amount)
+            .ok_or(ErrorCode::TopupCapExceeded)?;
+        require!(used <= self.max_topup_usd, ErrorCode::TopupCapExceeded);
+        self.used_topup_usd = used;
+        self.pending_topup_usd = amount;
+        Ok(())
+    }
+
+    /// Hands an unspent reservation back to the cap and returns its amount.
+    pub fn release(&mut self) -> u64 {
+        let amount = self.pending_topup_usd;
+        self.used_topup_usd -= amount;
+        self.pending_topup_usd = 0;
+        amount
+    }
+}
+
+/// Latest MXE ciphertext of the encrypted struct behind `subject`, a position,
+/// order or other account with encrypted state. Callbacks overwrite it with
+/// each new output, so the next computation sees what the last one wrote.
+#[account]
+#[derive(InitSpace)]
+pub struct EncryptedState {
+    pub subject: Pubkey,
+    pub nonce: u128,
+    #[max_len(MAX_ENCRYPTED_STATE_LEN)]
+    pub ciphertexts: Vec<[u8; 32]>,
+    pub bump: u8,
+}
+
+impl EncryptedState {
+    /// Ties a freshly created state account to its subject; a no-op afterwards.
+    pub fn bind(&mut self, subject: Pubkey, bump: u8) {
+        self.subject = subject;
+        self.bump = bump;
+    }
+
+    pub fn store(&mut self, nonce: u128, ciphertexts: &[[u8; 32]]) -> Result<()> {
+        require!(
+            ciphertexts.len() <= MAX_ENCRYPTED_STATE_LEN,
+            ErrorCode::EncryptedStateTooLarge
+        );
+        self.nonce = nonce;
+        self.ciphertexts = ciphertexts.to_vec();
+        Ok(())
+    }
+}
+
+/// Retry bookkeeping for a computation, keyed by its kind and the account it
+/// runs on (e.g. the position id), so it follows the real computation.
+#[account]
+#[derive(InitSpace)]
+pub struct ComputationStatus {
+    pub kind: ComputationKind,
+    pub target: [u8; 32],
+    pub retry_count: u8,
+    /// Earliest time `retry_computation` accepts the next retry.
+    pub next_retry_after: i64,
+    pub permanently_failed: bool,
+    /// Queued and not yet completed; still set after an abort.
+    pub in_flight: bool,
+    /// Set by `retry_computation`, spent by the next re-queue.
+    pub retry_granted: bool,
+    pub bump: u8,
+}
+
+impl ComputationStatus {
+    /// Called where the computation is queued. An attempt still in flight
+    /// can only be re-queued on a retry granted by `retry_computation`, so
+    /// the backoff can't be skipped by queueing directly. A fresh attempt
+    /// starts the first backoff from `now`.
+    pub fn begin(&mut self, kind: ComputationKind, target: [u8; 32], bump: u8, now: i64) -> Result<()> {
+        if self.in_flight {
+            require!(self.retry_granted, ErrorCode::RetryNotGranted);
+        } else {
+            self.next_retry_after = now + RETRY_BASE_BACKOFF_SECONDS;
+        }
+        self.kind = kind;
+        self.target = target;
+        self.bump = bump;
+        self.in_flight = true;
+        self.retry_granted = false;
+        Ok(())
+    }
+
+    /// Called from a successful callback; the next attempt starts fresh.
+    pub fn complete(&mut self) {
+        self.retry_count = 0;
+        self.next_retry_after = 0;
+        self.permanently_failed = false;
+        self.in_flight = false;
+        self.retry_granted = false;
+    }
+}
+
+/// Owner-set bounds for keeper-driven debt repayment on a position.
+#[account]
+#[derive(InitSpace)]
+pub struct AutoDeleverageConfig {
+    pub position_id: [u8; 32],
+    pub trigger_health_bps: u64,
+    pub target_health_bps: u64,
+    /// Token account that delegated to this PDA; repayments are drawn from it.
+    pub source: Pubkey,
+    /// Debt account repayments are paid into.
+    pub repayment: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DelayedRevealAccount {
+    pub owner: Pubkey,
+    pub reveal_id: [u8; 32],
+    pub reveal_after: i64,
+    /// Set once the commit callback has stored the MXE ciphertext below.
+    pub committed: bool,
+    pub nonce: u128,
+    pub ciphertext: [u8; 32],
+    pub finalized: bool,
+    pub bump: u8,
+}
+
+/// Escrows the discount share of bundled computations' fees until their
+/// callbacks refund it to the payer.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeRebatePool {
+    pub bump: u8,
+}
+
+/// Written at queue time and consumed by the callback, which refunds the
+/// escrowed discount for computations flagged as bundled.
+#[account]
+#[derive(InitSpace)]
+pub struct BundleReceipt {
+    pub payer: Pubkey,
+    pub bundled: bool,
+    pub discount_lamports: u64,
+    pub bump: u8,
+}
+
+#[init_computation_definition_accounts("init_encrypted_position", payer)]
+#[derive(Accounts)]
+pub struct InitPositionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_health_threshold", payer)]
+#[derive(Accounts)]
+pub struct InitHealthCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_dark_pool_order", payer)]
+#[derive(Accounts)]
+pub struct InitDarkPoolCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("execute_private_swap", payer)]
+#[derive(Accounts)]
+pub struct InitSwapCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("extend_order_expiry", payer)]
+#[derive(Accounts)]
+pub struct InitExtendExpiryCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_maker_volume", payer)]
+#[derive(Accounts)]
+pub struct InitMakerVolumeCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_volume_tier", payer)]
+#[derive(Accounts)]
+pub struct InitVolumeTierCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("update_health_factor", payer)]
+#[derive(Accounts)]
+pub struct InitUpdateHealthCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("match_dark_pool_orders", payer)]
+#[derive(Accounts)]
+pub struct InitMatchOrdersCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("batch_health_check", payer)]
+#[derive(Accounts)]
+pub struct InitBatchHealthCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("calculate_liquidation_risk", payer)]
+#[derive(Accounts)]
+pub struct InitLiquidationRiskCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_price_in_band", payer)]
+#[derive(Accounts)]
+pub struct InitPriceInBandCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("convert_stop_to_market", payer)]
+#[derive(Accounts)]
+pub struct InitConvertStopCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compute_solvency_ratio", payer)]
+#[derive(Accounts)]
+pub struct InitSolvencyRatioCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_multi_collateral_position", payer)]
+#[derive(Accounts)]
+pub struct InitMultiCollateralCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("update_multi_collateral_position", payer)]
+#[derive(Accounts)]
+pub struct InitUpdateMultiCollateralCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_weighted_health", payer)]
+#[derive(Accounts)]
+pub struct InitWeightedHealthCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("select_keeper", payer)]
+#[derive(Accounts)]
+pub struct InitSelectKeeperCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_aggregate_leverage_bounded", payer)]
+#[derive(Accounts)]
+pub struct InitAggregateLeverageCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("preview_multi_market", payer)]
+#[derive(Accounts)]
+pub struct InitMultiMarketCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_collateral_composition", payer)]
+#[derive(Accounts)]
+pub struct InitCollateralCompositionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("apply_collateral_topup", payer)]
+#[derive(Accounts)]
+pub struct InitCollateralTopupCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("record_liquidation", payer)]
+#[derive(Accounts)]
+pub struct InitRecordLiquidationCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_clean_history", payer)]
+#[derive(Accounts)]
+pub struct InitProveCleanHistoryCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_health_thresholds", payer)]
+#[derive(Accounts)]
+pub struct InitProveHealthThresholdsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("preview_match", payer)]
+#[derive(Accounts)]
+pub struct InitPreviewMatchCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_price_improvement", payer)]
+#[derive(Accounts)]
+pub struct InitProvePriceImprovementCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("migrate_position_schema", payer)]
+#[derive(Accounts)]
+pub struct InitMigratePositionSchemaCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("migrate_position_schema_v1", payer)]
+#[derive(Accounts)]
+pub struct InitMigratePositionSchemaV1CompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("migrate_position_schema_v2", payer)]
+#[derive(Accounts)]
+pub struct InitMigratePositionSchemaV2CompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("stress_test_portfolio", payer)]
+#[derive(Accounts)]
+pub struct InitStressTestPortfolioCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("commit_delayed_reveal", payer)]
+#[derive(Accounts)]
+pub struct InitCommitDelayedRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("finalize_delayed_reveal", payer)]
+#[derive(Accounts)]
+pub struct InitFinalizeDelayedRevealCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("match_book", payer)]
+#[derive(Accounts)]
+pub struct InitMatchBookCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("batch_weighted_risk_score", payer)]
+#[derive(Accounts)]
+pub struct InitBatchWeightedRiskScoreCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compute_max_liquidatable", payer)]
+#[derive(Accounts)]
+pub struct InitComputeMaxLiquidatableCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compare_protocol_exposure", payer)]
+#[derive(Accounts)]
+pub struct InitCompareProtocolExposureCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("update_and_prove_health", payer)]
+#[derive(Accounts)]
+pub struct InitUpdateAndProveHealthCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compute_age_distribution", payer)]
+#[derive(Accounts)]
+pub struct InitComputeAgeDistributionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("handle_expiring_order", payer)]
+#[derive(Accounts)]
+pub struct InitHandleExpiringOrderCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_unleveraged", payer)]
+#[derive(Accounts)]
+pub struct InitProveUnleveragedCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("batch_init_encrypted_positions", payer)]
+#[derive(Accounts)]
+pub struct InitBatchInitPositionsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_min_collateral", payer)]
+#[derive(Accounts)]
+pub struct InitProveMinCollateralCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_no_front_running", payer)]
+#[derive(Accounts)]
+pub struct InitProveNoFrontRunningCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compute_risk_adjusted_score", payer)]
+#[derive(Accounts)]
+pub struct InitComputeRiskAdjustedScoreCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_uncorrelated", payer)]
+#[derive(Accounts)]
+pub struct InitProveUncorrelatedCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_arms_length", payer)]
+#[derive(Accounts)]
+pub struct InitProveArmsLengthCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compute_fee_tier", payer)]
+#[derive(Accounts)]
+pub struct InitComputeFeeTierCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_risk_cap_respected", payer)]
+#[derive(Accounts)]
+pub struct InitProveRiskCapRespectedCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_risk_cap_tracker", payer)]
+#[derive(Accounts)]
+pub struct InitInitRiskCapTrackerCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("aggregate_portfolio_risk", payer)]
+#[derive(Accounts)]
+pub struct InitAggregatePortfolioRiskCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("match_and_topup", payer)]
+#[derive(Accounts)]
+pub struct InitMatchAndTopupCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_rewards_eligible", payer)]
+#[derive(Accounts)]
+pub struct InitProveRewardsEligibleCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_min_collateral_diversity", payer)]
+#[derive(Accounts)]
+pub struct InitProveMinCollateralDiversityCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_min_healthy_count", payer)]
+#[derive(Accounts)]
+pub struct InitProveMinHealthyCountCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_position_parity", payer)]
+#[derive(Accounts)]
+pub struct InitProvePositionParityCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_route_optimal", payer)]
+#[derive(Accounts)]
+pub struct InitProveRouteOptimalCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("estimate_cascade_risk", payer)]
+#[derive(Accounts)]
+pub struct InitEstimateCascadeRiskCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_order_activity", payer)]
+#[derive(Accounts)]
+pub struct InitInitOrderActivityCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("record_order_activity", payer)]
+#[derive(Accounts)]
+pub struct InitRecordOrderActivityCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_not_spoofing", payer)]
+#[derive(Accounts)]
+pub struct InitProveNotSpoofingCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_floor_held", payer)]
+#[derive(Accounts)]
+pub struct InitProveFloorHeldCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_swap_low_impact", payer)]
+#[derive(Accounts)]
+pub struct InitProveSwapLowImpactCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_top_tier", payer)]
+#[derive(Accounts)]
+pub struct InitProveTopTierCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("apply_auto_deleverage", payer)]
+#[derive(Accounts)]
+pub struct InitAutoDeleverageCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_terms_match", payer)]
+#[derive(Accounts)]
+pub struct InitProveTermsMatchCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_liquidation_cooldown_passed", payer)]
+#[derive(Accounts)]
+pub struct InitProveLiquidationCooldownCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_health_with_funding", payer)]
+#[derive(Accounts)]
+pub struct InitProveHealthWithFundingCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compute_book_imbalance", payer)]
+#[derive(Accounts)]
+pub struct InitComputeBookImbalanceCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compute_slippage_from_confidence", payer)]
+#[derive(Accounts)]
+pub struct InitComputeSlippageFromConfidenceCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_under_credit_line", payer)]
+#[derive(Accounts)]
+pub struct InitProveUnderCreditLineCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_realized_volume_tier", payer)]
+#[derive(Accounts)]
+pub struct InitProveRealizedVolumeTierCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_clean_source", payer)]
+#[derive(Accounts)]
+pub struct InitProveCleanSourceCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("calculate_liquidation_risk_weighted", payer)]
+#[derive(Accounts)]
+pub struct InitCalculateLiquidationRiskWeightedCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_portfolio_neutral", payer)]
+#[derive(Accounts)]
+pub struct InitProvePortfolioNeutralCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_debt_diversified", payer)]
+#[derive(Accounts)]
+pub struct InitProveDebtDiversifiedCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("compute_margin_call_buffer", payer)]
+#[derive(Accounts)]
+pub struct InitComputeMarginCallBufferCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_not_round_trip", payer)]
+#[derive(Accounts)]
+pub struct InitProveNotRoundTripCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_smoothed_health_threshold", payer)]
+#[derive(Accounts)]
+pub struct InitProveSmoothedHealthThresholdCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("prove_position_stale", payer)]
+#[derive(Accounts)]
+pub struct InitProvePositionStaleCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("check_conditional_trigger", payer)]
+#[derive(Accounts)]
+pub struct InitCheckConditionalTriggerCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("partial_close_position", payer)]
+#[derive(Accounts)]
+pub struct InitPartialClosePositionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("init_encrypted_position", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32], protocol: u8)]
+pub struct InitEncryptedPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        init,
+        space = 8 + PositionAccount::INIT_SPACE,
+        payer = payer,
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[protocol]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// Registered feed for the asset the position is priced on; bound at init
+    /// so the position can be risk-checked straight away.
+    pub oracle_feed: Account<'info, OracleFeed>,
+    #[account(seeds = [PROTOCOL_STATE_SEED], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        payer = payer,
+        seeds = [OWNER_REGISTRY_SEED, payer.key().as_ref()],
+        bump,
+    )]
+    pub owner_registry: Account<'info, OwnerRegistry>,
+}
+
+#[callback_accounts("init_encrypted_position")]
+#[derive(Accounts)]
+pub struct InitEncryptedPositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("update_health_factor", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct UpdateHealthFactor<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_UPDATE_HEALTH))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    /// CHECK: must be the feed bound to the position via `rebind_position_oracle`.
+    #[account(
+        constraint = position_account.oracle != Pubkey::default()
+            && oracle.key() == position_account.oracle @ ErrorCode::OracleNotBound,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+    #[account(constraint = oracle_feed.oracle == oracle.key() @ ErrorCode::OracleNotBound)]
+    pub oracle_feed: Account<'info, OracleFeed>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut, seeds = [REBATE_VAULT_SEED], bump = rebate_vault.bump)]
+    pub rebate_vault: Account<'info, RebateVault>,
+    #[account(
+        init_if_needed,
+        space = 8 + ComputationStatus::INIT_SPACE,
+        payer = payer,
+        seeds = [
+            COMPUTATION_STATUS_SEED,
+            &[ComputationKind::UpdateHealth as u8],
+            position_id.as_ref(),
+        ],
+        bump,
+    )]
+    pub computation_status: Box<Account<'info, ComputationStatus>>,
+}
+
+#[callback_accounts("update_health_factor")]
+#[derive(Accounts)]
+pub struct UpdateHealthFactorCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_UPDATE_HEALTH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    #[account(mut)]
+    pub computation_status: Box<Account<'info, ComputationStatus>>,
+}
+
+
+#[queue_computation_accounts("prove_health_threshold", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveHealthThreshold<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_HEALTH))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [ALERT_CONFIG_SEED, position_id.as_ref()],
+        bump = alert_config.bump,
+    )]
+    pub alert_config: Account<'info, AlertConfig>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    /// CHECK: must be the feed bound to the position via `rebind_position_oracle`.
+    #[account(
+        constraint = position_account.oracle != Pubkey::default()
+            && oracle.key() == position_account.oracle @ ErrorCode::OracleNotBound,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("prove_health_threshold")]
+#[derive(Accounts)]
+pub struct ProveHealthThresholdCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_HEALTH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub alert_config: Account<'info, AlertConfig>,
+}
+
+#[queue_computation_accounts("init_dark_pool_order", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, order_id: [u8; 32], side: u8, expires_at: i64, order_hash: [u8; 32])]
+pub struct InitDarkPoolOrder<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_DARK_ORDER))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        init_if_needed,
+        space = 8 + DarkPoolState::INIT_SPACE,
+        payer = payer,
+        seeds = [DARK_POOL_SEED],
+        bump,
+    )]
+    pub dark_pool_state: Account<'info, DarkPoolState>,
+    #[account(
+        init,
+        space = 8 + DarkPoolOrderAccount::INIT_SPACE,
+        payer = payer,
+        seeds = [DARK_POOL_SEED, order_id.as_ref()],
+        bump,
+    )]
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + SubmittedOrders::INIT_SPACE,
+        payer = payer,
+        seeds = [SUBMITTED_ORDER_SEED, payer.key().as_ref(), order_hash.as_ref()],
+        bump,
+    )]
+    pub submitted_order: Account<'info, SubmittedOrders>,
+    #[account(
+        mut,
+        seeds = [ORDER_ACTIVITY_SEED, payer.key().as_ref()],
+        bump = order_activity.bump,
+    )]
+    pub order_activity: Account<'info, OrderActivityAccount>,
+}
+
+#[callback_accounts("init_dark_pool_order")]
+#[derive(Accounts)]
+pub struct InitDarkPoolOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_DARK_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("match_dark_pool_orders", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, buy_order_id: [u8; 32], sell_order_id: [u8; 32])]
+pub struct MatchDarkPoolOrders<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_ORDERS))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [DARK_POOL_SEED, buy_order_id.as_ref()], bump = buy_order.bump)]
+    pub buy_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut, seeds = [DARK_POOL_SEED, sell_order_id.as_ref()], bump = sell_order.bump)]
+    pub sell_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut, seeds = [MAKER_VOLUME_SEED, maker_volume.maker.as_ref()], bump = maker_volume.bump)]
+    pub maker_volume: Account<'info, MakerVolumeAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, maker_volume.key().as_ref()],
+        bump,
+    )]
+    pub volume_state: Box<Account<'info, EncryptedState>>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[DARK_POOL_CONFIG_ID]],
+        bump = dark_pool_config.bump,
+    )]
+    pub dark_pool_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init_if_needed,
+        space = 8 + MatchSequenceCounter::INIT_SPACE,
+        payer = payer,
+        seeds = [MATCH_SEQUENCE_SEED],
+        bump,
+    )]
+    pub match_sequence_counter: Account<'info, MatchSequenceCounter>,
+    #[account(seeds = [REBATE_VAULT_SEED], bump = rebate_vault.bump)]
+    pub rebate_vault: Account<'info, RebateVault>,
+    #[account(seeds = [ORDER_ACTIVITY_SEED, buy_order.owner.as_ref()], bump = buy_activity.bump)]
+    pub buy_activity: Account<'info, OrderActivityAccount>,
+    #[account(seeds = [ORDER_ACTIVITY_SEED, sell_order.owner.as_ref()], bump = sell_activity.bump)]
+    pub sell_activity: Account<'info, OrderActivityAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, buy_order.key().as_ref()],
+        bump,
+    )]
+    pub buy_order_state: Box<Account<'info, EncryptedState>>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, sell_order.key().as_ref()],
+        bump,
+    )]
+    pub sell_order_state: Box<Account<'info, EncryptedState>>,
+    /// CHECK: receives the taker's priority fee; must be the dark pool's treasury.
+    #[account(mut, address = dark_pool_config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: the buy order's duplicate-submission marker; it may already be
+    /// closed, so it's only read in the callback.
+    #[account(
+        mut,
+        seeds = [SUBMITTED_ORDER_SEED, buy_order.owner.as_ref(), buy_order.order_hash.as_ref()],
+        bump,
+    )]
+    pub buy_marker: UncheckedAccount<'info>,
+    /// CHECK: the sell order's duplicate-submission marker, as `buy_marker`.
+    #[account(
+        mut,
+        seeds = [SUBMITTED_ORDER_SEED, sell_order.owner.as_ref(), sell_order.order_hash.as_ref()],
+        bump,
+    )]
+    pub sell_marker: UncheckedAccount<'info>,
+}
+
+#[callback_accounts("match_dark_pool_orders")]
+#[derive(Accounts)]
+pub struct MatchDarkPoolOrdersCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_ORDERS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub maker_volume: Account<'info, MakerVolumeAccount>,
+    #[account(mut)]
+    pub volume_state: Box<Account<'info, EncryptedState>>,
+    #[account(mut, seeds = [MATCH_SEQUENCE_SEED], bump = match_sequence_counter.bump)]
+    pub match_sequence_counter: Account<'info, MatchSequenceCounter>,
+    #[account(mut)]
+    pub buy_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut)]
+    pub sell_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut, seeds = [REBATE_VAULT_SEED], bump = rebate_vault.bump)]
+    pub rebate_vault: Account<'info, RebateVault>,
+    #[account(mut)]
+    pub buy_activity: Account<'info, OrderActivityAccount>,
+    #[account(mut)]
+    pub sell_activity: Account<'info, OrderActivityAccount>,
+    #[account(mut)]
+    pub buy_order_state: Box<Account<'info, EncryptedState>>,
+    #[account(mut)]
+    pub sell_order_state: Box<Account<'info, EncryptedState>>,
+    /// CHECK: the dark pool treasury, checked at queue time.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: the buy order's marker, checked at queue time.
+    #[account(mut)]
+    pub buy_marker: UncheckedAccount<'info>,
+    /// CHECK: the sell order's marker, checked at queue time.
+    #[account(mut)]
+    pub sell_marker: UncheckedAccount<'info>,
+}
+
+#[queue_computation_accounts("execute_private_swap", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, intent_id: [u8; 32])]
+pub struct ExecutePrivateSwap<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PRIVATE_SWAP))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: the registered feed for one side of the intent's pair; the
+    /// circuit checks `oracle_feed.mint` against the intent.
+    #[account(address = oracle_feed.oracle @ ErrorCode::OracleNotBound)]
+    pub oracle: UncheckedAccount<'info>,
+    pub oracle_feed: Account<'info, OracleFeed>,
+    #[account(
+        init_if_needed,
+        space = 8 + SwapIntentAccount::INIT_SPACE,
+        payer = payer,
+        seeds = [SWAP_INTENT_SEED, intent_id.as_ref()],
+        bump,
+    )]
+    pub swap_intent: Account<'info, SwapIntentAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, swap_intent.key().as_ref()],
+        bump,
+    )]
+    pub intent_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("execute_private_swap")]
+#[derive(Accounts)]
+pub struct ExecutePrivateSwapCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PRIVATE_SWAP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub swap_intent: Account<'info, SwapIntentAccount>,
+    #[account(mut)]
+    pub intent_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("batch_health_check", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct BatchHealthCheck<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_HEALTH))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("batch_health_check")]
+#[derive(Accounts)]
+pub struct BatchHealthCheckCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_HEALTH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("calculate_liquidation_risk", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct CalculateLiquidationRisk<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_LIQUIDATION_RISK))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// CHECK: must be the feed bound to the position via `rebind_position_oracle`.
+    #[account(
+        constraint = position_account.oracle != Pubkey::default()
+            && oracle.key() == position_account.oracle @ ErrorCode::OracleNotBound,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+}
+
+#[callback_accounts("calculate_liquidation_risk")]
+#[derive(Accounts)]
+pub struct CalculateLiquidationRiskCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_LIQUIDATION_RISK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[queue_computation_accounts("extend_order_expiry", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, order_id: [u8; 32])]
+pub struct ExtendOrderExpiry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EXTEND_EXPIRY))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [DARK_POOL_SEED, order_id.as_ref()],
+        bump = order_account.bump,
+        constraint = order_account.owner == payer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, order_account.key().as_ref()],
+        bump,
+    )]
+    pub order_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("extend_order_expiry")]
+#[derive(Accounts)]
+pub struct ExtendOrderExpiryCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EXTEND_EXPIRY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut)]
+    pub order_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("init_maker_volume", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitMakerVolume<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MAKER_VOLUME))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        init,
+        space = 8 + MakerVolumeAccount::INIT_SPACE,
+        payer = payer,
+        seeds = [MAKER_VOLUME_SEED, payer.key().as_ref()],
+        bump,
+    )]
+    pub maker_volume: Account<'info, MakerVolumeAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, maker_volume.key().as_ref()],
+        bump,
+    )]
+    pub volume_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("init_maker_volume")]
+#[derive(Accounts)]
+pub struct InitMakerVolumeCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MAKER_VOLUME))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub volume_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("prove_volume_tier", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveVolumeTier<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VOLUME_TIER))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [MAKER_VOLUME_SEED, payer.key().as_ref()],
+        bump = maker_volume.bump,
+    )]
+    pub maker_volume: Account<'info, MakerVolumeAccount>,
+}
+
+#[callback_accounts("prove_volume_tier")]
+#[derive(Accounts)]
+pub struct ProveVolumeTierCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VOLUME_TIER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: [u8; 32])]
+pub struct SetPositionGuardians<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: [u8; 32], new_owner: Pubkey)]
+pub struct GuardianRecover<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init_if_needed,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        payer = payer,
+        seeds = [OWNER_REGISTRY_SEED, position_account.owner.as_ref()],
+        bump,
+    )]
+    pub previous_owner_registry: Account<'info, OwnerRegistry>,
+    #[account(
+        init_if_needed,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        payer = payer,
+        seeds = [OWNER_REGISTRY_SEED, new_owner.as_ref()],
+        bump,
+    )]
+    pub new_owner_registry: Account<'info, OwnerRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("prove_price_in_band", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, buy_order_id: [u8; 32], sell_order_id: [u8; 32])]
+pub struct ProvePriceInBand<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PRICE_IN_BAND))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [DARK_POOL_SEED, buy_order_id.as_ref()], bump = buy_order.bump)]
+    pub buy_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(seeds = [DARK_POOL_SEED, sell_order_id.as_ref()], bump = sell_order.bump)]
+    pub sell_order: Account<'info, DarkPoolOrderAccount>,
+}
+
+#[callback_accounts("prove_price_in_band")]
+#[derive(Accounts)]
+pub struct ProvePriceInBandCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PRICE_IN_BAND))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("convert_stop_to_market", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, order_id: [u8; 32])]
+pub struct ConvertStopToMarket<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONVERT_STOP))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [DARK_POOL_SEED, order_id.as_ref()],
+        bump = order_account.bump,
+        constraint = order_account.owner == payer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, order_account.key().as_ref()],
+        bump,
+    )]
+    pub order_state: Box<Account<'info, EncryptedState>>,
+    /// CHECK: must be the feed registered in `oracle_feed`.
+    #[account(address = oracle_feed.oracle @ ErrorCode::OracleNotBound)]
+    pub oracle: UncheckedAccount<'info>,
+    pub oracle_feed: Account<'info, OracleFeed>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[DARK_POOL_CONFIG_ID]],
+        bump = dark_pool_config.bump,
+    )]
+    pub dark_pool_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("convert_stop_to_market")]
+#[derive(Accounts)]
+pub struct ConvertStopToMarketCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONVERT_STOP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut)]
+    pub order_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolState<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        space = 8 + ProtocolState::INIT_SPACE,
+        payer = admin,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxPositionsPerOwner<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSignPda<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        mut,
+        close = admin,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Account<'info, PendingComputations>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: [u8; 32])]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        close = owner,
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        payer = owner,
+        seeds = [OWNER_REGISTRY_SEED, owner.key().as_ref()],
+        bump,
+    )]
+    pub owner_registry: Account<'info, OwnerRegistry>,
+    /// CHECK: the position's alert config, closed along with it if it exists.
+    #[account(mut, seeds = [ALERT_CONFIG_SEED, position_id.as_ref()], bump)]
+    pub alert_config: UncheckedAccount<'info>,
+    /// CHECK: the position's topup authorization, closed along with it if it exists.
+    #[account(mut, seeds = [TOPUP_AUTH_SEED, position_id.as_ref()], bump)]
+    pub topup_authorization: UncheckedAccount<'info>,
+    /// CHECK: the position's deleverage config, closed along with it if it exists.
+    #[account(mut, seeds = [AUTO_DELEVERAGE_SEED, position_id.as_ref()], bump)]
+    pub deleverage_config: UncheckedAccount<'info>,
+    /// CHECK: the position's encrypted state, closed along with it if it exists.
+    #[account(mut, seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()], bump)]
+    pub position_state: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(protocol_id: u8)]
+pub struct InitProtocolConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init,
+        space = 8 + ProtocolConfig::INIT_SPACE,
+        payer = admin,
+        seeds = [PROTOCOL_CONFIG_SEED, &[protocol_id]],
+        bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(protocol_id: u8)]
+pub struct UpdateProtocolConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        mut,
+        seeds = [PROTOCOL_CONFIG_SEED, &[protocol_id]],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct BatchUpdateProtocolConfigs<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[queue_computation_accounts("compute_solvency_ratio", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, protocol_id: u8)]
+pub struct ComputeSolvencyRatio<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SOLVENCY_RATIO))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[protocol_id]],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// CHECK: receives the surcharge; must be the protocol's treasury.
+    #[account(mut, address = protocol_config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+#[callback_accounts("compute_solvency_ratio")]
+#[derive(Accounts)]
+pub struct ComputeSolvencyRatioCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SOLVENCY_RATIO))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("init_multi_collateral_position", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct InitMultiCollateralPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MULTI_COLLATERAL))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        init,
+        space = 8 + MultiCollateralPositionAccount::INIT_SPACE,
+        payer = payer,
+        seeds = [MULTI_COLLATERAL_SEED, position_id.as_ref()],
+        bump,
+    )]
+    pub multi_collateral: Account<'info, MultiCollateralPositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, multi_collateral.key().as_ref()],
+        bump,
+    )]
+    pub multi_collateral_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("init_multi_collateral_position")]
+#[derive(Accounts)]
+pub struct InitMultiCollateralPositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_MULTI_COLLATERAL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub multi_collateral_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("update_multi_collateral_position", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct UpdateMultiCollateralPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_UPDATE_MULTI_COLLATERAL))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Attests the deposit's source flags; a depositor can't clear their own.
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(seeds = [MULTI_COLLATERAL_SEED, position_id.as_ref()], bump = multi_collateral.bump)]
+    pub multi_collateral: Account<'info, MultiCollateralPositionAccount>,
+    #[account(mut, seeds = [ENCRYPTED_STATE_SEED, multi_collateral.key().as_ref()], bump = multi_collateral_state.bump)]
+    pub multi_collateral_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("update_multi_collateral_position")]
+#[derive(Accounts)]
+pub struct UpdateMultiCollateralPositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_UPDATE_MULTI_COLLATERAL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub multi_collateral_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("prove_weighted_health", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveWeightedHealth<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_WEIGHTED_HEALTH))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("prove_weighted_health")]
+#[derive(Accounts)]
+pub struct ProveWeightedHealthCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_WEIGHTED_HEALTH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("select_keeper", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SelectKeeper<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SELECT_KEEPER))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [KEEPER_REGISTRY_SEED], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+    /// CHECK: address-constrained to the SlotHashes sysvar, read for the selection seed.
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+}
+
+#[callback_accounts("select_keeper")]
+#[derive(Accounts)]
+pub struct SelectKeeperCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SELECT_KEEPER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut, seeds = [KEEPER_REGISTRY_SEED], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct ManageKeepers<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        space = 8 + KeeperRegistry::INIT_SPACE,
+        payer = admin,
+        seeds = [KEEPER_REGISTRY_SEED],
+        bump,
+    )]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: [u8; 32])]
+pub struct SetAlertConfig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + AlertConfig::INIT_SPACE,
+        payer = owner,
+        seeds = [ALERT_CONFIG_SEED, position_id.as_ref()],
+        bump,
+    )]
+    pub alert_config: Account<'info, AlertConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BatchCancelOrders<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ORDER_ACTIVITY_SEED, owner.key().as_ref()],
+        bump = order_activity.bump,
+    )]
+    pub order_activity: Account<'info, OrderActivityAccount>,
+}
+
+#[queue_computation_accounts("prove_aggregate_leverage_bounded", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveAggregateLeverageBounded<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_AGGREGATE_LEVERAGE))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("prove_aggregate_leverage_bounded")]
+#[derive(Accounts)]
+pub struct ProveAggregateLeverageBoundedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_AGGREGATE_LEVERAGE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("preview_multi_market", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct PreviewMultiMarket<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MULTI_MARKET))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[DARK_POOL_CONFIG_ID]],
+        bump = dark_pool_config.bump,
+    )]
+    pub dark_pool_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("preview_multi_market")]
+#[derive(Accounts)]
+pub struct PreviewMultiMarketCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MULTI_MARKET))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_collateral_composition", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveCollateralComposition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COLLATERAL_COMPOSITION))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("prove_collateral_composition")]
+#[derive(Accounts)]
+pub struct ProveCollateralCompositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COLLATERAL_COMPOSITION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[derive(Accounts)]
+pub struct EstimateComputationCost {}
+
+#[derive(Accounts)]
+pub struct ProveBalanceSufficient<'info> {
+    pub payer: Signer<'info>,
+}
+
+#[queue_computation_accounts("apply_collateral_topup", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ExecuteAutoTopup<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COLLATERAL_TOPUP))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    #[account(
+        mut,
+        seeds = [TOPUP_AUTH_SEED, position_id.as_ref()],
+        bump = topup_authorization.bump,
+    )]
+    pub topup_authorization: Account<'info, TopupAuthorization>,
+    /// CHECK: the authorized source token account; SPL Token checks the delegation.
+    #[account(mut, address = topup_authorization.source)]
+    pub source: UncheckedAccount<'info>,
+    /// CHECK: the authorized collateral account topups are paid into.
+    #[account(mut, address = topup_authorization.destination)]
+    pub destination: UncheckedAccount<'info>,
+    /// CHECK: must be SPL Token.
+    #[account(address = SPL_TOKEN_PROGRAM_ID)]
+    pub token_program: UncheckedAccount<'info>,
+    #[account(seeds = [KEEPER_REGISTRY_SEED], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("apply_collateral_topup")]
+#[derive(Accounts)]
+pub struct ExecuteAutoTopupCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COLLATERAL_TOPUP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    #[account(mut)]
+    pub topup_authorization: Account<'info, TopupAuthorization>,
+    /// CHECK: the source recorded on `topup_authorization` at queue time.
+    #[account(mut)]
+    pub source: UncheckedAccount<'info>,
+    /// CHECK: the destination recorded on `topup_authorization` at queue time.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    /// CHECK: SPL Token, checked again before the transfer.
+    pub token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: [u8; 32])]
+pub struct AuthorizeAutoTopup<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init_if_needed,
+        space = 8 + TopupAuthorization::INIT_SPACE,
+        payer = owner,
+        seeds = [TOPUP_AUTH_SEED, position_id.as_ref()],
+        bump,
+    )]
+    pub topup_authorization: Account<'info, TopupAuthorization>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("record_liquidation", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct RecordLiquidation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RECORD_LIQUIDATION))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    #[account(seeds = [KEEPER_REGISTRY_SEED], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("record_liquidation")]
+#[derive(Accounts)]
+pub struct RecordLiquidationCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RECORD_LIQUIDATION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("prove_clean_history", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveCleanHistory<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_CLEAN_HISTORY))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[callback_accounts("prove_clean_history")]
+#[derive(Accounts)]
+pub struct ProveCleanHistoryCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_CLEAN_HISTORY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_health_thresholds", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveHealthThresholds<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_HEALTH_THRESHOLDS))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[callback_accounts("prove_health_thresholds")]
+#[derive(Accounts)]
+pub struct ProveHealthThresholdsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_HEALTH_THRESHOLDS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("preview_match", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, buy_order_id: [u8; 32], sell_order_id: [u8; 32])]
+pub struct PreviewMatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PREVIEW_MATCH))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [DARK_POOL_SEED, buy_order_id.as_ref()], bump = buy_order.bump)]
+    pub buy_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(seeds = [DARK_POOL_SEED, sell_order_id.as_ref()], bump = sell_order.bump)]
+    pub sell_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[DARK_POOL_CONFIG_ID]],
+        bump = dark_pool_config.bump,
+    )]
+    pub dark_pool_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("preview_match")]
+#[derive(Accounts)]
+pub struct PreviewMatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PREVIEW_MATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_price_improvement", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, intent_id: [u8; 32])]
+pub struct ProvePriceImprovement<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_PRICE_IMPROVEMENT))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [SWAP_INTENT_SEED, intent_id.as_ref()], bump = swap_intent.bump)]
+    pub swap_intent: Account<'info, SwapIntentAccount>,
+}
+
+#[callback_accounts("prove_price_improvement")]
+#[derive(Accounts)]
+pub struct ProvePriceImprovementCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_PRICE_IMPROVEMENT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("migrate_position_schema", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct MigratePositionSchema<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MIGRATE_POSITION_SCHEMA))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        constraint = position_account.owner == payer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("migrate_position_schema")]
+#[derive(Accounts)]
+pub struct MigratePositionSchemaCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MIGRATE_POSITION_SCHEMA))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("migrate_position_schema_v1", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct MigratePositionSchemaV1<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MIGRATE_POSITION_SCHEMA_V1))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        constraint = position_account.owner == payer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("migrate_position_schema_v1")]
+#[derive(Accounts)]
+pub struct MigratePositionSchemaV1Callback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MIGRATE_POSITION_SCHEMA_V1))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("migrate_position_schema_v2", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct MigratePositionSchemaV2<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MIGRATE_POSITION_SCHEMA_V2))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        constraint = position_account.owner == payer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("migrate_position_schema_v2")]
+#[derive(Accounts)]
+pub struct MigratePositionSchemaV2Callback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MIGRATE_POSITION_SCHEMA_V2))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("stress_test_portfolio", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, protocol_id: u8)]
+pub struct StressTestPortfolio<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_STRESS_TEST_PORTFOLIO))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[protocol_id]],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// CHECK: receives the surcharge; must be the protocol's treasury.
+    #[account(mut, address = protocol_config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+#[callback_accounts("stress_test_portfolio")]
+#[derive(Accounts)]
+pub struct StressTestPortfolioCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_STRESS_TEST_PORTFOLIO))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("commit_delayed_reveal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, reveal_id: [u8; 32])]
+pub struct CommitDelayedReveal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMMIT_DELAYED_REVEAL))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        init,
+        space = 8 + DelayedRevealAccount::INIT_SPACE,
+        payer = payer,
+        seeds = [DELAYED_REVEAL_SEED, reveal_id.as_ref()],
+        bump,
+    )]
+    pub delayed_reveal: Account<'info, DelayedRevealAccount>,
+}
+
+#[callback_accounts("commit_delayed_reveal")]
+#[derive(Accounts)]
+pub struct CommitDelayedRevealCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMMIT_DELAYED_REVEAL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub delayed_reveal: Account<'info, DelayedRevealAccount>,
+}
+
+#[queue_computation_accounts("finalize_delayed_reveal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, reveal_id: [u8; 32])]
+pub struct FinalizeDelayedReveal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_FINALIZE_DELAYED_REVEAL))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [DELAYED_REVEAL_SEED, reveal_id.as_ref()],
+        bump = delayed_reveal.bump,
+    )]
+    pub delayed_reveal: Account<'info, DelayedRevealAccount>,
+}
+
+#[callback_accounts("finalize_delayed_reveal")]
+#[derive(Accounts)]
+pub struct FinalizeDelayedRevealCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_FINALIZE_DELAYED_REVEAL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub delayed_reveal: Account<'info, DelayedRevealAccount>,
+}
+
+#[queue_computation_accounts("match_book", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, resting_order_id: [u8; 32])]
+pub struct MatchBook<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_BOOK))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [DARK_POOL_SEED, resting_order_id.as_ref()], bump = resting_order.bump)]
+    pub resting_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[DARK_POOL_CONFIG_ID]],
+        bump = dark_pool_config.bump,
+    )]
+    pub dark_pool_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init_if_needed,
+        space = 8 + MatchSequenceCounter::INIT_SPACE,
+        payer = payer,
+        seeds = [MATCH_SEQUENCE_SEED],
+        bump,
+    )]
+    pub match_sequence_counter: Account<'info, MatchSequenceCounter>,
+}
+
+#[callback_accounts("match_book")]
+#[derive(Accounts)]
+pub struct MatchBookCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_BOOK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut, seeds = [MATCH_SEQUENCE_SEED], bump = match_sequence_counter.bump)]
+    pub match_sequence_counter: Account<'info, MatchSequenceCounter>,
+}
+
+#[queue_computation_accounts("batch_weighted_risk_score", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, protocol_id: u8)]
+pub struct BatchWeightedRiskScore<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_WEIGHTED_RISK_SCORE))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[protocol_id]],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// CHECK: receives the surcharge; must be the protocol's treasury.
+    #[account(mut, address = protocol_config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+#[callback_accounts("batch_weighted_risk_score")]
+#[derive(Accounts)]
+pub struct BatchWeightedRiskScoreCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_WEIGHTED_RISK_SCORE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("compute_max_liquidatable", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ComputeMaxLiquidatable<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_MAX_LIQUIDATABLE))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("compute_max_liquidatable")]
+#[derive(Accounts)]
+pub struct ComputeMaxLiquidatableCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_MAX_LIQUIDATABLE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("compare_protocol_exposure", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CompareProtocolExposure<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPARE_PROTOCOL_EXPOSURE))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("compare_protocol_exposure")]
+#[derive(Accounts)]
+pub struct CompareProtocolExposureCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPARE_PROTOCOL_EXPOSURE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: [u8; 32])]
+pub struct RebindPositionOracle<'info> {
+    #[account(
+        constraint = authority.key() == position_account.owner
+            || authority.key() == protocol_state.admin @ ErrorCode::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(seeds = [PROTOCOL_STATE_SEED], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    pub oracle_feed: Account<'info, OracleFeed>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct RegisterOracleFeed<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        space = 8 + OracleFeed::INIT_SPACE,
+        payer = admin,
+        seeds = [ORACLE_FEED_SEED, mint.as_ref()],
+        bump,
+    )]
+    pub oracle_feed: Account<'info, OracleFeed>,
+    /// CHECK: owner and layout are validated in `register_oracle_feed`.
+    pub oracle: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: [u8; 32])]
+pub struct ProveOracleFresh<'info> {
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    /// CHECK: must be the feed bound to the position via `rebind_position_oracle`.
+    #[account(
+        constraint = position_account.oracle != Pubkey::default()
+            && oracle.key() == position_account.oracle @ ErrorCode::OracleNotBound,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[queue_computation_accounts("update_and_prove_health", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct UpdateAndProveHealth<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_UPDATE_AND_PROVE_HEALTH))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    /// CHECK: must be the feed bound to the position via `rebind_position_oracle`.
+    #[account(
+        constraint = position_account.oracle != Pubkey::default()
+            && oracle.key() == position_account.oracle @ ErrorCode::OracleNotBound,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+    #[account(constraint = oracle_feed.oracle == oracle.key() @ ErrorCode::OracleNotBound)]
+    pub oracle_feed: Account<'info, OracleFeed>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init,
+        space = 8 + BundleReceipt::INIT_SPACE,
+        payer = payer,
+        seeds = [BUNDLE_RECEIPT_SEED, computation_offset.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub bundle_receipt: Account<'info, BundleReceipt>,
+    #[account(mut, seeds = [FEE_REBATE_POOL_SEED], bump = fee_rebate_pool.bump)]
+    pub fee_rebate_pool: Account<'info, FeeRebatePool>,
+    #[account(mut, seeds = [REBATE_VAULT_SEED], bump = rebate_vault.bump)]
+    pub rebate_vault: Account<'info, RebateVault>,
+}
+
+#[callback_accounts("update_and_prove_health")]
+#[derive(Accounts)]
+pub struct UpdateAndProveHealthCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_UPDATE_AND_PROVE_HEALTH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut, close = payer, has_one = payer)]
+    pub bundle_receipt: Account<'info, BundleReceipt>,
+    #[account(mut, seeds = [FEE_REBATE_POOL_SEED], bump = fee_rebate_pool.bump)]
+    pub fee_rebate_pool: Account<'info, FeeRebatePool>,
+    /// CHECK: checked against the receipt.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[derive(Accounts)]
+pub struct InitFeeRebatePool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init,
+        space = 8 + FeeRebatePool::INIT_SPACE,
+        payer = admin,
+        seeds = [FEE_REBATE_POOL_SEED],
+        bump,
+    )]
+    pub fee_rebate_pool: Account<'info, FeeRebatePool>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("compute_age_distribution", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, protocol_id: u8)]
+pub struct ComputeAgeDistribution<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_AGE_DISTRIBUTION))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[protocol_id]],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("compute_age_distribution")]
+#[derive(Accounts)]
+pub struct ComputeAgeDistributionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_AGE_DISTRIBUTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("handle_expiring_order", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, order_id: [u8; 32])]
+pub struct HandleExpiringOrder<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_HANDLE_EXPIRING_ORDER))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [DARK_POOL_SEED, order_id.as_ref()], bump = order_account.bump)]
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, order_account.key().as_ref()],
+        bump,
+    )]
+    pub order_state: Box<Account<'info, EncryptedState>>,
+    #[account(seeds = [KEEPER_REGISTRY_SEED], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+    /// CHECK: must be the feed registered in `oracle_feed`.
+    #[account(address = oracle_feed.oracle @ ErrorCode::OracleNotBound)]
+    pub oracle: UncheckedAccount<'info>,
+    pub oracle_feed: Account<'info, OracleFeed>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[DARK_POOL_CONFIG_ID]],
+        bump = dark_pool_config.bump,
+    )]
+    pub dark_pool_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("handle_expiring_order")]
+#[derive(Accounts)]
+pub struct HandleExpiringOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_HANDLE_EXPIRING_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut)]
+    pub order_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("prove_unleveraged", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveUnleveraged<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_UNLEVERAGED))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[callback_accounts("prove_unleveraged")]
+#[derive(Accounts)]
+pub struct ProveUnleveragedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_UNLEVERAGED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("batch_init_encrypted_positions", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct BatchInitEncryptedPositions<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_INIT_POSITIONS))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Registered feed every position in the batch is bound to.
+    pub oracle_feed: Account<'info, OracleFeed>,
+    #[account(seeds = [PROTOCOL_STATE_SEED], bump = protocol_state.bump)]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init_if_needed,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        payer = payer,
+        seeds = [OWNER_REGISTRY_SEED, payer.key().as_ref()],
+        bump,
+    )]
+    pub owner_registry: Account<'info, OwnerRegistry>,
+}
+
+#[callback_accounts("batch_init_encrypted_positions")]
+#[derive(Accounts)]
+pub struct BatchInitEncryptedPositionsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_INIT_POSITIONS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_min_collateral", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveMinCollateral<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_MIN_COLLATERAL))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[callback_accounts("prove_min_collateral")]
+#[derive(Accounts)]
+pub struct ProveMinCollateralCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_MIN_COLLATERAL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[derive(Accounts)]
+#[instruction(protocol_id: u8)]
+pub struct PauseProtocol<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        mut,
+        seeds = [PROTOCOL_CONFIG_SEED, &[protocol_id]],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[queue_computation_accounts("prove_no_front_running", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveNoFrontRunning<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_NO_FRONT_RUNNING))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("prove_no_front_running")]
+#[derive(Accounts)]
+pub struct ProveNoFrontRunningCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_NO_FRONT_RUNNING))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[derive(Accounts)]
+pub struct InitRebateVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    #[account(
+        init,
+        space = 8 + RebateVault::INIT_SPACE,
+        payer = admin,
+        seeds = [REBATE_VAULT_SEED],
+        bump,
+    )]
+    pub rebate_vault: Account<'info, RebateVault>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositRebateFees<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    #[account(mut, seeds = [REBATE_VAULT_SEED], bump = rebate_vault.bump)]
+    pub rebate_vault: Account<'info, RebateVault>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimMakerRebate<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MAKER_VOLUME_SEED, maker.key().as_ref()],
+        bump = maker_volume.bump,
+        has_one = maker,
+    )]
+    pub maker_volume: Account<'info, MakerVolumeAccount>,
+    #[account(mut, seeds = [REBATE_VAULT_SEED], bump = rebate_vault.bump)]
+    pub rebate_vault: Account<'info, RebateVault>,
+}
+
+#[queue_computation_accounts("compute_risk_adjusted_score", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ComputeRiskAdjustedScore<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_RISK_ADJUSTED_SCORE))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("compute_risk_adjusted_score")]
+#[derive(Accounts)]
+pub struct ComputeRiskAdjustedScoreCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_RISK_ADJUSTED_SCORE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_uncorrelated", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveUncorrelated<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_UNCORRELATED))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[callback_accounts("prove_uncorrelated")]
+#[derive(Accounts)]
+pub struct ProveUncorrelatedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_UNCORRELATED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_arms_length", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveArmsLength<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_ARMS_LENGTH))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[DARK_POOL_CONFIG_ID]],
+        bump = dark_pool_config.bump,
+    )]
+    pub dark_pool_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("prove_arms_length")]
+#[derive(Accounts)]
+pub struct ProveArmsLengthCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_ARMS_LENGTH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("compute_fee_tier", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ComputeFeeTier<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_FEE_TIER))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("compute_fee_tier")]
+#[derive(Accounts)]
+pub struct ComputeFeeTierCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_FEE_TIER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[queue_computation_accounts("init_risk_cap_tracker", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitRiskCapTracker<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RISK_CAP_TRACKER))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        init,
+        space = 8 + RiskCapTrackerAccount::INIT_SPACE,
+        payer = payer,
+        seeds = [RISK_CAP_TRACKER_SEED, payer.key().as_ref()],
+        bump,
+    )]
+    pub risk_cap_tracker: Account<'info, RiskCapTrackerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, risk_cap_tracker.key().as_ref()],
+        bump,
+    )]
+    pub tracker_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("init_risk_cap_tracker")]
+#[derive(Accounts)]
+pub struct InitRiskCapTrackerCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RISK_CAP_TRACKER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub tracker_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("aggregate_portfolio_risk", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AggregatePortfolioRisk<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_AGGREGATE_PORTFOLIO_RISK))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [RISK_CAP_TRACKER_SEED, payer.key().as_ref()],
+        bump = risk_cap_tracker.bump,
+    )]
+    pub risk_cap_tracker: Account<'info, RiskCapTrackerAccount>,
+    #[account(
+        mut,
+        seeds = [ENCRYPTED_STATE_SEED, risk_cap_tracker.key().as_ref()],
+        bump = tracker_state.bump,
+    )]
+    pub tracker_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("aggregate_portfolio_risk")]
+#[derive(Accounts)]
+pub struct AggregatePortfolioRiskCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_AGGREGATE_PORTFOLIO_RISK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub tracker_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("prove_risk_cap_respected", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveRiskCapRespected<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_RISK_CAP_RESPECTED))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [RISK_CAP_TRACKER_SEED, risk_cap_tracker.owner.as_ref()], bump = risk_cap_tracker.bump)]
+    pub risk_cap_tracker: Account<'info, RiskCapTrackerAccount>,
+}
+
+#[callback_accounts("prove_risk_cap_respected")]
+#[derive(Accounts)]
+pub struct ProveRiskCapRespectedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_RISK_CAP_RESPECTED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("match_and_topup", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, buy_order_id: [u8; 32], sell_order_id: [u8; 32], position_id: [u8; 32])]
+pub struct MatchAndTopup<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_AND_TOPUP))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, seeds = [DARK_POOL_SEED, buy_order_id.as_ref()], bump = buy_order.bump)]
+    pub buy_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut, seeds = [DARK_POOL_SEED, sell_order_id.as_ref()], bump = sell_order.bump)]
+    pub sell_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        mut,
+        seeds = [TOPUP_AUTH_SEED, position_id.as_ref()],
+        bump = topup_authorization.bump,
+    )]
+    pub topup_authorization: Account<'info, TopupAuthorization>,
+    /// CHECK: the authorized source token account; SPL Token checks the delegation.
+    #[account(mut, address = topup_authorization.source)]
+    pub source: UncheckedAccount<'info>,
+    /// CHECK: the authorized collateral account topups are paid into.
+    #[account(mut, address = topup_authorization.destination)]
+    pub destination: UncheckedAccount<'info>,
+    /// CHECK: must be SPL Token.
+    #[account(address = SPL_TOKEN_PROGRAM_ID)]
+    pub token_program: UncheckedAccount<'info>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[DARK_POOL_CONFIG_ID]],
+        bump = dark_pool_config.bump,
+    )]
+    pub dark_pool_config: Account<'info, ProtocolConfig>,
+    #[account(
+        init_if_needed,
+        space = 8 + MatchSequenceCounter::INIT_SPACE,
+        payer = payer,
+        seeds = [MATCH_SEQUENCE_SEED],
+        bump,
+    )]
+    pub match_sequence_counter: Account<'info, MatchSequenceCounter>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, buy_order.key().as_ref()],
+        bump,
+    )]
+    pub buy_order_state: Box<Account<'info, EncryptedState>>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, sell_order.key().as_ref()],
+        bump,
+    )]
+    pub sell_order_state: Box<Account<'info, EncryptedState>>,
+    #[account(mut, seeds = [MAKER_VOLUME_SEED, maker_volume.maker.as_ref()], bump = maker_volume.bump)]
+    pub maker_volume: Account<'info, MakerVolumeAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, maker_volume.key().as_ref()],
+        bump,
+    )]
+    pub volume_state: Box<Account<'info, EncryptedState>>,
+    #[account(seeds = [REBATE_VAULT_SEED], bump = rebate_vault.bump)]
+    pub rebate_vault: Account<'info, RebateVault>,
+    #[account(seeds = [ORDER_ACTIVITY_SEED, buy_order.owner.as_ref()], bump = buy_activity.bump)]
+    pub buy_activity: Account<'info, OrderActivityAccount>,
+    #[account(seeds = [ORDER_ACTIVITY_SEED, sell_order.owner.as_ref()], bump = sell_activity.bump)]
+    pub sell_activity: Account<'info, OrderActivityAccount>,
+    /// CHECK: receives the taker's priority fee; must be the dark pool's treasury.
+    #[account(mut, address = dark_pool_config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: the buy order's duplicate-submission marker; it may already be
+    /// closed, so it's only read in the callback.
+    #[account(
+        mut,
+        seeds = [SUBMITTED_ORDER_SEED, buy_order.owner.as_ref(), buy_order.order_hash.as_ref()],
+        bump,
+    )]
+    pub buy_marker: UncheckedAccount<'info>,
+    /// CHECK: the sell order's duplicate-submission marker, as `buy_marker`.
+    #[account(
+        mut,
+        seeds = [SUBMITTED_ORDER_SEED, sell_order.owner.as_ref(), sell_order.order_hash.as_ref()],
+        bump,
+    )]
+    pub sell_marker: UncheckedAccount<'info>,
+}
+
+#[callback_accounts("match_and_topup")]
+#[derive(Accounts)]
+pub struct MatchAndTopupCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_AND_TOPUP))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut, seeds = [MATCH_SEQUENCE_SEED], bump = match_sequence_counter.bump)]
+    pub match_sequence_counter: Account<'info, MatchSequenceCounter>,
+    #[account(mut)]
+    pub buy_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut)]
+    pub sell_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    #[account(mut)]
+    pub topup_authorization: Account<'info, TopupAuthorization>,
+    /// CHECK: the source recorded on `topup_authorization` at queue time.
+    #[account(mut)]
+    pub source: UncheckedAccount<'info>,
+    /// CHECK: the destination recorded on `topup_authorization` at queue time.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    /// CHECK: SPL Token, checked again before the transfer.
+    pub token_program: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub buy_order_state: Box<Account<'info, EncryptedState>>,
+    #[account(mut)]
+    pub sell_order_state: Box<Account<'info, EncryptedState>>,
+    #[account(mut)]
+    pub maker_volume: Account<'info, MakerVolumeAccount>,
+    #[account(mut)]
+    pub volume_state: Box<Account<'info, EncryptedState>>,
+    #[account(mut, seeds = [REBATE_VAULT_SEED], bump = rebate_vault.bump)]
+    pub rebate_vault: Account<'info, RebateVault>,
+    #[account(mut)]
+    pub buy_activity: Account<'info, OrderActivityAccount>,
+    #[account(mut)]
+    pub sell_activity: Account<'info, OrderActivityAccount>,
+    /// CHECK: the dark pool treasury, checked at queue time.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: the buy order's marker, checked at queue time.
+    #[account(mut)]
+    pub buy_marker: UncheckedAccount<'info>,
+    /// CHECK: the sell order's marker, checked at queue time.
+    #[account(mut)]
+    pub sell_marker: UncheckedAccount<'info>,
+}
+
+#[queue_computation_accounts("prove_rewards_eligible", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveRewardsEligible<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_REWARDS_ELIGIBLE))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[callback_accounts("prove_rewards_eligible")]
+#[derive(Accounts)]
+pub struct ProveRewardsEligibleCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_REWARDS_ELIGIBLE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[derive(Accounts)]
+#[instruction(buy_order_id: [u8; 32], sell_order_id: [u8; 32])]
+pub struct SettleMatch<'info> {
+    pub settler: Signer<'info>,
+    #[account(mut, seeds = [DARK_POOL_SEED, buy_order_id.as_ref()], bump = buy_order.bump)]
+    pub buy_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(seeds = [DARK_POOL_SEED, sell_order_id.as_ref()], bump = sell_order.bump)]
+    pub sell_order: Account<'info, DarkPoolOrderAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[DARK_POOL_CONFIG_ID]],
+        bump = dark_pool_config.bump,
+    )]
+    pub dark_pool_config: Account<'info, ProtocolConfig>,
+    /// CHECK: must be the settlement program configured on the dark pool.
+    #[account(
+        executable,
+        constraint = dark_pool_config.settlement_enabled
+            && settlement_program.key() == dark_pool_config.settlement_program
+            @ ErrorCode::InvalidSettlementProgram,
+    )]
+    pub settlement_program: UncheckedAccount<'info>,
+    /// CHECK: data-less PDA that signs the settlement CPI.
+    #[account(seeds = [SETTLEMENT_AUTHORITY_SEED], bump)]
+    pub settlement_authority: UncheckedAccount<'info>,
+    /// Required when the buy order has a linked refresh position.
+    #[account(mut)]
+    pub buy_position: Option<Account<'info, PositionAccount>>,
+    /// Required when the sell order has a linked refresh position.
+    #[account(mut)]
+    pub sell_position: Option<Account<'info, PositionAccount>>,
+}
+
+#[queue_computation_accounts("prove_min_collateral_diversity", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveMinCollateralDiversity<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_MIN_COLLATERAL_DIVERSITY))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("prove_min_collateral_diversity")]
+#[derive(Accounts)]
+pub struct ProveMinCollateralDiversityCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_MIN_COLLATERAL_DIVERSITY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_min_healthy_count", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveMinHealthyCount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_MIN_HEALTHY_COUNT))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("prove_min_healthy_count")]
+#[derive(Accounts)]
+pub struct ProveMinHealthyCountCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_MIN_HEALTHY_COUNT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_position_parity", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_a_id: [u8; 32], position_b_id: [u8; 32])]
+pub struct ProvePositionParity<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_POSITION_PARITY))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_a_id.as_ref()], bump = position_a.bump)]
+    pub position_a: Account<'info, PositionAccount>,
+    #[account(seeds = [POSITION_PDA_SEED, position_b_id.as_ref()], bump = position_b.bump)]
+    pub position_b: Account<'info, PositionAccount>,
+}
+
+#[callback_accounts("prove_position_parity")]
+#[derive(Accounts)]
+pub struct ProvePositionParityCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_POSITION_PARITY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_route_optimal", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, intent_id: [u8; 32])]
+pub struct ProveRouteOptimal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_ROUTE_OPTIMAL))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [SWAP_INTENT_SEED, intent_id.as_ref()], bump = swap_intent.bump)]
+    pub swap_intent: Account<'info, SwapIntentAccount>,
+    /// CHECK: the registered feed for the token the intent sells.
+    #[account(address = from_oracle_feed.oracle @ ErrorCode::OracleNotBound)]
+    pub from_oracle: UncheckedAccount<'info>,
+    pub from_oracle_feed: Account<'info, OracleFeed>,
+    /// CHECK: the registered feed for the token the intent buys.
+    #[account(address = to_oracle_feed.oracle @ ErrorCode::OracleNotBound)]
+    pub to_oracle: UncheckedAccount<'info>,
+    pub to_oracle_feed: Account<'info, OracleFeed>,
+}
+
+#[callback_accounts("prove_route_optimal")]
+#[derive(Accounts)]
+pub struct ProveRouteOptimalCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_ROUTE_OPTIMAL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("estimate_cascade_risk", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct EstimateCascadeRisk<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ESTIMATE_CASCADE_RISK))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("estimate_cascade_risk")]
+#[derive(Accounts)]
+pub struct EstimateCascadeRiskCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ESTIMATE_CASCADE_RISK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("init_order_activity", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct InitOrderActivity<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ORDER_ACTIVITY))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        init,
+        space = 8 + OrderActivityAccount::INIT_SPACE,
+        payer = payer,
+        seeds = [ORDER_ACTIVITY_SEED, payer.key().as_ref()],
+        bump,
+    )]
+    pub order_activity: Account<'info, OrderActivityAccount>,
+    #[account(
+        init,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, order_activity.key().as_ref()],
+        bump,
+    )]
+    pub activity_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("init_order_activity")]
+#[derive(Accounts)]
+pub struct InitOrderActivityCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ORDER_ACTIVITY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub activity_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("record_order_activity", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RecordOrderActivity<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RECORD_ORDER_ACTIVITY))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        mut,
+        seeds = [ORDER_ACTIVITY_SEED, order_activity.owner.as_ref()],
+        bump = order_activity.bump,
+    )]
+    pub order_activity: Account<'info, OrderActivityAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, order_activity.key().as_ref()],
+        bump,
+    )]
+    pub activity_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[callback_accounts("record_order_activity")]
+#[derive(Accounts)]
+pub struct RecordOrderActivityCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RECORD_ORDER_ACTIVITY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub order_activity: Account<'info, OrderActivityAccount>,
+    #[account(mut)]
+    pub activity_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[queue_computation_accounts("prove_not_spoofing", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveNotSpoofing<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_NOT_SPOOFING))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [ORDER_ACTIVITY_SEED, order_activity.owner.as_ref()],
+        bump = order_activity.bump,
+    )]
+    pub order_activity: Account<'info, OrderActivityAccount>,
+}
+
+#[callback_accounts("prove_not_spoofing")]
+#[derive(Accounts)]
+pub struct ProveNotSpoofingCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_NOT_SPOOFING))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_floor_held", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveFloorHeld<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_FLOOR_HELD))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+}
 
-#[event]
-pub struct HealthFactorUpdated {
-    pub timestamp: i64,
+#[callback_accounts("prove_floor_held")]
+#[derive(Accounts)]
+pub struct ProveFloorHeldCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_FLOOR_HELD))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_swap_low_impact", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveSwapLowImpact<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_SWAP_LOW_IMPACT))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("prove_swap_low_impact")]
+#[derive(Accounts)]
+pub struct ProveSwapLowImpactCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_SWAP_LOW_IMPACT))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_top_tier", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveTopTier<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_TOP_TIER))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[callback_accounts("prove_top_tier")]
+#[derive(Accounts)]
+pub struct ProveTopTierCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_TOP_TIER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("apply_auto_deleverage", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ExecuteAutoDeleverage<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_AUTO_DELEVERAGE))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [AUTO_DELEVERAGE_SEED, position_id.as_ref()],
+        bump = deleverage_config.bump,
+    )]
+    pub deleverage_config: Account<'info, AutoDeleverageConfig>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    /// CHECK: the configured source token account; SPL Token checks the delegation.
+    #[account(mut, address = deleverage_config.source)]
+    pub source: UncheckedAccount<'info>,
+    /// CHECK: the configured debt account repayments are paid into.
+    #[account(mut, address = deleverage_config.repayment)]
+    pub repayment: UncheckedAccount<'info>,
+    /// CHECK: must be SPL Token.
+    #[account(address = SPL_TOKEN_PROGRAM_ID)]
+    pub token_program: UncheckedAccount<'info>,
+    #[account(seeds = [KEEPER_REGISTRY_SEED], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[callback_accounts("apply_auto_deleverage")]
+#[derive(Accounts)]
+pub struct ExecuteAutoDeleverageCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_AUTO_DELEVERAGE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    pub deleverage_config: Account<'info, AutoDeleverageConfig>,
+    /// CHECK: the source recorded on `deleverage_config` at queue time.
+    #[account(mut)]
+    pub source: UncheckedAccount<'info>,
+    /// CHECK: the repayment account recorded on `deleverage_config` at queue time.
+    #[account(mut)]
+    pub repayment: UncheckedAccount<'info>,
+    /// CHECK: SPL Token, checked again before the transfer.
+    pub token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: [u8; 32])]
+pub struct ConfigureAutoDeleverage<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + AutoDeleverageConfig::INIT_SPACE,
+        payer = owner,
+        seeds = [AUTO_DELEVERAGE_SEED, position_id.as_ref()],
+        bump,
+    )]
+    pub deleverage_config: Account<'info, AutoDeleverageConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(kind: ComputationKind, target: [u8; 32])]
+pub struct RetryComputation<'info> {
+    pub keeper: Signer<'info>,
+    #[account(seeds = [KEEPER_REGISTRY_SEED], bump = keeper_registry.bump)]
+    pub keeper_registry: Account<'info, KeeperRegistry>,
+    #[account(
+        mut,
+        seeds = [COMPUTATION_STATUS_SEED, &[kind as u8], target.as_ref()],
+        bump = computation_status.bump,
+    )]
+    pub computation_status: Account<'info, ComputationStatus>,
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct ProveMinPositionCount<'info> {
+    #[account(seeds = [OWNER_REGISTRY_SEED, owner.as_ref()], bump = owner_registry.bump)]
+    pub owner_registry: Account<'info, OwnerRegistry>,
+}
+
+#[derive(Accounts)]
+#[instruction(position_id: [u8; 32])]
+pub struct SetRefreshHealthOnSettle<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: [u8; 32], position_id: [u8; 32])]
+pub struct LinkRefreshPosition<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [DARK_POOL_SEED, order_id.as_ref()],
+        bump = order_account.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
+    #[account(
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        constraint = position_account.refresh_health_on_settle @ ErrorCode::RefreshNotEnabled,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+}
+
+#[queue_computation_accounts("prove_terms_match", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveTermsMatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_TERMS_MATCH))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("prove_terms_match")]
+#[derive(Accounts)]
+pub struct ProveTermsMatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_TERMS_MATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_liquidation_cooldown_passed", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveLiquidationCooldownPassed<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_LIQUIDATION_COOLDOWN))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
 }
 
-#[event]
-pub struct HealthThresholdProved {
-    pub is_healthy: bool,
-    pub timestamp: i64,
+#[callback_accounts("prove_liquidation_cooldown_passed")]
+#[derive(Accounts)]
+pub struct ProveLiquidationCooldownPassedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_LIQUIDATION_COOLDOWN))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[event]
-pub struct DarkPoolOrderCreated {
-    pub timestamp: i64,
+#[queue_computation_accounts("prove_health_with_funding", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveHealthWithFunding<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_HEALTH_WITH_FUNDING))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
 }
 
-#[event]
-pub struct DarkPoolOrdersMatched {
-    pub is_matched: bool,
-    pub timestamp: i64,
+#[callback_accounts("prove_health_with_funding")]
+#[derive(Accounts)]
+pub struct ProveHealthWithFundingCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_HEALTH_WITH_FUNDING))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[event]
-pub struct PrivateSwapExecuted {
-    pub success: bool,
-    pub timestamp: i64,
+#[queue_computation_accounts("compute_book_imbalance", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ComputeBookImbalance<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BOOK_IMBALANCE))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
 }
 
-#[event]
-pub struct BatchHealthChecked {
-    pub at_risk_count: u8,
-    pub timestamp: i64,
+#[callback_accounts("compute_book_imbalance")]
+#[derive(Accounts)]
+pub struct ComputeBookImbalanceCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BOOK_IMBALANCE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[event]
-pub struct LiquidationRiskCalculated {
-    pub risk_level: u8,
-    pub timestamp: i64,
+#[queue_computation_accounts("compute_slippage_from_confidence", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ComputeSlippageFromConfidence<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONFIDENCE_SLIPPAGE))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// CHECK: parsed as a Pyth feed; other owners are rejected when read.
+    pub oracle: UncheckedAccount<'info>,
 }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Computation was aborted")]
-    AbortedComputation,
-    #[msg("Cluster not configured")]
-    ClusterNotSet,
-    #[msg("Invalid position state")]
-    InvalidPositionState,
-    #[msg("Order expired")]
-    OrderExpired,
-    #[msg("Insufficient liquidity")]
-    InsufficientLiquidity,
-    #[msg("Slippage exceeded")]
-    SlippageExceeded,
+#[callback_accounts("compute_slippage_from_confidence")]
+#[derive(Accounts)]
+pub struct ComputeSlippageFromConfidenceCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CONFIDENCE_SLIPPAGE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[init_computation_definition_accounts("init_encrypted_position", payer)]
+#[queue_computation_accounts("prove_under_credit_line", payer)]
 #[derive(Accounts)]
-pub struct InitPositionCompDef<'info> {
+#[instruction(computation_offset: u64)]
+pub struct ProveUnderCreditLine<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_UNDER_CREDIT_LINE))]
     pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("prove_under_credit_line")]
+#[derive(Accounts)]
+pub struct ProveUnderCreditLineCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_UNDER_CREDIT_LINE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_realized_volume_tier", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ProveRealizedVolumeTier<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REALIZED_VOLUME_TIER))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [MAKER_VOLUME_SEED, payer.key().as_ref()],
+        bump = maker_volume.bump,
+    )]
+    pub maker_volume: Account<'info, MakerVolumeAccount>,
+}
+
+#[callback_accounts("prove_realized_volume_tier")]
+#[derive(Accounts)]
+pub struct ProveRealizedVolumeTierCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REALIZED_VOLUME_TIER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[init_computation_definition_accounts("prove_health_threshold", payer)]
+#[queue_computation_accounts("prove_clean_source", payer)]
 #[derive(Accounts)]
-pub struct InitHealthCompDef<'info> {
+#[instruction(computation_offset: u64)]
+pub struct ProveCleanSource<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_CLEAN_SOURCE))]
     pub comp_def_account: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
 }
 
-#[init_computation_definition_accounts("init_dark_pool_order", payer)]
+#[callback_accounts("prove_clean_source")]
 #[derive(Accounts)]
-pub struct InitDarkPoolCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
-    pub comp_def_account: UncheckedAccount<'info>,
+pub struct ProveCleanSourceCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_CLEAN_SOURCE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[init_computation_definition_accounts("execute_private_swap", payer)]
+#[queue_computation_accounts("calculate_liquidation_risk_weighted", payer)]
 #[derive(Accounts)]
-pub struct InitSwapCompDef<'info> {
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct CalculateLiquidationRiskWeighted<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(mut, address = derive_mempool_pda!())]
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(mut, address = derive_execpool_pda!())]
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(mut, address = derive_comp_pda!(computation_offset))]
+    pub computation_account: UncheckedAccount<'info>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_LIQUIDATION_RISK_WEIGHTED))]
     pub comp_def_account: UncheckedAccount<'info>,
-    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// CHECK: must be the feed bound to the position via `rebind_position_oracle`.
+    #[account(
+        constraint = position_account.oracle != Pubkey::default()
+            && oracle.key() == position_account.oracle @ ErrorCode::OracleNotBound,
+    )]
+    pub oracle: UncheckedAccount<'info>,
 }
 
-#[queue_computation_accounts("init_encrypted_position", payer)]
+#[callback_accounts("calculate_liquidation_risk_weighted")]
+#[derive(Accounts)]
+pub struct CalculateLiquidationRiskWeightedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_LIQUIDATION_RISK_WEIGHTED))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+}
+
+#[queue_computation_accounts("prove_portfolio_neutral", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct InitEncryptedPosition<'info> {
+pub struct ProvePortfolioNeutral<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -495,6 +16038,14 @@ pub struct InitEncryptedPosition<'info> {
         address = derive_sign_pda!(),
     )]
     pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
     #[account(mut, address = derive_mempool_pda!())]
@@ -503,8 +16054,9 @@ pub struct InitEncryptedPosition<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_PORTFOLIO_NEUTRAL))]
+    pub comp_def_account: UncheckedAccount<'info>,
     #[account(mut, address = derive_cluster_pda!(mxe_account))]
     pub cluster_account: Account<'info, Cluster>,
     #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
@@ -515,20 +16067,22 @@ pub struct InitEncryptedPosition<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[callback_accounts("init_encrypted_position")]
+#[callback_accounts("prove_portfolio_neutral")]
 #[derive(Accounts)]
-pub struct InitEncryptedPositionCallback<'info> {
+pub struct ProvePortfolioNeutralCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_POSITION))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_PORTFOLIO_NEUTRAL))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[queue_computation_accounts("update_health_factor", payer)]
+#[queue_computation_accounts("prove_debt_diversified", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct UpdateHealthFactor<'info> {
+pub struct ProveDebtDiversified<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -540,6 +16094,14 @@ pub struct UpdateHealthFactor<'info> {
         address = derive_sign_pda!(),
     )]
     pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
     #[account(mut, address = derive_mempool_pda!())]
@@ -548,8 +16110,9 @@ pub struct UpdateHealthFactor<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_UPDATE_HEALTH))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_DEBT_DIVERSIFIED))]
+    pub comp_def_account: UncheckedAccount<'info>,
     #[account(mut, address = derive_cluster_pda!(mxe_account))]
     pub cluster_account: Account<'info, Cluster>,
     #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
@@ -560,21 +16123,22 @@ pub struct UpdateHealthFactor<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[callback_accounts("update_health_factor")]
+#[callback_accounts("prove_debt_diversified")]
 #[derive(Accounts)]
-pub struct UpdateHealthFactorCallback<'info> {
+pub struct ProveDebtDiversifiedCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_UPDATE_HEALTH))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_DEBT_DIVERSIFIED))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-
-#[queue_computation_accounts("prove_health_threshold", payer)]
+#[queue_computation_accounts("compute_margin_call_buffer", payer)]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct ProveHealthThreshold<'info> {
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ComputeMarginCallBuffer<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -586,6 +16150,14 @@ pub struct ProveHealthThreshold<'info> {
         address = derive_sign_pda!(),
     )]
     pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
     #[account(mut, address = derive_mempool_pda!())]
@@ -594,8 +16166,9 @@ pub struct ProveHealthThreshold<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_HEALTH))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_MARGIN_CALL_BUFFER))]
+    pub comp_def_account: UncheckedAccount<'info>,
     #[account(mut, address = derive_cluster_pda!(mxe_account))]
     pub cluster_account: Account<'info, Cluster>,
     #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
@@ -604,22 +16177,38 @@ pub struct ProveHealthThreshold<'info> {
     pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// CHECK: must be the feed bound to the position via `rebind_position_oracle`.
+    #[account(
+        constraint = position_account.oracle != Pubkey::default()
+            && oracle.key() == position_account.oracle @ ErrorCode::OracleNotBound,
+    )]
+    pub oracle: UncheckedAccount<'info>,
 }
 
-#[callback_accounts("prove_health_threshold")]
+#[callback_accounts("compute_margin_call_buffer")]
 #[derive(Accounts)]
-pub struct ProveHealthThresholdCallback<'info> {
+pub struct ComputeMarginCallBufferCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_HEALTH))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPUTE_MARGIN_CALL_BUFFER))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[queue_computation_accounts("init_dark_pool_order", payer)]
+#[queue_computation_accounts("prove_not_round_trip", payer)]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct InitDarkPoolOrder<'info> {
+#[instruction(computation_offset: u64, intent_a_id: [u8; 32], intent_b_id: [u8; 32])]
+pub struct ProveNotRoundTrip<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -631,6 +16220,14 @@ pub struct InitDarkPoolOrder<'info> {
         address = derive_sign_pda!(),
     )]
     pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
     #[account(mut, address = derive_mempool_pda!())]
@@ -639,8 +16236,9 @@ pub struct InitDarkPoolOrder<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_DARK_ORDER))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_NOT_ROUND_TRIP))]
+    pub comp_def_account: UncheckedAccount<'info>,
     #[account(mut, address = derive_cluster_pda!(mxe_account))]
     pub cluster_account: Account<'info, Cluster>,
     #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
@@ -649,22 +16247,28 @@ pub struct InitDarkPoolOrder<'info> {
     pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [SWAP_INTENT_SEED, intent_a_id.as_ref()], bump = swap_intent_a.bump)]
+    pub swap_intent_a: Account<'info, SwapIntentAccount>,
+    #[account(seeds = [SWAP_INTENT_SEED, intent_b_id.as_ref()], bump = swap_intent_b.bump)]
+    pub swap_intent_b: Account<'info, SwapIntentAccount>,
 }
 
-#[callback_accounts("init_dark_pool_order")]
+#[callback_accounts("prove_not_round_trip")]
 #[derive(Accounts)]
-pub struct InitDarkPoolOrderCallback<'info> {
+pub struct ProveNotRoundTripCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_DARK_ORDER))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_NOT_ROUND_TRIP))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[queue_computation_accounts("match_dark_pool_orders", payer)]
+#[queue_computation_accounts("prove_smoothed_health_threshold", payer)]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct MatchDarkPoolOrders<'info> {
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProveSmoothedHealthThreshold<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -676,6 +16280,14 @@ pub struct MatchDarkPoolOrders<'info> {
         address = derive_sign_pda!(),
     )]
     pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
     #[account(mut, address = derive_mempool_pda!())]
@@ -684,8 +16296,9 @@ pub struct MatchDarkPoolOrders<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_ORDERS))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_SMOOTHED_HEALTH_THRESHOLD))]
+    pub comp_def_account: UncheckedAccount<'info>,
     #[account(mut, address = derive_cluster_pda!(mxe_account))]
     pub cluster_account: Account<'info, Cluster>,
     #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
@@ -694,22 +16307,38 @@ pub struct MatchDarkPoolOrders<'info> {
     pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// CHECK: must be the feed bound to the position via `rebind_position_oracle`.
+    #[account(
+        constraint = position_account.oracle != Pubkey::default()
+            && oracle.key() == position_account.oracle @ ErrorCode::OracleNotBound,
+    )]
+    pub oracle: UncheckedAccount<'info>,
 }
 
-#[callback_accounts("match_dark_pool_orders")]
+#[callback_accounts("prove_smoothed_health_threshold")]
 #[derive(Accounts)]
-pub struct MatchDarkPoolOrdersCallback<'info> {
+pub struct ProveSmoothedHealthThresholdCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_ORDERS))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_SMOOTHED_HEALTH_THRESHOLD))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[queue_computation_accounts("execute_private_swap", payer)]
+#[queue_computation_accounts("prove_position_stale", payer)]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct ExecutePrivateSwap<'info> {
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct ProvePositionStale<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -721,6 +16350,14 @@ pub struct ExecutePrivateSwap<'info> {
         address = derive_sign_pda!(),
     )]
     pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
     #[account(mut, address = derive_mempool_pda!())]
@@ -729,8 +16366,9 @@ pub struct ExecutePrivateSwap<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PRIVATE_SWAP))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_POSITION_STALE))]
+    pub comp_def_account: UncheckedAccount<'info>,
     #[account(mut, address = derive_cluster_pda!(mxe_account))]
     pub cluster_account: Account<'info, Cluster>,
     #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
@@ -739,22 +16377,26 @@ pub struct ExecutePrivateSwap<'info> {
     pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [POSITION_PDA_SEED, position_id.as_ref()], bump = position_account.bump)]
+    pub position_account: Account<'info, PositionAccount>,
 }
 
-#[callback_accounts("execute_private_swap")]
+#[callback_accounts("prove_position_stale")]
 #[derive(Accounts)]
-pub struct ExecutePrivateSwapCallback<'info> {
+pub struct ProvePositionStaleCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PRIVATE_SWAP))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PROVE_POSITION_STALE))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
 }
 
-#[queue_computation_accounts("batch_health_check", payer)]
+#[queue_computation_accounts("check_conditional_trigger", payer)]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct BatchHealthCheck<'info> {
+#[instruction(computation_offset: u64, order_id: [u8; 32], position_id: [u8; 32])]
+pub struct CheckConditionalTrigger<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -766,6 +16408,14 @@ pub struct BatchHealthCheck<'info> {
         address = derive_sign_pda!(),
     )]
     pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
     #[account(mut, address = derive_mempool_pda!())]
@@ -774,8 +16424,9 @@ pub struct BatchHealthCheck<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_HEALTH))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_CONDITIONAL_TRIGGER))]
+    pub comp_def_account: UncheckedAccount<'info>,
     #[account(mut, address = derive_cluster_pda!(mxe_account))]
     pub cluster_account: Account<'info, Cluster>,
     #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
@@ -784,22 +16435,34 @@ pub struct BatchHealthCheck<'info> {
     pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [DARK_POOL_SEED, order_id.as_ref()], bump = order_account.bump)]
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
+    #[account(
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        constraint = position_account.owner == order_account.owner @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
 }
 
-#[callback_accounts("batch_health_check")]
+#[callback_accounts("check_conditional_trigger")]
 #[derive(Accounts)]
-pub struct BatchHealthCheckCallback<'info> {
+pub struct CheckConditionalTriggerCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_HEALTH))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_CONDITIONAL_TRIGGER))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub order_account: Account<'info, DarkPoolOrderAccount>,
 }
 
-#[queue_computation_accounts("calculate_liquidation_risk", payer)]
+#[queue_computation_accounts("partial_close_position", payer)]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct CalculateLiquidationRisk<'info> {
+#[instruction(computation_offset: u64, position_id: [u8; 32])]
+pub struct PartialClosePosition<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -811,6 +16474,14 @@ pub struct CalculateLiquidationRisk<'info> {
         address = derive_sign_pda!(),
     )]
     pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + PendingComputations::INIT_SPACE,
+        payer = payer,
+        seeds = [PENDING_COMPUTATIONS_SEED],
+        bump,
+    )]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
     #[account(address = derive_mxe_pda!())]
     pub mxe_account: Account<'info, MXEAccount>,
     #[account(mut, address = derive_mempool_pda!())]
@@ -819,8 +16490,9 @@ pub struct CalculateLiquidationRisk<'info> {
     pub executing_pool: UncheckedAccount<'info>,
     #[account(mut, address = derive_comp_pda!(computation_offset))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_LIQUIDATION_RISK))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    /// CHECK: deserialized by `require_comp_def_ready` before queueing.
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PARTIAL_CLOSE_POSITION))]
+    pub comp_def_account: UncheckedAccount<'info>,
     #[account(mut, address = derive_cluster_pda!(mxe_account))]
     pub cluster_account: Account<'info, Cluster>,
     #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
@@ -829,14 +16501,207 @@ pub struct CalculateLiquidationRisk<'info> {
     pub clock_account: Account<'info, ClockAccount>,
     pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        seeds = [POSITION_PDA_SEED, position_id.as_ref()],
+        bump = position_account.bump,
+        constraint = position_account.owner == payer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub position_account: Account<'info, PositionAccount>,
+    #[account(
+        init_if_needed,
+        space = 8 + EncryptedState::INIT_SPACE,
+        payer = payer,
+        seeds = [ENCRYPTED_STATE_SEED, position_account.key().as_ref()],
+        bump,
+    )]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+    #[account(
+        seeds = [PROTOCOL_CONFIG_SEED, &[position_account.protocol_id]],
+        bump = protocol_config.bump,
+        constraint = !protocol_config.protocol_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
-#[callback_accounts("calculate_liquidation_risk")]
+#[callback_accounts("partial_close_position")]
 #[derive(Accounts)]
-pub struct CalculateLiquidationRiskCallback<'info> {
+pub struct PartialClosePositionCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_LIQUIDATION_RISK))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_PARTIAL_CLOSE_POSITION))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut, seeds = [PENDING_COMPUTATIONS_SEED], bump = pending_computations.bump)]
+    pub pending_computations: Box<Account<'info, PendingComputations>>,
+    #[account(mut)]
+    pub position_state: Box<Account<'info, EncryptedState>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collateral_legs_reject_duplicate_mints() {
+        let usdc = [1u8; 32];
+        let sol = [2u8; 32];
+        assert_eq!(
+            collateral_leg_mints(&[usdc, sol, usdc]).unwrap_err(),
+            ErrorCode::DuplicateCollateralMint.into()
+        );
+
+        let legs = collateral_leg_mints(&[usdc, sol]).unwrap();
+        assert_eq!(legs, [usdc, sol, [0u8; 32], [0u8; 32]]);
+    }
+
+    #[test]
+    fn collateral_legs_reject_more_than_max() {
+        let mints: Vec<[u8; 32]> = (1..=MAX_COLLATERAL_LEGS as u8 + 1).map(|i| [i; 32]).collect();
+        assert_eq!(
+            collateral_leg_mints(&mints).unwrap_err(),
+            ErrorCode::TooManyLegs.into()
+        );
+        assert!(collateral_leg_mints(&mints[..MAX_COLLATERAL_LEGS]).is_ok());
+    }
+
+    #[test]
+    fn sign_pda_stays_open_until_pending_computations_finish() {
+        let mut pending = PendingComputations { count: 0, bump: 0 };
+        pending.start(255);
+        pending.start(255);
+        assert_eq!(
+            pending.require_idle().unwrap_err(),
+            ErrorCode::ComputationsPending.into()
+        );
+
+        // An aborted computation's callback succeeds, so its finish sticks.
+        assert!(reject_callback(ErrorCode::AbortedComputation).is_ok());
+        pending.finish();
+        assert!(pending.require_idle().is_err());
+
+        pending.finish();
+        assert!(pending.require_idle().is_ok());
+    }
+
+    #[test]
+    fn unbacked_rebate_stays_owed_until_fees_arrive() {
+        let mut vault = RebateVault { collected_fees: 100, committed_rebates: 60, bump: 0 };
+        let mut maker = MakerVolumeAccount {
+            maker: Pubkey::default(),
+            match_count: 0,
+            claimable_rebate: 0,
+            owed_rebate: 0,
+            window_started_at: 0,
+            pending_window_start: 0,
+            bump: 0,
+        };
+
+        maker.credit_rebate(&mut vault, 50);
+        assert_eq!((maker.claimable_rebate, maker.owed_rebate), (40, 10));
+        assert_eq!(vault.committed_rebates, 100);
+
+        vault.collected_fees += 25;
+        maker.credit_rebate(&mut vault, 0);
+        assert_eq!((maker.claimable_rebate, maker.owed_rebate), (50, 0));
+        assert_eq!(vault.committed_rebates, 110);
+    }
+
+    #[test]
+    fn first_retry_waits_out_the_backoff_from_queue_time() {
+        let mut status = ComputationStatus {
+            kind: ComputationKind::UpdateHealth,
+            target: [0u8; 32],
+            retry_count: 0,
+            next_retry_after: 0,
+            permanently_failed: false,
+            in_flight: false,
+            retry_granted: false,
+            bump: 0,
+        };
+
+        status.begin(ComputationKind::UpdateHealth, [1u8; 32], 255, 1_000).unwrap();
+        assert_eq!(status.next_retry_after, 1_000 + RETRY_BASE_BACKOFF_SECONDS);
+
+        // Re-queuing the stuck attempt needs a granted retry and keeps its backoff.
+        assert_eq!(
+            status.begin(ComputationKind::UpdateHealth, [1u8; 32], 255, 1_001).unwrap_err(),
+            ErrorCode::RetryNotGranted.into()
+        );
+        status.retry_granted = true;
+        status.next_retry_after = 2_000;
+        status.begin(ComputationKind::UpdateHealth, [1u8; 32], 255, 1_500).unwrap();
+        assert_eq!(status.next_retry_after, 2_000);
+
+        assert!(ComputationKind::UpdateHealth.is_retryable());
+        assert!(!ComputationKind::MatchOrders.is_retryable());
+    }
+
+    #[test]
+    fn batch_flags_the_unregistered_protocol() {
+        let program_id = crate::ID;
+        let config_key = |protocol: u8| {
+            Pubkey::find_program_address(&[PROTOCOL_CONFIG_SEED, &[protocol]], &program_id).0
+        };
+        let config_data = |paused: bool| {
+            let mut data = ProtocolConfig::DISCRIMINATOR.to_vec();
+            data.resize(8 + ProtocolConfig::INIT_SPACE, 0);
+            let mut config = ProtocolConfig::try_deserialize(&mut &data[..]).unwrap();
+            config.protocol_paused = paused;
+            config.try_serialize(&mut &mut data[..]).unwrap();
+            data
+        };
+        let flagged = |protocols: &[u8], configs: &[AccountInfo]| {
+            unusable_protocol_index(&program_id, protocols, configs.iter())
+                .map(|(i, error)| (i, u32::from(error)))
+        };
+
+        // Protocol 1 has an initialized config, protocol 2's is paused and
+        // protocol 7's was never created.
+        let (registered_key, paused_key, unregistered_key) = (config_key(1), config_key(2), config_key(7));
+        let (mut registered_lamports, mut paused_lamports, mut unregistered_lamports) = (1, 1, 0);
+        let (mut registered_data, mut paused_data, mut unregistered_data) =
+            (config_data(false), config_data(true), Vec::new());
+        let registered = AccountInfo::new(
+            &registered_key,
+            false,
+            false,
+            &mut registered_lamports,
+            &mut registered_data,
+            &program_id,
+            false,
+            0,
+        );
+        let paused = AccountInfo::new(
+            &paused_key,
+            false,
+            false,
+            &mut paused_lamports,
+            &mut paused_data,
+            &program_id,
+            false,
+            0,
+        );
+        let unregistered = AccountInfo::new(
+            &unregistered_key,
+            false,
+            false,
+            &mut unregistered_lamports,
+            &mut unregistered_data,
+            &program_id,
+            false,
+            0,
+        );
+
+        let unregistered_code = u32::from(ErrorCode::UnregisteredProtocol);
+        let configs = [registered.clone(), unregistered];
+        assert_eq!(flagged(&[1, 7], &configs), Some((1, unregistered_code)));
+        assert_eq!(flagged(&[1], &configs), None);
+        // A registered config passed for the wrong protocol doesn't count.
+        assert_eq!(flagged(&[7], &[registered.clone()]), Some((0, unregistered_code)));
+        // Nor does one whose protocol is paused.
+        assert_eq!(
+            flagged(&[1, 2], &[registered, paused]),
+            Some((1, u32::from(ErrorCode::ProtocolPaused)))
+        );
+    }
 }